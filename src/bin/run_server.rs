@@ -6,6 +6,20 @@ use tower_http::{cors, trace};
 
 use text_translator_rust::config::ServiceConfig;
 use text_translator_rust::logger;
+use text_translator_rust::modules::back_translation::MonitorSampler;
+use text_translator_rust::modules::confidence::ConfidenceScorer;
+use text_translator_rust::modules::consistency::ConsistencyChecker;
+use text_translator_rust::modules::context::SessionStore;
+use text_translator_rust::modules::documents::DocumentRegistry;
+use text_translator_rust::modules::formality::FormalityChecker;
+use text_translator_rust::modules::glossary::GlossaryStore;
+use text_translator_rust::modules::masking::Masker;
+use text_translator_rust::modules::pass_through::PassThroughEngine;
+use text_translator_rust::modules::pipeline::{TranslationPipeline, TranslationPipelineBuilder};
+use text_translator_rust::modules::profiles::ProfileRegistry;
+use text_translator_rust::modules::sanitizer::OutputSanitizer;
+use text_translator_rust::modules::templates::TemplateStore;
+use text_translator_rust::modules::validation::OutputValidator;
 use text_translator_rust::server::AppState;
 
 #[tokio::main(worker_threads = 8)]
@@ -16,8 +30,52 @@ async fn main() -> anyhow::Result<()> {
     let llm_client_config = config.llm_client();
     let mode = config.server().llm_mode();
     let llm_client = mode.create_client(llm_client_config).await?;
+    let fallback_client = mode.create_fallback_client(llm_client_config).await?;
 
-    let server_app = AppState::new(llm_client, Arc::new(config.clone()));
+    let profiles = ProfileRegistry::load(config.profiles())?;
+    let templates = Arc::new(TemplateStore::load(config.templates())?);
+    templates.watch();
+    let glossary = GlossaryStore::load(config.glossary())?;
+    let masker = Masker::new(config.masking())?;
+    let pass_through = PassThroughEngine::new(config.pass_through())?;
+    let sanitizer = OutputSanitizer::new(config.sanitizer())?;
+    let validator = OutputValidator::new(config.validation())?;
+    let consistency = ConsistencyChecker::new(config.consistency())?;
+    let formality = FormalityChecker::new(config.formality())?;
+    let sessions = SessionStore::new(config.context());
+    let confidence = ConfidenceScorer::new(config.confidence());
+    let documents = DocumentRegistry::new(config.documents());
+    let monitor = MonitorSampler::new(config.back_translation());
+    let (monitor, monitor_receiver) = match monitor {
+        Some((sampler, receiver)) => (Some(Arc::new(sampler)), Some(receiver)),
+        None => (None, None),
+    };
+
+    let pipeline = TranslationPipelineBuilder::default()
+        .llm_client(llm_client)
+        .fallback_client(fallback_client)
+        .profiles(Arc::new(profiles))
+        .templates(templates)
+        .glossary(Arc::new(glossary))
+        .masker(Arc::new(masker))
+        .pass_through(Arc::new(pass_through))
+        .sanitizer(Arc::new(sanitizer))
+        .validator(Arc::new(validator))
+        .consistency(Arc::new(consistency))
+        .formality(Arc::new(formality))
+        .sessions(Arc::new(sessions))
+        .confidence(Arc::new(confidence))
+        .documents(Arc::new(documents))
+        .monitor(monitor)
+        .build()?;
+    let pipeline = Arc::new(pipeline);
+    if let Some(receiver) = monitor_receiver {
+        tokio::spawn(TranslationPipeline::run_back_translation_monitor(
+            Arc::downgrade(&pipeline),
+            receiver,
+        ));
+    }
+    let server_app = AppState::new(pipeline, Arc::new(config.clone()));
 
     let cors_layer = cors::CorsLayer::permissive();
     let trace_layer = trace::TraceLayer::new_for_http()
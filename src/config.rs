@@ -1,5 +1,19 @@
 use crate::logger::LoggerConfig;
+use crate::modules::back_translation::config::BackTranslationConfig;
+use crate::modules::confidence::config::ConfidenceConfig;
+use crate::modules::consistency::config::ConsistencyConfig;
+use crate::modules::context::config::ContextConfig;
+use crate::modules::documents::config::DocumentsConfig;
+use crate::modules::formality::config::FormalityConfig;
+use crate::modules::glossary::config::GlossaryConfig;
 use crate::modules::llm_client::config::LLMClientConfig;
+use crate::modules::masking::config::MaskingConfig;
+use crate::modules::pass_through::config::PassThroughConfig;
+use crate::modules::profiles::config::ProfilesConfig;
+use crate::modules::sanitizer::config::SanitizerConfig;
+use crate::modules::tables::config::TablesConfig;
+use crate::modules::templates::config::TemplatesConfig;
+use crate::modules::validation::config::ValidationConfig;
 use crate::server::config::ServerConfig;
 
 use config::{Config, ConfigError, Environment, File, FileFormat};
@@ -18,6 +32,20 @@ pub struct ServiceConfig {
     llm_client: LLMClientConfig,
     server: ServerConfig,
     logger: LoggerConfig,
+    profiles: ProfilesConfig,
+    templates: TemplatesConfig,
+    glossary: GlossaryConfig,
+    masking: MaskingConfig,
+    pass_through: PassThroughConfig,
+    sanitizer: SanitizerConfig,
+    validation: ValidationConfig,
+    consistency: ConsistencyConfig,
+    formality: FormalityConfig,
+    context: ContextConfig,
+    back_translation: BackTranslationConfig,
+    confidence: ConfidenceConfig,
+    documents: DocumentsConfig,
+    tables: TablesConfig,
 }
 
 impl ServiceConfig {
@@ -0,0 +1,13 @@
+use getset::CopyGetters;
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct BackTranslationConfig {
+    /// Every n-th translation is back-translated in the background; 0 disables the monitor.
+    monitor_every: u64,
+    /// Samples waiting for the monitor; new ones are dropped while it is full.
+    monitor_queue: usize,
+    /// chrF below which the monitor logs a warning.
+    warning_score: f64,
+}
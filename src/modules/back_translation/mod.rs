@@ -0,0 +1,155 @@
+pub mod config;
+pub mod models;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::sync::mpsc;
+
+use crate::modules::back_translation::config::BackTranslationConfig;
+use crate::modules::back_translation::models::MonitorSample;
+
+pub const BACK_TRANSLATION_METRIC: &str = "translator_back_translation_chrf";
+
+/// Character n-gram orders of chrF.
+const MAX_ORDER: usize = 6;
+/// Recall weighs `BETA` times as much as precision.
+const BETA: f64 = 2.0;
+
+/// chrF score between a reference and a hypothesis, 0 to 100. Whitespace is
+/// ignored; precision and recall are averaged over the n-gram orders both
+/// texts are long enough for.
+pub fn chrf(reference: &str, hypothesis: &str) -> f64 {
+    let reference: Vec<char> = reference.chars().filter(|ch| !ch.is_whitespace()).collect();
+    let hypothesis: Vec<char> = hypothesis
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .collect();
+
+    let mut precision = 0.0;
+    let mut recall = 0.0;
+    let mut orders = 0;
+    for order in 1..=MAX_ORDER {
+        let reference_ngrams = ngrams(&reference, order);
+        let hypothesis_ngrams = ngrams(&hypothesis, order);
+        let reference_total: usize = reference_ngrams.values().sum();
+        let hypothesis_total: usize = hypothesis_ngrams.values().sum();
+        if reference_total == 0 || hypothesis_total == 0 {
+            break;
+        }
+        let matches: usize = hypothesis_ngrams
+            .iter()
+            .map(|(ngram, count)| (*count).min(reference_ngrams.get(ngram).copied().unwrap_or(0)))
+            .sum();
+        precision += matches as f64 / hypothesis_total as f64;
+        recall += matches as f64 / reference_total as f64;
+        orders += 1;
+    }
+    if orders == 0 {
+        return if reference.is_empty() && hypothesis.is_empty() {
+            100.0
+        } else {
+            0.0
+        };
+    }
+
+    let precision = precision / orders as f64;
+    let recall = recall / orders as f64;
+    let beta2 = BETA * BETA;
+    if precision + recall == 0.0 {
+        return 0.0;
+    }
+    100.0 * (1.0 + beta2) * precision * recall / (beta2 * precision + recall)
+}
+
+fn ngrams(chars: &[char], order: usize) -> HashMap<&[char], usize> {
+    let mut counts = HashMap::new();
+    for window in chars.windows(order) {
+        *counts.entry(window).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Picks every n-th finished translation for back-translation in the
+/// background. Samples are dropped while the monitor is behind.
+pub struct MonitorSampler {
+    config: BackTranslationConfig,
+    counter: AtomicU64,
+    sender: mpsc::Sender<MonitorSample>,
+}
+
+impl MonitorSampler {
+    /// None when the monitor is disabled.
+    pub fn new(config: &BackTranslationConfig) -> Option<(Self, mpsc::Receiver<MonitorSample>)> {
+        if config.monitor_every() == 0 {
+            return None;
+        }
+        let (sender, receiver) = mpsc::channel(config.monitor_queue().max(1));
+        let sampler = MonitorSampler {
+            config: config.to_owned(),
+            counter: AtomicU64::new(0),
+            sender,
+        };
+        Some((sampler, receiver))
+    }
+
+    pub fn config(&self) -> &BackTranslationConfig {
+        &self.config
+    }
+
+    /// Queues the sample when its turn has come; the sample is built only then.
+    pub fn offer(&self, sample: impl FnOnce() -> MonitorSample) {
+        let count = self.counter.fetch_add(1, Ordering::Relaxed) + 1;
+        if !count.is_multiple_of(self.config.monitor_every()) {
+            return;
+        }
+        if self.sender.try_send(sample()).is_err() {
+            tracing::debug!("Back-translation monitor is busy, sample dropped");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_back_translation {
+    use crate::modules::back_translation::config::BackTranslationConfig;
+    use crate::modules::back_translation::models::MonitorSample;
+    use crate::modules::back_translation::{MonitorSampler, chrf};
+
+    #[test]
+    fn test_chrf() {
+        let source = "Check the radio before the convoy leaves.";
+        assert_eq!(chrf(source, source), 100.0);
+        assert_eq!(chrf(source, ""), 0.0);
+        assert_eq!(chrf("", ""), 100.0);
+
+        let close = chrf(source, "Check your radio before the convoy departs.");
+        let far = chrf(source, "The weather was cold in the mountains.");
+        assert!(close > 60.0 && close < 100.0, "{close}");
+        assert!(far < 40.0, "{far}");
+        assert_eq!(chrf("a b", "ab"), 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_monitor_sampler() {
+        let config: BackTranslationConfig = serde_json::from_value(serde_json::json!({
+            "monitor_every": 2,
+            "monitor_queue": 1,
+            "warning_score": 40.0,
+        }))
+        .unwrap();
+        let (sampler, mut receiver) = MonitorSampler::new(&config).unwrap();
+        for index in 0..6 {
+            sampler.offer(|| {
+                MonitorSample::new(
+                    "default".to_owned(),
+                    "en".to_owned(),
+                    "ru".to_owned(),
+                    format!("text {index}"),
+                    format!("текст {index}"),
+                )
+            });
+        }
+        assert_eq!(receiver.recv().await.unwrap().source(), "text 1");
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -0,0 +1,48 @@
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Translation of the output back into the source language.
+#[derive(Clone, Serialize, Deserialize, Getters, CopyGetters, PartialEq, Debug, ToSchema)]
+pub struct BackTranslation {
+    #[getset(get = "pub")]
+    text: String,
+    /// chrF between the source and the back-translation, 0 to 100.
+    #[getset(get_copy = "pub")]
+    score: f64,
+}
+
+impl BackTranslation {
+    pub fn new(text: String, score: f64) -> Self {
+        Self { text, score }
+    }
+}
+
+/// Finished translation queued for the background monitor.
+#[derive(Getters, Clone, Debug)]
+#[getset(get = "pub")]
+pub struct MonitorSample {
+    profile: String,
+    source_language: String,
+    target_language: String,
+    source: String,
+    translation: String,
+}
+
+impl MonitorSample {
+    pub fn new(
+        profile: String,
+        source_language: String,
+        target_language: String,
+        source: String,
+        translation: String,
+    ) -> Self {
+        Self {
+            profile,
+            source_language,
+            target_language,
+            source,
+            translation,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use getset::CopyGetters;
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct ConfidenceConfig {
+    /// Tokens less likely than this are returned as low-confidence spans.
+    low_probability: f64,
+}
@@ -0,0 +1,201 @@
+pub mod config;
+pub mod models;
+
+use std::ops::Range;
+
+use crate::modules::confidence::config::ConfidenceConfig;
+use crate::modules::confidence::models::{Confidence, ConfidenceSpan, SegmentConfidence};
+use crate::modules::llm_client::models::TokenLogprob;
+
+/// Bytes of output a token may be searched ahead of the previous one. Tokens
+/// of text the pipeline removed or replaced are not found within it and skipped.
+const MAX_TOKEN_GAP: usize = 32;
+
+/// Turns token log probabilities of the model output into confidence of the
+/// translated text. Tokens are located in the final text, so sanitizing,
+/// placeholder restoring and escaping do not shift the offsets.
+pub struct ConfidenceScorer {
+    config: ConfidenceConfig,
+}
+
+impl ConfidenceScorer {
+    pub fn new(config: &ConfidenceConfig) -> Self {
+        ConfidenceScorer {
+            config: config.to_owned(),
+        }
+    }
+
+    pub fn config(&self) -> &ConfidenceConfig {
+        &self.config
+    }
+
+    /// Confidence of one translated segment, with offsets relative to `text`.
+    /// None when no token is found in the text.
+    pub fn score(&self, text: &str, logprobs: &[TokenLogprob]) -> Option<Confidence> {
+        let aligned = align(text, logprobs);
+        if aligned.is_empty() {
+            return None;
+        }
+
+        let tokens: Vec<ConfidenceSpan> = aligned
+            .iter()
+            .map(|(range, logprob)| span(text, range.clone(), logprob.exp()))
+            .collect();
+
+        let mut low: Vec<(Range<usize>, f64)> = Vec::new();
+        for (range, logprob) in &aligned {
+            let probability = logprob.exp();
+            if probability >= self.config.low_probability() {
+                continue;
+            }
+            match low.last_mut() {
+                Some((last, lowest)) if text[last.end..range.start].trim().is_empty() => {
+                    last.end = range.end;
+                    *lowest = lowest.min(probability);
+                }
+                _ => low.push((range.clone(), probability)),
+            }
+        }
+        let low_confidence_spans = low
+            .into_iter()
+            .map(|(range, probability)| span(text, range, probability))
+            .collect();
+
+        let probability = mean_probability(&tokens);
+        let segment = SegmentConfidence::new(0, text.chars().count(), probability);
+        Some(Confidence::new(
+            probability,
+            vec![segment],
+            low_confidence_spans,
+            tokens,
+        ))
+    }
+
+    /// Joins the confidence of the segments of a document. Each segment comes
+    /// with the position in the document of its character offsets.
+    pub fn combine<P>(&self, parts: Vec<(P, Confidence)>) -> Option<Confidence>
+    where
+        P: Fn(usize) -> usize,
+    {
+        let mut segments = Vec::new();
+        let mut low_confidence_spans = Vec::new();
+        let mut tokens = Vec::new();
+        for (place, part) in parts {
+            let shifted = |mut span: ConfidenceSpan| {
+                span.place(&place);
+                span
+            };
+            segments.extend(part.segments().iter().cloned().map(|mut segment| {
+                segment.place(&place);
+                segment
+            }));
+            low_confidence_spans.extend(part.low_confidence_spans().iter().cloned().map(shifted));
+            tokens.extend(part.tokens().iter().cloned().map(shifted));
+        }
+        if tokens.is_empty() {
+            return None;
+        }
+        Some(Confidence::new(
+            mean_probability(&tokens),
+            segments,
+            low_confidence_spans,
+            tokens,
+        ))
+    }
+}
+
+/// Byte ranges of the tokens found in `text`, in order, with their log probabilities.
+fn align(text: &str, logprobs: &[TokenLogprob]) -> Vec<(Range<usize>, f64)> {
+    let mut aligned = Vec::new();
+    let mut cursor = 0;
+    for logprob in logprobs {
+        let token = logprob.token().trim();
+        if token.is_empty() {
+            continue;
+        }
+        let Some(found) = text[cursor..].find(token) else {
+            continue;
+        };
+        if found > MAX_TOKEN_GAP {
+            continue;
+        }
+        let start = cursor + found;
+        cursor = start + token.len();
+        aligned.push((start..cursor, logprob.logprob()));
+    }
+    aligned
+}
+
+fn span(text: &str, range: Range<usize>, probability: f64) -> ConfidenceSpan {
+    let start = text[..range.start].chars().count();
+    let value = &text[range];
+    ConfidenceSpan::new(
+        start,
+        start + value.chars().count(),
+        value.to_owned(),
+        probability,
+    )
+}
+
+fn mean_probability(tokens: &[ConfidenceSpan]) -> f64 {
+    let sum: f64 = tokens
+        .iter()
+        .map(|token| token.probability().max(f64::MIN_POSITIVE).ln())
+        .sum();
+    (sum / tokens.len() as f64).exp()
+}
+
+#[cfg(test)]
+mod test_confidence {
+    use crate::modules::confidence::ConfidenceScorer;
+    use crate::modules::confidence::config::ConfidenceConfig;
+    use crate::modules::llm_client::models::TokenLogprob;
+
+    #[test]
+    fn test_confidence_spans() {
+        let config: ConfidenceConfig = serde_json::from_value(serde_json::json!({
+            "low_probability": 0.5,
+        }))
+        .unwrap();
+        let scorer = ConfidenceScorer::new(&config);
+        let logprobs: Vec<TokenLogprob> = [
+            ("«", -0.01),
+            ("Про", -0.1),
+            ("верь", -0.05),
+            (" ра", -1.5),
+            ("цию", -0.9),
+            ("»", -0.01),
+            (" ⟦1⟧", -0.01),
+            (" до", -2.0),
+            (" рас", -0.2),
+            ("света", -0.1),
+            (".", -0.01),
+        ]
+        .into_iter()
+        .map(|(token, logprob)| TokenLogprob::new(token.to_owned(), logprob))
+        .collect();
+
+        // Quotes removed by the sanitizer and a restored placeholder.
+        let text = "Проверь рацию https://example.com до рассвета.";
+        let confidence = scorer.score(text, &logprobs).unwrap();
+        assert_eq!(confidence.tokens().len(), 8);
+        let spans: Vec<(usize, usize, &str)> = confidence
+            .low_confidence_spans()
+            .iter()
+            .map(|span| (span.start(), span.end(), span.text().as_str()))
+            .collect();
+        assert_eq!(spans, vec![(8, 13, "рацию"), (34, 36, "до")]);
+        assert!(
+            (confidence.low_confidence_spans()[0].probability() - (-1.5f64).exp()).abs() < 1e-9
+        );
+
+        let at = |offset: usize| move |index: usize| offset + index;
+        let document = scorer
+            .combine(vec![(at(0), confidence.clone()), (at(50), confidence)])
+            .unwrap();
+        assert_eq!(document.segments().len(), 2);
+        assert_eq!(document.segments()[1].start(), 50);
+        assert_eq!(document.low_confidence_spans()[3].start(), 84);
+        assert!(document.probability() > 0.5 && document.probability() < 1.0);
+    }
+}
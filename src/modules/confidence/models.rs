@@ -0,0 +1,91 @@
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Part of the translation with the probability the model gave it. Offsets
+/// are in characters of the translated text, `end` exclusive.
+#[derive(Clone, Serialize, Deserialize, Getters, CopyGetters, PartialEq, Debug, ToSchema)]
+pub struct ConfidenceSpan {
+    #[getset(get_copy = "pub")]
+    start: usize,
+    #[getset(get_copy = "pub")]
+    end: usize,
+    #[getset(get = "pub")]
+    text: String,
+    /// Token probability; for a span of several tokens, the lowest one.
+    #[getset(get_copy = "pub")]
+    probability: f64,
+}
+
+impl ConfidenceSpan {
+    pub fn new(start: usize, end: usize, text: String, probability: f64) -> Self {
+        Self {
+            start,
+            end,
+            text,
+            probability,
+        }
+    }
+
+    /// Moves the span to the position `place` gives for its offsets.
+    pub fn place(&mut self, place: impl Fn(usize) -> usize) {
+        self.start = place(self.start);
+        self.end = place(self.end);
+    }
+}
+
+/// Geometric mean token probability of one translated segment.
+#[derive(Clone, Serialize, Deserialize, CopyGetters, PartialEq, Debug, ToSchema)]
+#[getset(get_copy = "pub")]
+pub struct SegmentConfidence {
+    start: usize,
+    end: usize,
+    probability: f64,
+}
+
+impl SegmentConfidence {
+    pub fn new(start: usize, end: usize, probability: f64) -> Self {
+        Self {
+            start,
+            end,
+            probability,
+        }
+    }
+
+    /// Moves the span to the position `place` gives for its offsets.
+    pub fn place(&mut self, place: impl Fn(usize) -> usize) {
+        self.start = place(self.start);
+        self.end = place(self.end);
+    }
+}
+
+/// Model confidence in a translation, from the log probabilities of its tokens.
+#[derive(Clone, Serialize, Deserialize, Getters, CopyGetters, PartialEq, Debug, ToSchema)]
+pub struct Confidence {
+    /// Geometric mean probability of all tokens.
+    #[getset(get_copy = "pub")]
+    probability: f64,
+    #[getset(get = "pub")]
+    segments: Vec<SegmentConfidence>,
+    /// Adjacent tokens below the configured probability, merged.
+    #[getset(get = "pub")]
+    low_confidence_spans: Vec<ConfidenceSpan>,
+    #[getset(get = "pub")]
+    tokens: Vec<ConfidenceSpan>,
+}
+
+impl Confidence {
+    pub fn new(
+        probability: f64,
+        segments: Vec<SegmentConfidence>,
+        low_confidence_spans: Vec<ConfidenceSpan>,
+        tokens: Vec<ConfidenceSpan>,
+    ) -> Self {
+        Self {
+            probability,
+            segments,
+            low_confidence_spans,
+            tokens,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use getset::{CopyGetters, Getters};
+use serde::Deserialize;
+
+use crate::modules::consistency::models::{ConsistencyAction, ConsistencyKind};
+
+#[derive(Clone, Deserialize, CopyGetters, Getters)]
+pub struct ConsistencyConfig {
+    #[getset(get_copy = "pub")]
+    enabled: bool,
+    #[getset(get_copy = "pub")]
+    #[serde(default)]
+    action: ConsistencyAction,
+    #[getset(get_copy = "pub")]
+    #[serde(default)]
+    max_retries: u32,
+    /// Kinds of values compared between source and translation.
+    #[getset(get = "pub")]
+    checks: Vec<ConsistencyKind>,
+}
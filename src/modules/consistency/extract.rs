@@ -0,0 +1,431 @@
+use regex::{Captures, Regex};
+
+use crate::modules::consistency::models::ConsistencyKind;
+
+/// Languages writing decimals with a comma and grouping thousands with a dot or space.
+const COMMA_DECIMAL_LANGUAGES: [&str; 10] =
+    ["ru", "uk", "fr", "de", "es", "it", "pl", "cs", "pt", "tr"];
+
+const NUMBER_PATTERN: &str = r"\d{1,3}(?:[ \u{00A0}\u{202F}]\d{3})+\b(?:[.,]\d+)?|\d+(?:[.,']\d+)*";
+
+/// Lowercase month name prefixes of supported languages.
+const MONTHS: [(&str, u32); 51] = [
+    ("jan", 1),
+    ("feb", 2),
+    ("fév", 2),
+    ("mar", 3),
+    ("mär", 3),
+    ("apr", 4),
+    ("avr", 4),
+    ("abr", 4),
+    ("may", 5),
+    ("mai", 5),
+    ("jun", 6),
+    ("juin", 6),
+    ("jul", 7),
+    ("juil", 7),
+    ("aug", 8),
+    ("août", 8),
+    ("ago", 8),
+    ("sep", 9),
+    ("oct", 10),
+    ("okt", 10),
+    ("nov", 11),
+    ("dec", 12),
+    ("dez", 12),
+    ("déc", 12),
+    ("dic", 12),
+    ("ene", 1),
+    ("янв", 1),
+    ("фев", 2),
+    ("мар", 3),
+    ("апр", 4),
+    ("май", 5),
+    ("мая", 5),
+    ("июн", 6),
+    ("июл", 7),
+    ("авг", 8),
+    ("сен", 9),
+    ("окт", 10),
+    ("ноя", 11),
+    ("дек", 12),
+    ("січ", 1),
+    ("лют", 2),
+    ("бер", 3),
+    ("кві", 4),
+    ("тра", 5),
+    ("чер", 6),
+    ("лип", 7),
+    ("сер", 8),
+    ("вер", 9),
+    ("жов", 10),
+    ("лис", 11),
+    ("гру", 12),
+];
+
+/// Canonical unit and its spellings. Units are case-sensitive (`T-72` is not tonnes).
+const UNITS: [(&str, &[&str]); 24] = [
+    ("km/h", &["km/h", "kph", "км/ч"]),
+    ("m/s", &["m/s", "м/с"]),
+    ("mph", &["mph"]),
+    ("km", &["km", "км"]),
+    ("cm", &["cm", "см"]),
+    ("mm", &["mm", "мм"]),
+    ("m", &["m", "м"]),
+    ("kg", &["kg", "кг"]),
+    ("mg", &["mg", "мг"]),
+    ("g", &["g"]),
+    ("t", &["t", "т"]),
+    ("ml", &["ml", "мл"]),
+    ("l", &["l", "L", "л"]),
+    ("h", &["h", "ч"]),
+    ("min", &["min", "мин"]),
+    ("s", &["s", "sec", "с", "сек"]),
+    ("kn", &["kn", "kt", "уз"]),
+    ("ft", &["ft"]),
+    ("%", &["%"]),
+    ("°C", &["°C", "°С"]),
+    ("MHz", &["MHz", "МГц"]),
+    ("GHz", &["GHz", "ГГц"]),
+    ("kHz", &["kHz", "кГц"]),
+    ("Hz", &["Hz", "Гц"]),
+];
+
+type Normalize = fn(&Captures, &str) -> Option<String>;
+
+pub struct Extractor {
+    kind: ConsistencyKind,
+    regex: Regex,
+    normalize: Normalize,
+    /// Match is dropped when a letter follows it (`5 mining` is not `5 m`).
+    standalone: bool,
+}
+
+impl Extractor {
+    fn new(
+        kind: ConsistencyKind,
+        pattern: &str,
+        normalize: Normalize,
+        standalone: bool,
+    ) -> Result<Self, regex::Error> {
+        Ok(Extractor {
+            kind,
+            regex: Regex::new(pattern)?,
+            normalize,
+            standalone,
+        })
+    }
+}
+
+/// Extractors in application order. Every match is blanked out before the
+/// next extractor runs, so the digits of a date are not counted as numbers.
+pub fn extractors() -> Result<Vec<Extractor>, regex::Error> {
+    let mut spellings: Vec<&str> = UNITS
+        .iter()
+        .flat_map(|(_, spellings)| spellings.iter().copied())
+        .collect();
+    spellings.sort_by_key(|spelling| std::cmp::Reverse(spelling.chars().count()));
+    let units: Vec<String> = spellings
+        .iter()
+        .map(|spelling| regex::escape(spelling))
+        .collect();
+
+    Ok(vec![
+        Extractor::new(
+            ConsistencyKind::Date,
+            r"\b(\d{4})-(\d{1,2})-(\d{1,2})\b",
+            date_iso,
+            false,
+        )?,
+        Extractor::new(
+            ConsistencyKind::Date,
+            r"\b(\d{1,2})([./])(\d{1,2})([./])(\d{4}|\d{2})\b",
+            date_numeric,
+            false,
+        )?,
+        Extractor::new(
+            ConsistencyKind::Date,
+            r"\b(\d{1,2})(?:st|nd|rd|th)?\s+(?:of\s+)?(\p{L}{3,})\.?,?\s+(\d{4})\b",
+            date_day_month,
+            false,
+        )?,
+        Extractor::new(
+            ConsistencyKind::Date,
+            r"\b(\p{L}{3,})\.?\s+(\d{1,2})(?:st|nd|rd|th)?,?\s+(\d{4})\b",
+            date_month_day,
+            false,
+        )?,
+        Extractor::new(
+            ConsistencyKind::Coordinate,
+            r"\b\d{1,2}[C-HJ-NP-X]\s?[A-HJ-NP-Z]{2}\s?\d{2,10}(?:\s\d{1,5})?\b",
+            grid_reference,
+            false,
+        )?,
+        Extractor::new(
+            ConsistencyKind::Coordinate,
+            r#"(\d{1,3}(?:[.,]\d+)?)°\s?(?:(\d{1,2}(?:[.,]\d+)?)['′]\s?)?(?:(\d{1,2}(?:[.,]\d+)?)["″]\s?)?([NSEWСЮВЗ])?"#,
+            degrees,
+            true,
+        )?,
+        Extractor::new(
+            ConsistencyKind::UnitValue,
+            &format!(r"(\d+(?:[.,]\d+)?)\s?({})", units.join("|")),
+            unit_value,
+            true,
+        )?,
+        Extractor::new(
+            ConsistencyKind::Entity,
+            r"\b\p{L}{1,5}[-‑]?\d{1,4}[\p{L}\d]*\b",
+            entity,
+            false,
+        )?,
+        Extractor::new(ConsistencyKind::Number, NUMBER_PATTERN, number, false)?,
+        Extractor::new(ConsistencyKind::Acronym, r"\b\p{Lu}{2,6}\b", acronym, false)?,
+    ])
+}
+
+/// Extracts normalized values of the enabled kinds.
+pub fn extract(
+    extractors: &[Extractor],
+    checks: &[ConsistencyKind],
+    text: &str,
+    language: &str,
+) -> Vec<(ConsistencyKind, String)> {
+    let mut remaining = ascii_digits(text);
+    let mut values = Vec::new();
+    for extractor in extractors {
+        let mut consumed = Vec::new();
+        for caps in extractor.regex.captures_iter(&remaining) {
+            let Some(found) = caps.get(0) else {
+                continue;
+            };
+            if extractor.standalone
+                && remaining[found.end()..]
+                    .chars()
+                    .next()
+                    .is_some_and(char::is_alphabetic)
+            {
+                continue;
+            }
+            let Some(value) = (extractor.normalize)(&caps, language) else {
+                continue;
+            };
+            consumed.push(found.range());
+            if checks.contains(&extractor.kind) {
+                values.push((extractor.kind, value));
+            }
+        }
+        for range in consumed.into_iter().rev() {
+            let blank = " ".repeat(range.len());
+            remaining.replace_range(range, &blank);
+        }
+    }
+    values
+}
+
+/// Resolves grouping and decimal separators: `1,234.5` in English and
+/// `1 234,5` or `1.234,5` in Russian all become `1234.5`. Tokens that are
+/// not numbers in either convention (`1.2.3`) are kept as is.
+pub fn normalize_number(token: &str, language: &str) -> String {
+    let token: String = token
+        .chars()
+        .filter(|ch| !matches!(ch, ' ' | '\u{00A0}' | '\u{202F}' | '\''))
+        .collect();
+    let separators: Vec<(usize, char)> = token
+        .char_indices()
+        .filter(|(_, ch)| matches!(ch, '.' | ','))
+        .collect();
+    let Some(&(last_index, last)) = separators.last() else {
+        return trim_leading_zeros(&token);
+    };
+
+    let group = if COMMA_DECIMAL_LANGUAGES.contains(&language) {
+        '.'
+    } else {
+        ','
+    };
+    let groups: Vec<&str> = token.split(['.', ',']).collect();
+    let mixed = separators.iter().any(|(_, ch)| *ch != last);
+
+    if !mixed
+        && groups[1..].iter().all(|digits| digits.len() == 3)
+        && (separators.len() > 1 || last == group)
+    {
+        return trim_leading_zeros(&token.replace(['.', ','], ""));
+    }
+    let integer_groups = &groups[1..groups.len() - 1];
+    let grouped_integer = separators[..separators.len() - 1]
+        .iter()
+        .all(|(_, ch)| *ch != last)
+        && integer_groups.iter().all(|digits| digits.len() == 3);
+    if separators.len() == 1 || (mixed && grouped_integer) {
+        let integer = trim_leading_zeros(&token[..last_index].replace(['.', ','], ""));
+        let fraction = token[last_index + 1..].trim_end_matches('0');
+        return if fraction.is_empty() {
+            integer
+        } else {
+            format!("{integer}.{fraction}")
+        };
+    }
+    token
+}
+
+fn trim_leading_zeros(digits: &str) -> String {
+    let trimmed = digits.trim_start_matches('0');
+    if trimmed.is_empty() {
+        "0".to_owned()
+    } else {
+        trimmed.to_owned()
+    }
+}
+
+fn date(year: &str, month: u32, day: &str) -> Option<String> {
+    let year: u32 = year.parse().ok()?;
+    let day: u32 = day.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let year = if year < 100 { year + 2000 } else { year };
+    Some(format!("{year:04}-{month:02}-{day:02}"))
+}
+
+fn date_iso(caps: &Captures, _language: &str) -> Option<String> {
+    date(&caps[1], caps[2].parse().ok()?, &caps[3])
+}
+
+/// `05.03.2024` is always day first, `03/05/2024` is month first in English.
+fn date_numeric(caps: &Captures, language: &str) -> Option<String> {
+    if caps[2] != caps[4] {
+        return None;
+    }
+    let (month, day) = if &caps[2] == "/" && language == "en" {
+        (&caps[1], &caps[3])
+    } else {
+        (&caps[3], &caps[1])
+    };
+    date(&caps[5], month.parse().ok()?, day)
+}
+
+fn date_day_month(caps: &Captures, _language: &str) -> Option<String> {
+    date(&caps[3], month_number(&caps[2])?, &caps[1])
+}
+
+fn date_month_day(caps: &Captures, _language: &str) -> Option<String> {
+    date(&caps[3], month_number(&caps[1])?, &caps[2])
+}
+
+fn month_number(word: &str) -> Option<u32> {
+    let word = word.to_lowercase();
+    MONTHS
+        .iter()
+        .find(|(prefix, _)| word.starts_with(prefix))
+        .map(|(_, month)| *month)
+}
+
+fn grid_reference(caps: &Captures, _language: &str) -> Option<String> {
+    Some(caps[0].split_whitespace().collect())
+}
+
+fn degrees(caps: &Captures, _language: &str) -> Option<String> {
+    let mut value = format!("{}°", caps[1].replace(',', "."));
+    if let Some(minutes) = caps.get(2) {
+        value.push_str(&format!("{}'", minutes.as_str().replace(',', ".")));
+    }
+    if let Some(seconds) = caps.get(3) {
+        value.push_str(&format!("{}\"", seconds.as_str().replace(',', ".")));
+    }
+    if let Some(hemisphere) = caps.get(4) {
+        value.push(match hemisphere.as_str() {
+            "С" => 'N',
+            "Ю" => 'S',
+            "В" => 'E',
+            "З" => 'W',
+            latin => latin.chars().next()?,
+        });
+    }
+    Some(value)
+}
+
+fn unit_value(caps: &Captures, language: &str) -> Option<String> {
+    let (unit, _) = UNITS
+        .iter()
+        .find(|(_, spellings)| spellings.contains(&&caps[2]))?;
+    Some(format!("{} {unit}", normalize_number(&caps[1], language)))
+}
+
+fn entity(caps: &Captures, _language: &str) -> Option<String> {
+    Some(transliterate(&caps[0]).replace(['-', '‑'], ""))
+}
+
+fn number(caps: &Captures, language: &str) -> Option<String> {
+    Some(normalize_number(&caps[0], language))
+}
+
+fn acronym(caps: &Captures, _language: &str) -> Option<String> {
+    let token = &caps[0];
+    if token.chars().all(|ch| "IVXLCDM".contains(ch)) {
+        return None;
+    }
+    Some(transliterate(token))
+}
+
+/// Uppercase Latin form, so `НАТО` matches `NATO` and `Су-25` matches `Su-25`.
+fn transliterate(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for ch in text.to_uppercase().chars() {
+        let latin = match ch {
+            'А' => "A",
+            'Б' => "B",
+            'В' => "V",
+            'Г' | 'Ґ' => "G",
+            'Д' => "D",
+            'Е' | 'Ё' | 'Э' => "E",
+            'Є' => "YE",
+            'Ж' => "ZH",
+            'З' => "Z",
+            'И' | 'І' => "I",
+            'Ї' => "YI",
+            'Й' | 'Ы' => "Y",
+            'К' => "K",
+            'Л' => "L",
+            'М' => "M",
+            'Н' => "N",
+            'О' => "O",
+            'П' => "P",
+            'Р' => "R",
+            'С' => "S",
+            'Т' => "T",
+            'У' => "U",
+            'Ф' => "F",
+            'Х' => "KH",
+            'Ц' => "TS",
+            'Ч' => "CH",
+            'Ш' => "SH",
+            'Щ' => "SHCH",
+            'Ъ' | 'Ь' => "",
+            'Ю' => "YU",
+            'Я' => "YA",
+            _ => {
+                result.push(ch);
+                continue;
+            }
+        };
+        result.push_str(latin);
+    }
+    result
+}
+
+/// Arabic-Indic and fullwidth digits are compared as ASCII ones.
+fn ascii_digits(text: &str) -> String {
+    text.chars()
+        .map(|ch| {
+            let zero = match ch {
+                '\u{0660}'..='\u{0669}' => 0x0660,
+                '\u{06F0}'..='\u{06F9}' => 0x06F0,
+                '\u{FF10}'..='\u{FF19}' => 0xFF10,
+                _ => return ch,
+            };
+            char::from_digit(ch as u32 - zero, 10).unwrap_or(ch)
+        })
+        .collect()
+}
@@ -0,0 +1,127 @@
+pub mod config;
+pub mod extract;
+pub mod models;
+
+use std::collections::BTreeMap;
+
+use crate::modules::consistency::config::ConsistencyConfig;
+use crate::modules::consistency::extract::Extractor;
+use crate::modules::consistency::models::{ConsistencyKind, ConsistencyWarning};
+
+/// Compares numbers, dates, coordinates, unit values, acronyms and
+/// designations of the source and the translation after normalization.
+pub struct ConsistencyChecker {
+    config: ConsistencyConfig,
+    extractors: Vec<Extractor>,
+}
+
+impl ConsistencyChecker {
+    pub fn new(config: &ConsistencyConfig) -> Result<Self, regex::Error> {
+        Ok(ConsistencyChecker {
+            config: config.to_owned(),
+            extractors: extract::extractors()?,
+        })
+    }
+
+    pub fn config(&self) -> &ConsistencyConfig {
+        &self.config
+    }
+
+    pub fn check(
+        &self,
+        source_language: &str,
+        target_language: &str,
+        source: &str,
+        translation: &str,
+    ) -> Vec<ConsistencyWarning> {
+        if !self.config.enabled() {
+            return Vec::new();
+        }
+
+        let mut counts: BTreeMap<(ConsistencyKind, String), (usize, usize)> = BTreeMap::new();
+        let checks = self.config.checks();
+        for value in extract::extract(&self.extractors, checks, source, source_language) {
+            counts.entry(value).or_default().0 += 1;
+        }
+        for value in extract::extract(&self.extractors, checks, translation, target_language) {
+            counts.entry(value).or_default().1 += 1;
+        }
+
+        counts
+            .into_iter()
+            .filter(|(_, (source_count, translation_count))| source_count != translation_count)
+            .map(|((kind, value), (source_count, translation_count))| {
+                ConsistencyWarning::new(kind, value, source_count, translation_count)
+            })
+            .collect()
+    }
+}
+
+/// Extra system prompt instruction for a retry after mismatched values.
+pub fn retry_instruction(warnings: &[ConsistencyWarning]) -> String {
+    let values: Vec<&str> = warnings
+        .iter()
+        .filter(|warning| warning.source_count() > 0)
+        .map(|warning| warning.value().as_str())
+        .collect();
+    format!(
+        "Your previous answer changed numbers, dates or designations. Keep every number, date, coordinate, \
+unit value, acronym and designation of the source exactly, in particular: {}.",
+        values.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod test_consistency {
+    use crate::config::ServiceConfig;
+    use crate::modules::consistency::ConsistencyChecker;
+    use crate::modules::consistency::extract::normalize_number;
+    use crate::modules::consistency::models::ConsistencyKind;
+
+    #[test]
+    fn test_normalize_number() {
+        assert_eq!(normalize_number("1,234.50", "en"), "1234.5");
+        assert_eq!(normalize_number("1 234,5", "ru"), "1234.5");
+        assert_eq!(normalize_number("1.234", "de"), "1234");
+        assert_eq!(normalize_number("1.234", "en"), "1.234");
+        assert_eq!(normalize_number("7,62", "ru"), "7.62");
+        assert_eq!(normalize_number("1.2.3", "en"), "1.2.3");
+    }
+
+    #[test]
+    fn test_check_translation() -> Result<(), anyhow::Error> {
+        let service_config = ServiceConfig::new()?;
+        let checker = ConsistencyChecker::new(service_config.consistency())?;
+
+        let source =
+            "On March 5, 2024 NATO moved 1,200 troops and 3 T-72 tanks 12.5 km to 38SMB 4484 0815.";
+        let translation = "5 марта 2024 г. НАТО переместило 1 200 военнослужащих и 3 танка Т-72 на 12,5 км в 38SMB 4484 0815.";
+        assert!(checker.check("en", "ru", source, translation).is_empty());
+
+        let changed = "5 марта 2023 г. НАТО переместило 1 300 военнослужащих и 3 танка Т-80 на 12,5 км в 38SMB 4484 0815.";
+        let kinds: Vec<(ConsistencyKind, String, usize, usize)> = checker
+            .check("en", "ru", source, changed)
+            .into_iter()
+            .map(|warning| {
+                (
+                    warning.kind(),
+                    warning.value().to_owned(),
+                    warning.source_count(),
+                    warning.translation_count(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ConsistencyKind::Number, "1200".to_owned(), 1, 0),
+                (ConsistencyKind::Number, "1300".to_owned(), 0, 1),
+                (ConsistencyKind::Date, "2023-03-05".to_owned(), 0, 1),
+                (ConsistencyKind::Date, "2024-03-05".to_owned(), 1, 0),
+                (ConsistencyKind::Entity, "T72".to_owned(), 1, 0),
+                (ConsistencyKind::Entity, "T80".to_owned(), 0, 1),
+            ]
+        );
+        Ok(())
+    }
+}
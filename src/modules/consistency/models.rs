@@ -0,0 +1,72 @@
+use std::fmt;
+
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsistencyAction {
+    /// Mismatches are returned with the translation.
+    #[default]
+    Report,
+    /// The translation is rejected with an error.
+    Reject,
+    /// The translation is repeated with the mismatched values listed,
+    /// remaining mismatches are reported.
+    Retry,
+}
+
+#[derive(
+    Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsistencyKind {
+    Number,
+    Date,
+    Coordinate,
+    UnitValue,
+    Acronym,
+    /// Designations with letters and digits: `T-72`, `Su-25`, `M777`.
+    Entity,
+}
+
+/// Normalized value occurring a different number of times in the source
+/// and in the translation.
+#[derive(Clone, Serialize, Deserialize, Getters, CopyGetters, PartialEq, Debug, ToSchema)]
+pub struct ConsistencyWarning {
+    #[getset(get_copy = "pub")]
+    kind: ConsistencyKind,
+    #[getset(get = "pub")]
+    value: String,
+    #[getset(get_copy = "pub")]
+    source_count: usize,
+    #[getset(get_copy = "pub")]
+    translation_count: usize,
+}
+
+impl ConsistencyWarning {
+    pub fn new(
+        kind: ConsistencyKind,
+        value: String,
+        source_count: usize,
+        translation_count: usize,
+    ) -> Self {
+        Self {
+            kind,
+            value,
+            source_count,
+            translation_count,
+        }
+    }
+}
+
+impl fmt::Display for ConsistencyWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} '{}' occurs {} time(s) in source and {} time(s) in translation",
+            self.kind, self.value, self.source_count, self.translation_count
+        )
+    }
+}
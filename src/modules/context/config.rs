@@ -0,0 +1,15 @@
+use getset::CopyGetters;
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct ContextConfig {
+    /// Segments of each kind (preceding, following, translated) put in the prompt.
+    max_segments: usize,
+    /// Characters of context put in the prompt; the farthest segments are dropped first.
+    max_chars: usize,
+    /// Sessions not used for this long are forgotten.
+    session_ttl_secs: u64,
+    /// Sessions kept in memory; the least recently used one is forgotten first.
+    max_sessions: usize,
+}
@@ -0,0 +1,223 @@
+pub mod config;
+pub mod models;
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+
+use crate::modules::context::config::ContextConfig;
+use crate::modules::context::models::{TranslatedSegment, TranslationContext};
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct SessionKey {
+    id: String,
+    source_language: String,
+    target_language: String,
+}
+
+struct Session {
+    segments: VecDeque<TranslatedSegment>,
+    used_at: Instant,
+}
+
+/// Remembers the latest translations of every session, so segments sent one
+/// by one get the document translated so far as context.
+pub struct SessionStore {
+    config: ContextConfig,
+    sessions: RwLock<HashMap<SessionKey, Session>>,
+}
+
+impl SessionStore {
+    pub fn new(config: &ContextConfig) -> Self {
+        SessionStore {
+            config: config.to_owned(),
+            sessions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &ContextConfig {
+        &self.config
+    }
+
+    /// Translations remembered for the session and language pair, oldest first.
+    pub async fn history(
+        &self,
+        session_id: &str,
+        source_language: &str,
+        target_language: &str,
+    ) -> Vec<TranslatedSegment> {
+        let key = SessionKey {
+            id: session_id.to_owned(),
+            source_language: source_language.to_owned(),
+            target_language: target_language.to_owned(),
+        };
+        let mut sessions = self.sessions.write().await;
+        match sessions.get_mut(&key) {
+            Some(session) if session.used_at.elapsed() < self.ttl() => {
+                session.used_at = Instant::now();
+                session.segments.iter().cloned().collect()
+            }
+            Some(_) => {
+                sessions.remove(&key);
+                Vec::new()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    pub async fn record(
+        &self,
+        session_id: &str,
+        source_language: &str,
+        target_language: &str,
+        segment: TranslatedSegment,
+    ) {
+        let key = SessionKey {
+            id: session_id.to_owned(),
+            source_language: source_language.to_owned(),
+            target_language: target_language.to_owned(),
+        };
+        let ttl = self.ttl();
+        let mut sessions = self.sessions.write().await;
+        sessions.retain(|_, session| session.used_at.elapsed() < ttl);
+        if !sessions.contains_key(&key) && sessions.len() >= self.config.max_sessions() {
+            let oldest = sessions
+                .iter()
+                .min_by_key(|(_, session)| session.used_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                sessions.remove(&oldest);
+            }
+        }
+
+        let session = sessions.entry(key).or_insert_with(|| Session {
+            segments: VecDeque::new(),
+            used_at: Instant::now(),
+        });
+        session.used_at = Instant::now();
+        session.segments.push_back(segment);
+        while session.segments.len() > self.config.max_segments() {
+            session.segments.pop_front();
+        }
+    }
+
+    /// Value of the `{{context}}` template variable. When the context exceeds
+    /// the configured size, following text goes first, then the oldest
+    /// translations, then the farthest preceding text.
+    pub fn format_for_prompt(&self, context: &TranslationContext) -> Option<String> {
+        let max_segments = self.config.max_segments();
+        let mut preceding: VecDeque<&str> = tail(context.preceding(), max_segments)
+            .iter()
+            .map(String::as_str)
+            .collect();
+        let mut following: VecDeque<&str> = context
+            .following()
+            .iter()
+            .take(max_segments)
+            .map(String::as_str)
+            .collect();
+        let mut translations: VecDeque<String> = tail(context.translations(), max_segments)
+            .iter()
+            .map(|segment| format!("{} => {}", segment.source(), segment.translation()))
+            .collect();
+
+        let size = |preceding: &VecDeque<&str>,
+                    following: &VecDeque<&str>,
+                    translations: &VecDeque<String>| {
+            preceding
+                .iter()
+                .map(|text| text.chars().count())
+                .sum::<usize>()
+                + following
+                    .iter()
+                    .map(|text| text.chars().count())
+                    .sum::<usize>()
+                + translations
+                    .iter()
+                    .map(|text| text.chars().count())
+                    .sum::<usize>()
+        };
+        while size(&preceding, &following, &translations) > self.config.max_chars() {
+            if following.pop_back().is_none()
+                && translations.pop_front().is_none()
+                && preceding.pop_front().is_none()
+            {
+                break;
+            }
+        }
+
+        let mut sections = Vec::new();
+        if !translations.is_empty() {
+            sections.push(format!(
+                "Earlier segments and their translations:\n{}",
+                Vec::from(translations).join("\n")
+            ));
+        }
+        if !preceding.is_empty() {
+            sections.push(format!("Text before:\n{}", Vec::from(preceding).join("\n")));
+        }
+        if !following.is_empty() {
+            sections.push(format!("Text after:\n{}", Vec::from(following).join("\n")));
+        }
+        if sections.is_empty() {
+            return None;
+        }
+        Some(sections.join("\n\n"))
+    }
+
+    fn ttl(&self) -> Duration {
+        Duration::from_secs(self.config.session_ttl_secs())
+    }
+}
+
+fn tail<T>(items: &[T], count: usize) -> &[T] {
+    &items[items.len().saturating_sub(count)..]
+}
+
+#[cfg(test)]
+mod test_context {
+    use crate::modules::context::SessionStore;
+    use crate::modules::context::config::ContextConfig;
+    use crate::modules::context::models::{TranslatedSegment, TranslationContext};
+
+    #[tokio::test]
+    async fn test_session_context() {
+        let config: ContextConfig = serde_json::from_value(serde_json::json!({
+            "max_segments": 2,
+            "max_chars": 60,
+            "session_ttl_secs": 60,
+            "max_sessions": 1,
+        }))
+        .unwrap();
+        let store = SessionStore::new(&config);
+        for (source, translation) in [("One", "Один"), ("Two", "Два"), ("Three", "Три")] {
+            let segment = TranslatedSegment::new(source.to_owned(), translation.to_owned());
+            store.record("doc-1", "en", "ru", segment).await;
+        }
+        let history = store.history("doc-1", "en", "ru").await;
+        let sources: Vec<&str> = history
+            .iter()
+            .map(|segment| segment.source().as_str())
+            .collect();
+        assert_eq!(sources, vec!["Two", "Three"]);
+        assert!(store.history("doc-1", "en", "de").await.is_empty());
+
+        let segment = TranslatedSegment::new("Other".to_owned(), "Другой".to_owned());
+        store.record("doc-2", "en", "ru", segment).await;
+        assert!(store.history("doc-1", "en", "ru").await.is_empty());
+
+        let mut context: TranslationContext = serde_json::from_value(serde_json::json!({
+            "preceding": ["The convoy left at dawn.", "It reached the bridge."],
+            "following": ["Then it turned north toward the village near the river."],
+        }))
+        .unwrap();
+        context.prepend_translations(history);
+        let prompt = store.format_for_prompt(&context).unwrap();
+        assert_eq!(
+            prompt,
+            "Earlier segments and their translations:\nThree => Три\n\n\
+             Text before:\nThe convoy left at dawn.\nIt reached the bridge."
+        );
+    }
+}
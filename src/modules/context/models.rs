@@ -0,0 +1,44 @@
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Source segment together with its approved or earlier translation.
+#[derive(Clone, Serialize, Deserialize, Getters, PartialEq, Debug, ToSchema)]
+#[getset(get = "pub")]
+pub struct TranslatedSegment {
+    source: String,
+    translation: String,
+}
+
+impl TranslatedSegment {
+    pub fn new(source: String, translation: String) -> Self {
+        Self {
+            source,
+            translation,
+        }
+    }
+}
+
+/// Surrounding document text passed to the model as read-only context.
+#[derive(Clone, Default, Serialize, Deserialize, Getters, PartialEq, Debug, ToSchema)]
+#[getset(get = "pub")]
+pub struct TranslationContext {
+    /// Source segments before the text, in document order.
+    #[serde(default)]
+    preceding: Vec<String>,
+    /// Source segments after the text, in document order.
+    #[serde(default)]
+    following: Vec<String>,
+    /// Earlier segments with their translations, oldest first.
+    #[serde(default)]
+    translations: Vec<TranslatedSegment>,
+}
+
+impl TranslationContext {
+    /// Puts segments remembered in a session before the ones sent with the request.
+    pub fn prepend_translations(&mut self, segments: Vec<TranslatedSegment>) {
+        let mut translations = segments;
+        translations.append(&mut self.translations);
+        self.translations = translations;
+    }
+}
@@ -0,0 +1,325 @@
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::documents::{ooxml, placeholders};
+use crate::modules::formats::markup::{self, Dialect, Element, Node};
+use crate::modules::formats::models::TextFormat;
+
+/// Parts of a string resource passed to the model as `<xN/>` tags:
+/// `<xliff:g>` spans, `\n` and `\t` escapes, `%%` and printf conversions.
+const TOKEN_PATTERN: &str = r"(?s)<xliff:g\b[^>]*?/>|<xliff:g\b[^>]*>.*?</xliff:g>|\\[nt]|%%|%(?:\d+\$)?[-+0#,(]*\d*(?:\.\d+)?[sSdoxXeEfgGaAcCbBhH]";
+
+static TOKEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(TOKEN_PATTERN).expect("token pattern is valid"));
+
+/// Android `strings.xml`: `<string>`, the items of `<string-array>` and the
+/// quantities of `<plurals>`. Resources with `translatable="false"` are
+/// skipped; `<xliff:g>` spans, escapes and format specifiers are kept.
+pub struct AndroidStringsDocument;
+
+impl DocumentFormat for AndroidStringsDocument {
+    fn name(&self) -> &'static str {
+        "android"
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        &["application/xml", "text/xml"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn file_names(&self) -> &'static [&'static str] {
+        &["strings.xml", "arrays.xml", "plurals.xml"]
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let xml = decode(data)?;
+        let nodes = parse(xml)?;
+        Ok(strings(&nodes)
+            .into_iter()
+            .map(|string| protect(&xml[string.inner]))
+            .map(|protected| DocumentSegment::new(protected.text, TextFormat::Xml))
+            .collect())
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let xml = decode(data)?;
+        let nodes = parse(xml)?;
+        let strings = strings(&nodes);
+        if strings.len() != translations.len() {
+            return Err(DocumentErrors::Malformed(format!(
+                "{} translations for {} strings",
+                translations.len(),
+                strings.len()
+            )));
+        }
+
+        let mut replacements = Vec::with_capacity(strings.len());
+        for (string, translation) in strings.into_iter().zip(translations) {
+            let source = &xml[string.inner.clone()];
+            let value = restore(translation, &protect(source));
+            placeholders::check_specifiers(&string.name, source, &value)?;
+            replacements.push((string.inner, value));
+        }
+        Ok(ooxml::replace(xml, &replacements).into_bytes())
+    }
+}
+
+/// Translatable string with the name of its resource.
+struct AndroidString {
+    name: String,
+    /// Content between the tags.
+    inner: Range<usize>,
+}
+
+/// Strings to translate in document order.
+fn strings(nodes: &[Node]) -> Vec<AndroidString> {
+    let mut strings = Vec::new();
+    let Some(resources) = ooxml::elements(nodes, "resources").into_iter().next() else {
+        return strings;
+    };
+    let translatable = |element: &Element| element.attribute("translatable") != Some("false");
+    for node in &resources.children {
+        let Node::Element(resource) = node else {
+            continue;
+        };
+        if !translatable(resource) {
+            continue;
+        }
+        let name = resource.attribute("name").unwrap_or_default();
+        match resource.name.as_str() {
+            "string" => strings.extend(string(resource, name.to_owned())),
+            "string-array" => {
+                for (index, item) in ooxml::children(resource, "item").enumerate() {
+                    strings.extend(string(item, format!("{name}[{index}]")));
+                }
+            }
+            "plurals" => {
+                for item in ooxml::children(resource, "item") {
+                    let quantity = item.attribute("quantity").unwrap_or_default();
+                    strings.extend(string(item, format!("{name}:{quantity}")));
+                }
+            }
+            _ => {}
+        }
+    }
+    strings
+}
+
+fn string(element: &Element, name: String) -> Option<AndroidString> {
+    let end_tag = element.end_tag.as_ref()?;
+    if !has_text(&element.children) {
+        return None;
+    }
+    Some(AndroidString {
+        name,
+        inner: element.start_tag.end..end_tag.start,
+    })
+}
+
+fn has_text(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| match node {
+        Node::Text(_) | Node::Other(_) => true,
+        Node::Element(element) => element.name != "xliff:g" && has_text(&element.children),
+    })
+}
+
+/// Resource text as XML for the model: Android escapes decoded, tokens as
+/// `<xN/>` tags and the quotes around a whole quoted value removed.
+struct Protected {
+    text: String,
+    tokens: Vec<String>,
+    quoted: bool,
+}
+
+fn protect(inner: &str) -> Protected {
+    let trimmed = inner.trim();
+    let quoted = trimmed.len() >= 2
+        && trimmed.starts_with('"')
+        && trimmed.ends_with('"')
+        && !trimmed.ends_with("\\\"");
+    let content = if quoted {
+        &trimmed[1..trimmed.len() - 1]
+    } else {
+        inner
+    };
+
+    let mut text = String::with_capacity(content.len());
+    let mut tokens = Vec::new();
+    let mut last = 0;
+    for found in TOKEN.find_iter(content) {
+        text.push_str(&unescape(&content[last..found.start()]));
+        tokens.push(found.as_str().to_owned());
+        text.push_str(&format!("<x{}/>", tokens.len()));
+        last = found.end();
+    }
+    text.push_str(&unescape(&content[last..]));
+    Protected {
+        text,
+        tokens,
+        quoted,
+    }
+}
+
+/// Translation with Android escapes and the tokens of the source put back.
+fn restore(translation: &str, protected: &Protected) -> String {
+    let mut value = String::with_capacity(translation.len());
+    let mut rest = translation;
+    while !rest.is_empty() {
+        let (piece, token, next) = placeholders::next_placeholder(rest);
+        value.push_str(&escape(piece, protected.quoted));
+        if let Some(token) = token.and_then(|index| protected.tokens.get(index)) {
+            value.push_str(token);
+        }
+        rest = next;
+    }
+    if protected.quoted {
+        return format!("\"{value}\"");
+    }
+    match value.chars().next() {
+        Some('@' | '?') => format!("\\{value}"),
+        _ => value,
+    }
+}
+
+/// Text with `\'`, `\"`, `\@`, `\?`, `\\` and `\uXXXX` decoded.
+fn unescape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            output.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('u') => {
+                let digits: String = chars.clone().take(4).collect();
+                match u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                {
+                    Some(decoded) if digits.len() == 4 => {
+                        output.push(decoded);
+                        chars.nth(3);
+                    }
+                    _ => output.push_str("\\u"),
+                }
+            }
+            Some(escaped) => output.push(escaped),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+/// Escapes the text outside tags: quotes and backslashes, and apostrophes
+/// unless the value is quoted.
+fn escape(text: &str, quoted: bool) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for ch in text.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            '\\' | '"' if !in_tag => output.push('\\'),
+            '\'' if !in_tag && !quoted => output.push('\\'),
+            _ => {}
+        }
+        output.push(ch);
+    }
+    output
+}
+
+fn decode(data: &[u8]) -> DocumentResult<&str> {
+    std::str::from_utf8(data)
+        .map_err(|err| DocumentErrors::Malformed(format!("string resources are not UTF-8: {err}")))
+}
+
+fn parse(xml: &str) -> DocumentResult<Vec<Node>> {
+    markup::parse(xml, Dialect::Xml).map_err(|err| DocumentErrors::Malformed(err.to_string()))
+}
+
+#[cfg(test)]
+mod test_android {
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::android::AndroidStringsDocument;
+    use crate::modules::documents::errors::DocumentErrors;
+    use crate::modules::documents::models::DocumentOptions;
+
+    const STRINGS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<resources xmlns:xliff="urn:oasis:names:tc:xliff:document:1.2">
+    <string name="app_name" translatable="false">Recon</string>
+    <string name="welcome">Welcome, <b>%1$s</b>! You\'re on <xliff:g id="unit">%2$s</xliff:g>.\nStay alert.</string>
+    <string name="quoted">"Don't panic"</string>
+    <string-array name="ranks">
+        <item>Private</item>
+        <item>Sergeant</item>
+    </string-array>
+    <plurals name="rounds">
+        <item quantity="one">%d round left</item>
+        <item quantity="other">%d rounds left</item>
+    </plurals>
+</resources>
+"#;
+
+    #[test]
+    fn test_android_roundtrip() {
+        let document = AndroidStringsDocument;
+        let options = DocumentOptions::default();
+        let segments = document.extract(STRINGS.as_bytes(), &options).unwrap();
+        let texts: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Welcome, <b><x1/></b>! You're on <x2/>.<x3/>Stay alert.",
+                "Don't panic",
+                "Private",
+                "Sergeant",
+                "<x1/> round left",
+                "<x1/> rounds left",
+            ]
+        );
+
+        let mut translations = vec![
+            "Добро пожаловать, <b><x1/></b>! Вы на \"<x2/>\".<x3/>Будьте начеку.".to_string(),
+            "Без 'паники'".to_string(),
+            "Рядовой".to_string(),
+            "Сержант".to_string(),
+            "Остался <x1/> патрон".to_string(),
+            "Осталось <x1/> патронов".to_string(),
+        ];
+        let rebuilt = document
+            .rebuild(STRINGS.as_bytes(), &options, &translations)
+            .unwrap();
+        let rebuilt = String::from_utf8(rebuilt).unwrap();
+        assert!(rebuilt.contains(r#"<string name="app_name" translatable="false">Recon</string>"#));
+        assert!(rebuilt.contains(
+            r#"<string name="welcome">Добро пожаловать, <b>%1$s</b>! Вы на \"<xliff:g id="unit">%2$s</xliff:g>\".\nБудьте начеку.</string>"#
+        ));
+        assert!(rebuilt.contains(r#"<string name="quoted">"Без 'паники'"</string>"#));
+        assert!(rebuilt.contains(r#"<item quantity="other">Осталось %d патронов</item>"#));
+
+        translations[4] = "Остался один патрон".to_string();
+        let result = document.rebuild(STRINGS.as_bytes(), &options, &translations);
+        assert!(matches!(result, Err(DocumentErrors::SpecifiersChanged(_))));
+    }
+}
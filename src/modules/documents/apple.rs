@@ -0,0 +1,348 @@
+use std::ops::Range;
+
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::documents::ooxml;
+use crate::modules::documents::placeholders::{self, Protected};
+use crate::modules::formats::models::TextFormat;
+
+/// Apple `.strings` file: `"key" = "value";` pairs between comments, in
+/// UTF-8 or UTF-16 with a byte order mark. Values are translated with their
+/// format specifiers kept as `<xN/>` tags; the file keeps its encoding.
+pub struct AppleStringsDocument;
+
+impl DocumentFormat for AppleStringsDocument {
+    fn name(&self) -> &'static str {
+        "strings"
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        &["text/plain"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["strings"]
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let (text, _) = decode(data)?;
+        Ok(pending(&parse(&text)?)
+            .into_iter()
+            .map(|(_, protected)| DocumentSegment::new(protected.text, TextFormat::Xml))
+            .collect())
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let (text, encoding) = decode(data)?;
+        let entries = parse(&text)?;
+        let pending = pending(&entries);
+        if pending.len() != translations.len() {
+            return Err(DocumentErrors::Malformed(format!(
+                "{} translations for {} strings",
+                translations.len(),
+                pending.len()
+            )));
+        }
+
+        let mut replacements = Vec::with_capacity(pending.len());
+        for ((entry, protected), translation) in pending.into_iter().zip(translations) {
+            let value = placeholders::restore(translation, &protected.tokens);
+            placeholders::check_specifiers(&entry.key, &entry.value, &value)?;
+            replacements.push((entry.range.clone(), format!("\"{}\"", escape(&value))));
+        }
+        Ok(encode(&ooxml::replace(&text, &replacements), encoding))
+    }
+}
+
+/// Key-value pair with the range of the quoted value.
+struct Entry {
+    key: String,
+    value: String,
+    range: Range<usize>,
+}
+
+fn pending(entries: &[Entry]) -> Vec<(&Entry, Protected)> {
+    entries
+        .iter()
+        .map(|entry| (entry, placeholders::protect(&entry.value)))
+        .filter(|(_, protected)| protected.has_text())
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+enum Encoding {
+    Utf8 { bom: bool },
+    Utf16Le,
+    Utf16Be,
+}
+
+fn decode(data: &[u8]) -> DocumentResult<(String, Encoding)> {
+    let utf16 = |data: &[u8], decode: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|pair| decode([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16(&units)
+            .map_err(|err| DocumentErrors::Malformed(format!("strings file is not UTF-16: {err}")))
+    };
+    match data {
+        [0xff, 0xfe, rest @ ..] => Ok((utf16(rest, u16::from_le_bytes)?, Encoding::Utf16Le)),
+        [0xfe, 0xff, rest @ ..] => Ok((utf16(rest, u16::from_be_bytes)?, Encoding::Utf16Be)),
+        _ => {
+            let bom = data.starts_with(b"\xef\xbb\xbf");
+            let text = std::str::from_utf8(if bom { &data[3..] } else { data }).map_err(|err| {
+                DocumentErrors::Malformed(format!("strings file is not UTF-8: {err}"))
+            })?;
+            Ok((text.to_owned(), Encoding::Utf8 { bom }))
+        }
+    }
+}
+
+fn encode(text: &str, encoding: Encoding) -> Vec<u8> {
+    match encoding {
+        Encoding::Utf8 { bom } => {
+            let bom: &[u8] = if bom { b"\xef\xbb\xbf" } else { b"" };
+            [bom, text.as_bytes()].concat()
+        }
+        Encoding::Utf16Le => [0xff, 0xfe]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect(),
+        Encoding::Utf16Be => [0xfe, 0xff]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect(),
+    }
+}
+
+/// Quoted value content with `\"`, `\\`, `\n`, `\t` and `\r` written back.
+fn escape(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            '\r' => output.push_str("\\r"),
+            _ => output.push(ch),
+        }
+    }
+    output
+}
+
+fn parse(text: &str) -> DocumentResult<Vec<Entry>> {
+    let mut parser = Parser { text, position: 0 };
+    let mut entries = Vec::new();
+    loop {
+        parser.skip_trivia()?;
+        if parser.peek().is_none() {
+            return Ok(entries);
+        }
+        let (key, _) = parser.token()?;
+        parser.skip_trivia()?;
+        match parser.next() {
+            Some('=') => {}
+            Some(';') => continue,
+            _ => return Err(parser.error("expected `=`")),
+        }
+        parser.skip_trivia()?;
+        let start = parser.position;
+        let (value, quoted) = parser.token()?;
+        let range = start..parser.position;
+        parser.skip_trivia()?;
+        if parser.next() != Some(';') {
+            return Err(parser.error("expected `;`"));
+        }
+        if quoted {
+            entries.push(Entry { key, value, range });
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// Quoted string or unquoted word, and whether it was quoted.
+    fn token(&mut self) -> DocumentResult<(String, bool)> {
+        if self.peek() == Some('"') {
+            return Ok((self.string()?, true));
+        }
+        let start = self.position;
+        while let Some(ch) = self.peek()
+            && (ch.is_alphanumeric() || "_.-$:/".contains(ch))
+        {
+            self.position += ch.len_utf8();
+        }
+        if self.position == start {
+            return Err(self.error("expected a string"));
+        }
+        Ok((self.text[start..self.position].to_owned(), false))
+    }
+
+    fn string(&mut self) -> DocumentResult<String> {
+        self.position += 1;
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some('U' | 'u') => {
+                        let mut units = vec![self.code_unit()?];
+                        // Characters outside the BMP are written as two
+                        // escapes, the high surrogate first.
+                        let rest = &self.text[self.position..];
+                        if (0xD800..0xDC00).contains(&units[0])
+                            && (rest.starts_with("\\U") || rest.starts_with("\\u"))
+                        {
+                            self.position += 2;
+                            units.push(self.code_unit()?);
+                        }
+                        let decoded = char::decode_utf16(units)
+                            .collect::<Result<String, _>>()
+                            .map_err(|_| self.error("invalid `\\U` escape"))?;
+                        value.push_str(&decoded);
+                    }
+                    Some(escaped) => value.push(escaped),
+                    None => return Err(self.error("unterminated string")),
+                },
+                Some(ch) => value.push(ch),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    /// UTF-16 code unit of the four hex digits of a `\U` escape.
+    fn code_unit(&mut self) -> DocumentResult<u16> {
+        let unit = self
+            .text
+            .get(self.position..self.position + 4)
+            .filter(|digits| digits.chars().all(|ch| ch.is_ascii_hexdigit()))
+            .and_then(|digits| u16::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| self.error("invalid `\\U` escape"))?;
+        self.position += 4;
+        Ok(unit)
+    }
+
+    /// Skips whitespace and `/* */` and `//` comments.
+    fn skip_trivia(&mut self) -> DocumentResult<()> {
+        loop {
+            let rest = &self.text[self.position..];
+            let trimmed = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+            if trimmed.starts_with("/*") {
+                let end = trimmed
+                    .find("*/")
+                    .ok_or_else(|| self.error("unterminated comment"))?;
+                self.position += end + 2;
+            } else if trimmed.starts_with("//") {
+                self.position += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.position += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn error(&self, message: &str) -> DocumentErrors {
+        DocumentErrors::Malformed(format!(
+            "invalid strings file at byte {}: {message}",
+            self.position
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test_apple {
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::apple::{self, AppleStringsDocument};
+    use crate::modules::documents::errors::DocumentErrors;
+    use crate::modules::documents::models::DocumentOptions;
+
+    const STRINGS: &str = r#"/* Greeting on the main screen */
+"greeting" = "Hello, %@!";
+// Counter
+"rounds" = "%1$ld rounds of %2$@";
+"quote" = "Say \"go\"\nthen wait";
+"code" = "%d";
+"#;
+
+    #[test]
+    fn test_strings_roundtrip() {
+        let document = AppleStringsDocument;
+        let options = DocumentOptions::default();
+        let data: Vec<u8> = [0xff, 0xfe]
+            .into_iter()
+            .chain(STRINGS.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let segments = document.extract(&data, &options).unwrap();
+        let texts: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Hello, <x1/>!",
+                "<x1/> rounds of <x2/>",
+                "Say \"go\"\nthen wait"
+            ]
+        );
+
+        let mut translations = vec![
+            "Привет, <x1/>!".to_string(),
+            "<x1/> выстрелов из <x2/>".to_string(),
+            "Скажите «вперёд»\nи ждите".to_string(),
+        ];
+        let rebuilt = document.rebuild(&data, &options, &translations).unwrap();
+        assert_eq!(&rebuilt[..2], &[0xff, 0xfe]);
+        let units: Vec<u16> = rebuilt[2..]
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        assert_eq!(
+            String::from_utf16(&units).unwrap(),
+            r#"/* Greeting on the main screen */
+"greeting" = "Привет, %@!";
+// Counter
+"rounds" = "%1$ld выстрелов из %2$@";
+"quote" = "Скажите «вперёд»\nи ждите";
+"code" = "%d";
+"#
+        );
+
+        let entries = apple::parse(r#""smile" = "Hi \UD83D\UDE00 \U00e9";"#).unwrap();
+        assert_eq!(entries[0].value, "Hi 😀 é");
+        assert!(apple::parse(r#""lone" = "\UD83D";"#).is_err());
+        assert!(apple::parse(r#""short" = "\U12";"#).is_err());
+
+        translations[1] = "<x2/>: <x1/> выстрелов <x1/>".to_string();
+        let result = document.rebuild(&data, &options, &translations);
+        assert!(matches!(result, Err(DocumentErrors::SpecifiersChanged(_))));
+    }
+}
@@ -0,0 +1,12 @@
+use getset::CopyGetters;
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct DocumentsConfig {
+    /// Largest accepted upload in bytes; the upload is rejected as soon as it grows past it.
+    max_file_size: usize,
+    /// Translated text of a slide text box longer than the source by this
+    /// factor is reported as likely to overflow.
+    overflow_ratio: f64,
+}
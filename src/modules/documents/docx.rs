@@ -0,0 +1,174 @@
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::DocumentResult;
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::documents::ooxml::{self, Vocabulary};
+use crate::modules::documents::package::Package;
+
+const WORDPROCESSING: Vocabulary = Vocabulary {
+    paragraph: "w:p",
+    run: "w:r",
+    run_properties: "w:rPr",
+    text: "w:t",
+    tab: Some("w:tab"),
+    line_break: Some("w:br"),
+    ignored: &["w:lastRenderedPageBreak"],
+    containers: &["w:hyperlink", "w:ins", "w:smartTag", "w:customXml"],
+    preserve_space: true,
+};
+
+/// Word document. Paragraphs of the body, headers, footers, footnotes,
+/// endnotes and comments are translated; runs with the same formatting are
+/// merged and formatting changes inside a paragraph go to the model as tags.
+pub struct DocxDocument;
+
+impl DocumentFormat for DocxDocument {
+    fn name(&self) -> &'static str {
+        "docx"
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        &["application/vnd.openxmlformats-officedocument.wordprocessingml.document"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["docx"]
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let mut package = Package::open(data)?;
+        let parts = parts(&package);
+        ooxml::extract(&mut package, &parts, &WORDPROCESSING)
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let mut package = Package::open(data)?;
+        let parts = parts(&package);
+        let rewritten = ooxml::rebuild(&mut package, &parts, &WORDPROCESSING, translations)?;
+        package.rewrite(&rewritten)
+    }
+}
+
+/// Parts with text, body first, then headers, footers, notes and comments.
+fn parts(package: &Package) -> Vec<String> {
+    let rank = |name: &str| {
+        let file = name.strip_prefix("word/")?.strip_suffix(".xml")?;
+        let numbered = |prefix: &str| {
+            file.strip_prefix(prefix)
+                .is_some_and(|number| number.chars().all(|ch| ch.is_ascii_digit()))
+        };
+        match file {
+            "document" => Some(0),
+            _ if numbered("header") => Some(1),
+            _ if numbered("footer") => Some(2),
+            "footnotes" => Some(3),
+            "endnotes" => Some(4),
+            "comments" => Some(5),
+            _ => None,
+        }
+    };
+    let mut parts: Vec<(u8, String)> = package
+        .names()
+        .into_iter()
+        .filter_map(|name| Some((rank(&name)?, name)))
+        .collect();
+    parts.sort_by(|(rank, name), (other_rank, other)| {
+        (rank, name.len(), name).cmp(&(other_rank, other.len(), other))
+    });
+    parts.into_iter().map(|(_, name)| name).collect()
+}
+
+#[cfg(test)]
+mod test_docx {
+    use std::io::{Cursor, Read, Write};
+
+    use zip::write::SimpleFileOptions;
+    use zip::{ZipArchive, ZipWriter};
+
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::docx::DocxDocument;
+    use crate::modules::documents::models::DocumentOptions;
+
+    const DOCUMENT: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>
+<w:p><w:pPr><w:pStyle w:val="Heading1"/><w:numPr><w:ilvl w:val="0"/><w:numId w:val="1"/></w:numPr></w:pPr><w:bookmarkStart w:id="0" w:name="top"/><w:r><w:t>Check the </w:t></w:r><w:r><w:t>radio</w:t></w:r><w:r><w:rPr><w:b/></w:rPr><w:t xml:space="preserve"> before </w:t></w:r><w:hyperlink r:id="rId5"><w:r><w:t>dawn</w:t></w:r></w:hyperlink><w:r><w:t>.</w:t></w:r><w:bookmarkEnd w:id="0"/></w:p>
+<w:tbl><w:tr><w:tc><w:p><w:r><w:t>Water &amp; food</w:t></w:r></w:p></w:tc></w:tr></w:tbl>
+<w:p><w:r><w:drawing/></w:r></w:p>
+</w:body></w:document>"#;
+
+    const HEADER: &str = r#"<w:hdr xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:p><w:r><w:t>Field manual</w:t></w:r></w:p></w:hdr>"#;
+
+    fn package() -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in [
+            ("[Content_Types].xml", "<Types/>"),
+            ("word/header1.xml", HEADER),
+            ("word/document.xml", DOCUMENT),
+            ("word/styles.xml", "<w:styles/>"),
+        ] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_docx_roundtrip() {
+        let data = package();
+        let segments = DocxDocument
+            .extract(&data, &DocumentOptions::default())
+            .unwrap();
+        let fragments: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(
+            fragments,
+            vec![
+                "<p>Check the radio<g1> before </g1><l4>dawn</l4>.</p>",
+                "<p>Water &amp; food</p>",
+                "<p>Field manual</p>",
+            ]
+        );
+
+        let translations = vec![
+            "<p>Проверьте рацию<g1> до </g1><l4>рассвета</l4>.</p>".to_owned(),
+            "<p>Вода и еда</p>".to_owned(),
+            "<p>Полевой устав</p>".to_owned(),
+        ];
+        let rebuilt = DocxDocument
+            .rebuild(&data, &DocumentOptions::default(), &translations)
+            .unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(rebuilt.as_slice())).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert_eq!(names.len(), 4);
+        let mut document = String::new();
+        archive
+            .by_name("word/document.xml")
+            .unwrap()
+            .read_to_string(&mut document)
+            .unwrap();
+        assert!(document.contains(
+            "<w:p><w:pPr><w:pStyle w:val=\"Heading1\"/><w:numPr><w:ilvl w:val=\"0\"/><w:numId w:val=\"1\"/></w:numPr></w:pPr>\
+             <w:bookmarkStart w:id=\"0\" w:name=\"top\"/>\
+             <w:r><w:t xml:space=\"preserve\">Проверьте рацию</w:t></w:r>\
+             <w:r><w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\"> до </w:t></w:r>\
+             <w:hyperlink r:id=\"rId5\"><w:r><w:t xml:space=\"preserve\">рассвета</w:t></w:r></w:hyperlink>\
+             <w:r><w:t xml:space=\"preserve\">.</w:t></w:r><w:bookmarkEnd w:id=\"0\"/></w:p>"
+        ));
+        assert!(document.contains(
+            "<w:tc><w:p><w:r><w:t xml:space=\"preserve\">Вода и еда</w:t></w:r></w:p></w:tc>"
+        ));
+        assert!(document.contains("<w:p><w:r><w:drawing/></w:r></w:p>"));
+    }
+}
@@ -0,0 +1,23 @@
+use thiserror::Error;
+
+pub type DocumentResult<T> = Result<T, DocumentErrors>;
+
+#[derive(Debug, Error)]
+pub enum DocumentErrors {
+    #[error("Unsupported document type: {0}")]
+    Unsupported(String),
+    #[error("Document is larger than {0} bytes")]
+    TooLarge(usize),
+    #[error("Malformed document: {0}")]
+    Malformed(String),
+    #[error("Invalid document option: {0}")]
+    InvalidOption(String),
+    #[error("Format specifiers changed in translation: {0}")]
+    SpecifiersChanged(String),
+}
+
+impl From<zip::result::ZipError> for DocumentErrors {
+    fn from(err: zip::result::ZipError) -> Self {
+        DocumentErrors::Malformed(err.to_string())
+    }
+}
@@ -0,0 +1,482 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use quick_xml::escape::{minimal_escape, unescape};
+
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::documents::ooxml;
+use crate::modules::documents::placeholders::next_placeholder;
+use crate::modules::formats::models::TextFormat;
+
+/// Indentation of continuation lines when the pattern has none to copy.
+const DEFAULT_INDENT: &str = "    ";
+
+/// Characters that cannot start a continuation line of a pattern.
+const SPECIAL_LINE_STARTS: [char; 3] = ['[', '*', '.'];
+
+/// Fluent resource. Values and attributes of messages and terms are
+/// translated as XML with placeables as `<xN/>` tags; every variant of a
+/// select expression is translated on its own. Fluent has no translation
+/// state, so the whole file is translated.
+pub struct FluentDocument;
+
+impl DocumentFormat for FluentDocument {
+    fn name(&self) -> &'static str {
+        "fluent"
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        &["text/x-fluent"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ftl"]
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let text = decode(data)?;
+        let mut segments = Vec::new();
+        for pattern in patterns(text)? {
+            pattern.segments(text, &mut segments);
+        }
+        Ok(segments
+            .into_iter()
+            .map(|(_, segment)| DocumentSegment::new(segment, TextFormat::Xml))
+            .collect())
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let text = decode(data)?;
+        let patterns = patterns(text)?;
+        let mut segments = Vec::new();
+        for pattern in &patterns {
+            pattern.segments(text, &mut segments);
+        }
+        if segments.len() != translations.len() {
+            return Err(DocumentErrors::Malformed(format!(
+                "{} translations for {} patterns",
+                translations.len(),
+                segments.len()
+            )));
+        }
+        let translated: HashMap<usize, &str> = segments
+            .iter()
+            .map(|(start, _)| *start)
+            .zip(translations.iter().map(String::as_str))
+            .collect();
+
+        let replacements: Vec<(Range<usize>, String)> = patterns
+            .iter()
+            .map(|pattern| (pattern.range.clone(), pattern.render(text, &translated)))
+            .collect();
+        Ok(ooxml::replace(text, &replacements).into_bytes())
+    }
+}
+
+/// Value of a message, term, attribute or variant.
+struct Pattern {
+    range: Range<usize>,
+    parts: Vec<Part>,
+}
+
+enum Part {
+    Text(Range<usize>),
+    /// `{ … }`, with the patterns of its variants when it is a select expression.
+    Placeable {
+        range: Range<usize>,
+        variants: Vec<Pattern>,
+    },
+}
+
+impl Pattern {
+    fn parse(text: &str, range: Range<usize>) -> DocumentResult<Self> {
+        let mut parts = Vec::new();
+        let mut position = range.start;
+        let mut text_start = range.start;
+        while position < range.end {
+            if text.as_bytes()[position] != b'{' {
+                position += 1;
+                continue;
+            }
+            if text_start < position {
+                parts.push(Part::Text(text_start..position));
+            }
+            let end = closing_brace(text, position, range.end)?;
+            parts.push(Part::Placeable {
+                range: position..end,
+                variants: variants(text, position + 1..end - 1)?,
+            });
+            position = end;
+            text_start = end;
+        }
+        if text_start < range.end {
+            parts.push(Part::Text(text_start..range.end));
+        }
+        Ok(Self { range, parts })
+    }
+
+    fn has_text(&self, text: &str) -> bool {
+        self.parts.iter().any(|part| match part {
+            Part::Text(range) => !text[range.clone()].trim().is_empty(),
+            Part::Placeable { .. } => false,
+        })
+    }
+
+    /// Text of the pattern as XML, without the whitespace around it.
+    fn segment(&self, text: &str) -> String {
+        let mut segment = String::new();
+        let mut placeables = 0;
+        for part in &self.parts {
+            match part {
+                Part::Text(range) => {
+                    let mut lines = text[range.clone()].split('\n');
+                    if let Some(first) = lines.next() {
+                        segment.push_str(&minimal_escape(first.trim_end_matches('\r')));
+                    }
+                    for line in lines {
+                        segment.push('\n');
+                        segment.push_str(&minimal_escape(line.trim_start().trim_end_matches('\r')));
+                    }
+                }
+                Part::Placeable { .. } => {
+                    placeables += 1;
+                    segment.push_str(&format!("<x{placeables}/>"));
+                }
+            }
+        }
+        segment.trim().to_owned()
+    }
+
+    /// Translatable patterns in document order, keyed by their start: this
+    /// pattern first, then the variants of its select expressions.
+    fn segments(&self, text: &str, segments: &mut Vec<(usize, String)>) {
+        if self.has_text(text) {
+            segments.push((self.range.start, self.segment(text)));
+        }
+        for part in &self.parts {
+            if let Part::Placeable { variants, .. } = part {
+                for variant in variants {
+                    variant.segments(text, segments);
+                }
+            }
+        }
+    }
+
+    fn render(&self, text: &str, translated: &HashMap<usize, &str>) -> String {
+        let original = &text[self.range.clone()];
+        let Some(translation) = translated.get(&self.range.start) else {
+            let mut output = String::new();
+            let mut position = self.range.start;
+            for part in &self.parts {
+                if let Part::Placeable { range, .. } = part {
+                    output.push_str(&text[position..range.start]);
+                    output.push_str(&part.render(text, translated));
+                    position = range.end;
+                }
+            }
+            output.push_str(&text[position..self.range.end]);
+            return output;
+        };
+
+        let leading = &original[..original.len() - original.trim_start().len()];
+        let trailing = &original[original.trim_end().len()..];
+        let newline = if original.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let indent = original
+            .split('\n')
+            .skip(1)
+            .map(|line| &line[..line.len() - line.trim_start().len()])
+            .find(|indent| !indent.is_empty())
+            .unwrap_or(DEFAULT_INDENT);
+        let placeables: Vec<&Part> = self
+            .parts
+            .iter()
+            .filter(|part| matches!(part, Part::Placeable { .. }))
+            .collect();
+
+        let mut output = leading.to_owned();
+        let mut line_start = leading.contains('\n');
+        let mut rest = *translation;
+        while !rest.is_empty() {
+            let (piece, placeable, next) = next_placeholder(rest);
+            let piece = unescape(piece).unwrap_or(Cow::Borrowed(piece));
+            for char in piece.chars() {
+                match char {
+                    '\n' => {
+                        output.push_str(newline);
+                        output.push_str(indent);
+                        line_start = true;
+                        continue;
+                    }
+                    '\r' => continue,
+                    '{' | '}' => output.push_str(&format!("{{\"{char}\"}}")),
+                    char if line_start && SPECIAL_LINE_STARTS.contains(&char) => {
+                        output.push_str(&format!("{{\"{char}\"}}"))
+                    }
+                    char => output.push(char),
+                }
+                line_start = false;
+            }
+            if let Some(part) = placeable.and_then(|index| placeables.get(index)) {
+                output.push_str(&part.render(text, translated));
+                line_start = false;
+            }
+            rest = next;
+        }
+        output.push_str(trailing);
+        output
+    }
+}
+
+impl Part {
+    fn render(&self, text: &str, translated: &HashMap<usize, &str>) -> String {
+        match self {
+            Part::Text(range) => text[range.clone()].to_owned(),
+            Part::Placeable { range, variants } => {
+                let mut output = String::new();
+                let mut position = range.start;
+                for variant in variants {
+                    output.push_str(&text[position..variant.range.start]);
+                    output.push_str(&variant.render(text, translated));
+                    position = variant.range.end;
+                }
+                output.push_str(&text[position..range.end]);
+                output
+            }
+        }
+    }
+}
+
+/// End of the placeable opening at `start`, past its closing brace.
+fn closing_brace(text: &str, start: usize, limit: usize) -> DocumentResult<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut position = start;
+    let mut in_string = false;
+    while position < limit {
+        match bytes[position] {
+            b'\\' if in_string => position += 1,
+            b'"' => in_string = !in_string,
+            b'{' if !in_string => depth += 1,
+            b'}' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(position + 1);
+                }
+            }
+            _ => {}
+        }
+        position += 1;
+    }
+    Err(DocumentErrors::Malformed(format!(
+        "unclosed placeable at byte {start}"
+    )))
+}
+
+/// Variant patterns of a select expression, given the inside of its placeable.
+fn variants(text: &str, range: Range<usize>) -> DocumentResult<Vec<Pattern>> {
+    let inside = &text[range.clone()];
+    let Some(arrow) = top_level_arrow(inside) else {
+        return Ok(Vec::new());
+    };
+
+    // Keys start lines: `[key]` or `*[key]` after indentation.
+    let mut keys = Vec::new();
+    let mut offset = range.start + arrow + 2;
+    let mut depth = 0;
+    for line in text[offset..range.end].split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let key_start = offset + line.len() - trimmed.len();
+        if depth == 0
+            && let Some(key) = trimmed
+                .strip_prefix('*')
+                .unwrap_or(trimmed)
+                .strip_prefix('[')
+            && let Some(close) = key.find(']')
+        {
+            let value_start = key_start + (trimmed.len() - key.len()) + close + 1;
+            keys.push((key_start, value_start));
+        }
+        depth += line.matches('{').count() as i32 - line.matches('}').count() as i32;
+        offset += line.len();
+    }
+
+    let mut patterns = Vec::new();
+    for (index, (_, value_start)) in keys.iter().enumerate() {
+        let end = keys.get(index + 1).map_or(range.end, |(next, _)| *next);
+        let value = &text[*value_start..end];
+        let value_end = value_start + value.trim_end().len();
+        patterns.push(Pattern::parse(text, *value_start..value_end)?);
+    }
+    Ok(patterns)
+}
+
+/// Position of `->` outside nested placeables and string literals.
+fn top_level_arrow(inside: &str) -> Option<usize> {
+    let bytes = inside.as_bytes();
+    let (mut depth, mut in_string) = (0, false);
+    for (position, byte) in bytes.iter().enumerate() {
+        match byte {
+            b'"' => in_string = !in_string,
+            b'{' if !in_string => depth += 1,
+            b'}' if !in_string => depth -= 1,
+            b'-' if !in_string && depth == 0 && bytes.get(position + 1) == Some(&b'>') => {
+                return Some(position);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Values and attributes of the messages and terms, in file order.
+fn patterns(text: &str) -> DocumentResult<Vec<Pattern>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut current: Option<Range<usize>> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let start = offset;
+        let end = offset + content.len();
+        offset += line.len();
+        if content.trim().is_empty() {
+            continue;
+        }
+
+        let indented = content.starts_with([' ', '\t']);
+        if !indented {
+            ranges.extend(current.take());
+            if let Some(equals) = entry_value(content, false) {
+                current = Some(start + equals..end);
+            }
+            continue;
+        }
+        let Some(pattern) = current.as_mut() else {
+            continue;
+        };
+        let trimmed = content.trim_start();
+        let inside_placeable =
+            text[pattern.clone()].matches('{').count() > text[pattern.clone()].matches('}').count();
+        match entry_value(trimmed, true) {
+            Some(equals) if !inside_placeable => {
+                ranges.push(pattern.clone());
+                let value = end - trimmed.len() + equals;
+                current = Some(value..end);
+            }
+            _ => pattern.end = end,
+        }
+    }
+    ranges.extend(current);
+
+    ranges
+        .into_iter()
+        .filter(|range| !text[range.clone()].trim().is_empty())
+        .map(|range| Pattern::parse(text, range))
+        .collect()
+}
+
+/// Offset just past the `=` of `id =`, `-term =` or, for attributes, `.name =`.
+fn entry_value(line: &str, attribute: bool) -> Option<usize> {
+    let name = if attribute {
+        line.strip_prefix('.')?
+    } else {
+        line.strip_prefix('-').unwrap_or(line)
+    };
+    if !name.starts_with(|char: char| char.is_ascii_alphabetic()) {
+        return None;
+    }
+    let rest = name.trim_start_matches(|char: char| {
+        char.is_ascii_alphanumeric() || char == '_' || char == '-'
+    });
+    let equals = rest.trim_start().strip_prefix('=')?;
+    Some(line.len() - equals.len())
+}
+
+fn decode(data: &[u8]) -> DocumentResult<&str> {
+    std::str::from_utf8(data)
+        .map_err(|err| DocumentErrors::Malformed(format!("Fluent resource is not UTF-8: {err}")))
+}
+
+#[cfg(test)]
+mod test_fluent {
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::fluent::FluentDocument;
+    use crate::modules::documents::models::DocumentOptions;
+
+    const RESOURCE: &str = "# Menu
+open = Open file
+    .title = Open a file from disk
+emails = { $count ->
+        [one] You have one email.
+       *[other] You have { $count } emails.
+    }
+welcome = Welcome, { $name }, to { -brand }!
+help =
+    First line
+    second line
+";
+
+    #[test]
+    fn test_fluent_roundtrip() {
+        let document = FluentDocument;
+        let options = DocumentOptions::default();
+        let segments = document.extract(RESOURCE.as_bytes(), &options).unwrap();
+        let texts: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Open file",
+                "Open a file from disk",
+                "You have one email.",
+                "You have <x1/> emails.",
+                "Welcome, <x1/>, to <x2/>!",
+                "First line\nsecond line",
+            ]
+        );
+
+        let translations = vec![
+            "Открыть файл".to_string(),
+            "Открыть файл с диска".to_string(),
+            "У вас одно письмо.".to_string(),
+            "У вас <x1/> писем.".to_string(),
+            "<x2/> приветствует вас, <x1/>!".to_string(),
+            "Первая строка\n[вторая] строка {1}".to_string(),
+        ];
+        let rebuilt = document
+            .rebuild(RESOURCE.as_bytes(), &options, &translations)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(rebuilt).unwrap(),
+            "# Menu
+open = Открыть файл
+    .title = Открыть файл с диска
+emails = { $count ->
+        [one] У вас одно письмо.
+       *[other] У вас { $count } писем.
+    }
+welcome = { -brand } приветствует вас, { $name }!
+help =
+    Первая строка
+    {\"[\"}вторая] строка {\"{\"}1{\"}\"}
+"
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use std::ops::Range;
+
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::resource::{Leaf, Resource, ScalarStyle};
+
+/// String leaves of a JSON document with their byte ranges.
+pub fn parse(text: &str) -> DocumentResult<Resource> {
+    let mut parser = Parser {
+        text,
+        position: text.len() - text.trim_start_matches('\u{feff}').len(),
+        leaves: Vec::new(),
+        top_keys: Vec::new(),
+    };
+    parser.value(&mut Vec::new())?;
+    parser.skip_whitespace();
+    if parser.position < text.len() {
+        return Err(parser.error("trailing characters"));
+    }
+
+    let root = match parser.top_keys.as_slice() {
+        [(key, range, true)] => Some((key.clone(), range.clone())),
+        _ => None,
+    };
+    Ok(Resource {
+        leaves: parser.leaves,
+        root,
+    })
+}
+
+/// JSON string literal of `value`.
+pub fn encode(value: &str) -> String {
+    serde_json::Value::String(value.to_owned()).to_string()
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    leaves: Vec<Leaf>,
+    /// Keys of the top-level object, inside their quotes, and whether their value is an object.
+    top_keys: Vec<(String, Range<usize>, bool)>,
+}
+
+impl Parser<'_> {
+    fn value(&mut self, path: &mut Vec<String>) -> DocumentResult<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(path),
+            Some(b'[') => self.array(path),
+            Some(b'"') => {
+                let (value, range) = self.string()?;
+                self.leaves.push(Leaf {
+                    path: path.clone(),
+                    value,
+                    range,
+                    style: ScalarStyle::Json,
+                });
+                Ok(())
+            }
+            Some(_) => {
+                let start = self.position;
+                while let Some(byte) = self.peek()
+                    && !matches!(byte, b',' | b']' | b'}')
+                    && !byte.is_ascii_whitespace()
+                {
+                    self.position += 1;
+                }
+                if self.position == start {
+                    return Err(self.error("expected a value"));
+                }
+                Ok(())
+            }
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    fn object(&mut self, path: &mut Vec<String>) -> DocumentResult<()> {
+        self.position += 1;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                Some(b'"') => {}
+                _ => return Err(self.error("expected a key")),
+            }
+            let (key, range) = self.string()?;
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                return Err(self.error("expected `:`"));
+            }
+            self.position += 1;
+            if path.is_empty() {
+                self.skip_whitespace();
+                let nested = self.peek() == Some(b'{');
+                self.top_keys
+                    .push((key.clone(), range.start + 1..range.end - 1, nested));
+            }
+            path.push(key);
+            self.value(path)?;
+            path.pop();
+            if !self.separator(b'}')? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn array(&mut self, path: &mut Vec<String>) -> DocumentResult<()> {
+        self.position += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(());
+        }
+        for index in 0.. {
+            path.push(index.to_string());
+            self.value(path)?;
+            path.pop();
+            if !self.separator(b']')? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Consumes a `,` (true) or the closing bracket (false).
+    fn separator(&mut self, close: u8) -> DocumentResult<bool> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b',') => {
+                self.position += 1;
+                Ok(true)
+            }
+            Some(byte) if byte == close => {
+                self.position += 1;
+                Ok(false)
+            }
+            _ => Err(self.error(&format!("expected `,` or `{}`", close as char))),
+        }
+    }
+
+    fn string(&mut self) -> DocumentResult<(String, Range<usize>)> {
+        let start = self.position;
+        self.position += 1;
+        loop {
+            match self.peek() {
+                Some(b'\\') => self.position += 2,
+                Some(b'"') => break,
+                Some(_) => self.position += 1,
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+        self.position += 1;
+        let range = start..self.position;
+        let value = serde_json::from_str(&self.text[range.clone()])
+            .map_err(|err| DocumentErrors::Malformed(format!("invalid JSON string: {err}")))?;
+        Ok((value, range))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|byte| byte.is_ascii_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn error(&self, message: &str) -> DocumentErrors {
+        DocumentErrors::Malformed(format!("invalid JSON at byte {}: {message}", self.position))
+    }
+}
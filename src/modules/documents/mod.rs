@@ -0,0 +1,268 @@
+pub mod android;
+pub mod apple;
+pub mod config;
+pub mod docx;
+pub mod errors;
+pub mod fluent;
+pub mod json;
+pub mod models;
+pub mod ods;
+pub mod ooxml;
+pub mod package;
+pub mod placeholders;
+pub mod po;
+pub mod pptx;
+pub mod resource;
+pub mod spreadsheet;
+pub mod subtitles;
+pub mod text;
+pub mod xcstrings;
+pub mod xliff;
+pub mod xlsx;
+pub mod yaml;
+
+use std::path::Path;
+
+use crate::modules::documents::android::AndroidStringsDocument;
+use crate::modules::documents::apple::AppleStringsDocument;
+use crate::modules::documents::config::DocumentsConfig;
+use crate::modules::documents::docx::DocxDocument;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::fluent::FluentDocument;
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::documents::ods::OdsDocument;
+use crate::modules::documents::po::PoDocument;
+use crate::modules::documents::pptx::PptxDocument;
+use crate::modules::documents::resource::{ResourceDocument, ResourceSyntax};
+use crate::modules::documents::subtitles::{SubtitleDocument, SubtitleFormat};
+use crate::modules::documents::text::TextDocument;
+use crate::modules::documents::xcstrings::XcstringsDocument;
+use crate::modules::documents::xliff::XliffDocument;
+use crate::modules::documents::xlsx::XlsxDocument;
+use crate::modules::formats::models::TextFormat;
+
+/// MIME type clients send when they do not know better; dispatch falls back to the extension.
+const GENERIC_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// File type the document endpoint can translate. The file is parsed twice:
+/// once to extract the segments and once to put their translations back.
+pub trait DocumentFormat: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// MIME types of the format; the first one is used for the response when the upload has none.
+    fn content_types(&self) -> &'static [&'static str];
+
+    /// File name extensions of the format, lowercase and without the dot.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Whole file names, lowercase, that identify the format whatever the
+    /// MIME type, such as Android `strings.xml`.
+    fn file_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Translatable segments in document order.
+    fn extract(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>>;
+
+    /// The document with its segments replaced by `translations`, given in
+    /// the order of [`extract`](DocumentFormat::extract) with the same options.
+    fn rebuild(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>>;
+
+    /// Problems of the translated document a reviewer should look at, such
+    /// as text likely to overflow its box. Translations are given as for
+    /// [`rebuild`](DocumentFormat::rebuild).
+    fn warnings(
+        &self,
+        _data: &[u8],
+        _options: &DocumentOptions,
+        _translations: &[String],
+    ) -> DocumentResult<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Document formats known to the service, looked up by the upload MIME type
+/// or file name extension.
+pub struct DocumentRegistry {
+    config: DocumentsConfig,
+    formats: Vec<Box<dyn DocumentFormat>>,
+}
+
+impl DocumentRegistry {
+    pub fn new(config: &DocumentsConfig) -> Self {
+        let formats: Vec<Box<dyn DocumentFormat>> = vec![
+            Box::new(TextDocument::new(TextFormat::Plain)),
+            Box::new(TextDocument::new(TextFormat::Markdown)),
+            Box::new(TextDocument::new(TextFormat::Html)),
+            Box::new(TextDocument::new(TextFormat::Xml)),
+            Box::new(DocxDocument),
+            Box::new(XlsxDocument),
+            Box::new(OdsDocument),
+            Box::new(PptxDocument::new(config.overflow_ratio())),
+            Box::new(SubtitleDocument::new(SubtitleFormat::Srt)),
+            Box::new(SubtitleDocument::new(SubtitleFormat::WebVtt)),
+            Box::new(PoDocument),
+            Box::new(XliffDocument),
+            Box::new(FluentDocument),
+            Box::new(ResourceDocument::new(ResourceSyntax::Json)),
+            Box::new(ResourceDocument::new(ResourceSyntax::Yaml)),
+            Box::new(AndroidStringsDocument),
+            Box::new(AppleStringsDocument),
+            Box::new(XcstringsDocument),
+        ];
+        DocumentRegistry {
+            config: config.to_owned(),
+            formats,
+        }
+    }
+
+    pub fn config(&self) -> &DocumentsConfig {
+        &self.config
+    }
+
+    pub fn formats(&self) -> impl Iterator<Item = &dyn DocumentFormat> {
+        self.formats.iter().map(Box::as_ref)
+    }
+
+    /// Format of an upload. A known file name wins over a specific MIME
+    /// type, which wins over the extension. Among formats sharing the MIME
+    /// type the one with the extension is taken.
+    pub fn find(
+        &self,
+        file_name: &str,
+        content_type: Option<&str>,
+    ) -> DocumentResult<&dyn DocumentFormat> {
+        let path = Path::new(file_name);
+        let base_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(str::to_ascii_lowercase);
+        if let Some(base_name) = &base_name
+            && let Some(format) = self
+                .formats()
+                .find(|format| format.file_names().contains(&base_name.as_str()))
+        {
+            return Ok(format);
+        }
+
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let has_extension = |format: &dyn DocumentFormat| {
+            extension
+                .as_deref()
+                .is_some_and(|extension| format.extensions().contains(&extension))
+        };
+
+        let mime = content_type
+            .map(|content_type| {
+                content_type
+                    .split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_ascii_lowercase()
+            })
+            .filter(|mime| !mime.is_empty() && mime != GENERIC_CONTENT_TYPE);
+        if let Some(mime) = &mime {
+            let candidates: Vec<&dyn DocumentFormat> = self
+                .formats()
+                .filter(|format| format.content_types().contains(&mime.as_str()))
+                .collect();
+            if let Some(format) = candidates
+                .iter()
+                .find(|format| has_extension(**format))
+                .or(candidates.first())
+            {
+                return Ok(*format);
+            }
+        }
+
+        if let Some(format) = self.formats().find(|format| has_extension(*format)) {
+            return Ok(format);
+        }
+
+        Err(DocumentErrors::Unsupported(format!(
+            "{file_name} ({})",
+            mime.as_deref().unwrap_or("no content type")
+        )))
+    }
+}
+
+/// Content type of the translated file: the uploaded one unless it is missing or generic.
+pub fn content_type(format: &dyn DocumentFormat, uploaded: Option<&str>) -> String {
+    match uploaded.map(str::trim) {
+        Some(uploaded) if !uploaded.is_empty() && !uploaded.starts_with(GENERIC_CONTENT_TYPE) => {
+            uploaded.to_owned()
+        }
+        _ => format.content_types()[0].to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod test_documents {
+    use crate::modules::documents::DocumentRegistry;
+    use crate::modules::documents::config::DocumentsConfig;
+    use crate::modules::documents::models::DocumentOptions;
+
+    #[test]
+    fn test_document_dispatch() {
+        let config: DocumentsConfig = serde_json::from_value(serde_json::json!({
+            "max_file_size": 1024,
+            "overflow_ratio": 1.3,
+        }))
+        .unwrap();
+        let registry = DocumentRegistry::new(&config);
+
+        let format = registry
+            .find("notes.md", Some("application/octet-stream"))
+            .unwrap();
+        assert_eq!(format.name(), "markdown");
+        let format = registry
+            .find("page.txt", Some("text/html; charset=utf-8"))
+            .unwrap();
+        assert_eq!(format.name(), "html");
+        assert!(registry.find("archive.rar", None).is_err());
+        assert_eq!(
+            registry
+                .find("res/values/strings.xml", Some("text/xml"))
+                .unwrap()
+                .name(),
+            "android"
+        );
+        assert_eq!(
+            registry
+                .find("Localizable.xcstrings", Some("application/json"))
+                .unwrap()
+                .name(),
+            "xcstrings"
+        );
+        assert_eq!(
+            registry
+                .find("Localizable.strings", Some("text/plain"))
+                .unwrap()
+                .name(),
+            "strings"
+        );
+
+        let data = "\u{feff}# Title\n".as_bytes();
+        let options = DocumentOptions::default();
+        let segments = format.extract(data, &options).unwrap();
+        assert_eq!(segments[0].text(), "# Title\n");
+        let rebuilt = format
+            .rebuild(data, &options, &["# Заголовок\n".to_owned()])
+            .unwrap();
+        assert_eq!(rebuilt, "\u{feff}# Заголовок\n".as_bytes());
+        assert!(format.extract(&[0xff, 0xfe], &options).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::modules::documents::spreadsheet::CellRange;
+use crate::modules::formats::models::TextFormat;
+
+/// Translatable part of a document, with the markup its text is in.
+#[derive(Getters, CopyGetters, Clone, Debug)]
+pub struct DocumentSegment {
+    #[getset(get = "pub")]
+    text: String,
+    #[getset(get_copy = "pub")]
+    format: TextFormat,
+}
+
+impl DocumentSegment {
+    pub fn new(text: String, format: TextFormat) -> Self {
+        Self { text, format }
+    }
+}
+
+/// Translated file, named and typed as the upload.
+#[derive(Getters, Clone, Debug)]
+#[getset(get = "pub")]
+pub struct TranslatedDocument {
+    file_name: String,
+    content_type: String,
+    data: Vec<u8>,
+    warnings: Vec<String>,
+}
+
+impl TranslatedDocument {
+    pub fn new(file_name: String, content_type: String, data: Vec<u8>) -> Self {
+        Self {
+            file_name,
+            content_type,
+            data,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn with_warnings(mut self, warnings: Vec<String>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+}
+
+/// Where translated spreadsheet cells are written.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpreadsheetOutput {
+    /// Translations replace the text of their cells.
+    #[default]
+    Overwrite,
+    /// Translations go to a new column right of the source column.
+    NewColumn,
+}
+
+/// Upload options of formats that need more than the file. Formats ignore
+/// the options that do not apply to them.
+#[derive(Getters, CopyGetters, Clone, Default, Debug)]
+pub struct DocumentOptions {
+    /// Sheets to translate; all of them when empty.
+    #[getset(get = "pub")]
+    sheets: Vec<String>,
+    /// Cells to translate; all of them when empty.
+    #[getset(get = "pub")]
+    ranges: Vec<CellRange>,
+    #[getset(get_copy = "pub")]
+    output: SpreadsheetOutput,
+    /// Longest subtitle line in characters.
+    #[getset(get_copy = "pub")]
+    max_line_chars: Option<usize>,
+    /// Highest subtitle reading speed in characters per second.
+    #[getset(get_copy = "pub")]
+    max_chars_per_second: Option<f64>,
+    #[getset(get = "pub")]
+    source_language: String,
+    #[getset(get = "pub")]
+    target_language: String,
+    /// Earlier translation of the file; formats that support it only fill what it lacks.
+    #[getset(get = "pub")]
+    existing_target: Option<Vec<u8>>,
+}
+
+impl DocumentOptions {
+    pub fn new(sheets: Vec<String>, ranges: Vec<CellRange>, output: SpreadsheetOutput) -> Self {
+        Self {
+            sheets,
+            ranges,
+            output,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_subtitle_limits(
+        mut self,
+        max_line_chars: Option<usize>,
+        max_chars_per_second: Option<f64>,
+    ) -> Self {
+        self.max_line_chars = max_line_chars;
+        self.max_chars_per_second = max_chars_per_second;
+        self
+    }
+
+    pub fn with_languages(mut self, source_language: String, target_language: String) -> Self {
+        self.source_language = source_language;
+        self.target_language = target_language;
+        self
+    }
+
+    pub fn with_existing_target(mut self, existing_target: Vec<u8>) -> Self {
+        self.existing_target = Some(existing_target);
+        self
+    }
+}
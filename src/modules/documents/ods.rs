@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment, SpreadsheetOutput};
+use crate::modules::documents::ooxml;
+use crate::modules::documents::package::Package;
+use crate::modules::documents::spreadsheet::{self, NewColumns};
+use crate::modules::formats::markup::{self, Dialect, Element, Node};
+use crate::modules::formats::models::TextFormat;
+
+const CONTENT: &str = "content.xml";
+const CELL: &str = "table:table-cell";
+const COVERED_CELL: &str = "table:covered-table-cell";
+const COLUMNS_REPEATED: &str = "table:number-columns-repeated";
+const ROWS_REPEATED: &str = "table:number-rows-repeated";
+
+/// OpenDocument spreadsheet. Paragraphs of the selected text cells are
+/// translated as XML; formulas, numbers, dates and booleans are left alone.
+pub struct OdsDocument;
+
+impl DocumentFormat for OdsDocument {
+    fn name(&self) -> &'static str {
+        "ods"
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        &["application/vnd.oasis.opendocument.spreadsheet"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ods"]
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let xml = Package::open(data)?.read(CONTENT)?;
+        let tables = tables(&xml, options)?;
+        let segments = translated(&tables)
+            .map(|paragraph| DocumentSegment::new(xml[paragraph].to_owned(), TextFormat::Xml))
+            .collect();
+        Ok(segments)
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let mut package = Package::open(data)?;
+        let xml = package.read(CONTENT)?;
+        let tables = tables(&xml, options)?;
+        let paragraphs: Vec<Range<usize>> = translated(&tables).collect();
+        if paragraphs.len() != translations.len() {
+            return Err(DocumentErrors::Malformed(format!(
+                "{} translations for {} paragraphs",
+                translations.len(),
+                paragraphs.len()
+            )));
+        }
+        let translated: HashMap<usize, &str> = paragraphs
+            .iter()
+            .map(|paragraph| paragraph.start)
+            .zip(translations.iter().map(String::as_str))
+            .collect();
+
+        let mut replacements = Vec::new();
+        match options.output() {
+            SpreadsheetOutput::Overwrite => {
+                for (paragraph, translation) in paragraphs.into_iter().zip(translations) {
+                    replacements.push((paragraph, translation.clone()));
+                }
+            }
+            SpreadsheetOutput::NewColumn => {
+                for table in &tables {
+                    let mut columns = NewColumns::new(table.used_columns());
+                    for row in &table.rows {
+                        let mut new_cells = Vec::new();
+                        for cell in row.cells.iter().filter(|cell| cell.selected) {
+                            let is_translated = cell
+                                .paragraphs
+                                .iter()
+                                .any(|paragraph| translated.contains_key(&paragraph.start));
+                            if is_translated {
+                                let column = columns.target(cell.column);
+                                new_cells.push((column, new_cell(&xml, cell, &translated)));
+                            }
+                        }
+                        new_cells.sort_by_key(|(column, _)| *column);
+                        replacements.extend(row.insert(&xml, new_cells));
+                    }
+                }
+            }
+        }
+        let mut rewritten = HashMap::new();
+        rewritten.insert(CONTENT.to_owned(), ooxml::replace(&xml, &replacements));
+        package.rewrite(&rewritten)
+    }
+}
+
+#[derive(PartialEq)]
+enum Kind {
+    /// No value, only formatting.
+    Empty,
+    Text,
+    /// Formula, number, date, boolean or a cell covered by a merged one.
+    Other,
+}
+
+struct Cell {
+    column: u32,
+    /// Columns the cell is repeated over.
+    span: u32,
+    range: Range<usize>,
+    start_tag: Range<usize>,
+    end_tag: Option<Range<usize>>,
+    /// Value of the repeat attribute.
+    repeat: Option<Range<usize>>,
+    style: Option<Range<usize>>,
+    kind: Kind,
+    /// Paragraphs of a text cell holding text.
+    paragraphs: Vec<Range<usize>>,
+    selected: bool,
+}
+
+struct Row {
+    /// Start of the end tag, None for rows without cells.
+    end: Option<usize>,
+    /// First column after the cells of the row.
+    width: u32,
+    cells: Vec<Cell>,
+}
+
+impl Row {
+    /// Replacements putting the new cells, in column order, into the row:
+    /// they split the empty cells repeated over their columns, or go after
+    /// the last cell.
+    fn insert(&self, xml: &str, mut new_cells: Vec<(u32, String)>) -> Vec<(Range<usize>, String)> {
+        let mut replacements = Vec::new();
+        for cell in &self.cells {
+            let last = cell.column + cell.span - 1;
+            let inside: Vec<(u32, String)> = new_cells
+                .iter()
+                .filter(|(column, _)| (cell.column..=last).contains(column))
+                .cloned()
+                .collect();
+            if inside.is_empty() || cell.kind != Kind::Empty {
+                continue;
+            }
+            new_cells.retain(|(column, _)| !(cell.column..=last).contains(column));
+            let mut replacement = String::new();
+            let mut next = cell.column;
+            for (column, new_cell) in inside {
+                if column > next {
+                    replacement.push_str(&cell.repeated(xml, column - next));
+                }
+                replacement.push_str(&new_cell);
+                next = column + 1;
+            }
+            if last >= next {
+                replacement.push_str(&cell.repeated(xml, last - next + 1));
+            }
+            replacements.push((cell.range.clone(), replacement));
+        }
+        if let Some(end) = self.end
+            && !new_cells.is_empty()
+        {
+            let mut appended = String::new();
+            let mut next = self.width;
+            for (column, new_cell) in new_cells
+                .into_iter()
+                .filter(|(column, _)| *column >= self.width)
+            {
+                match column - next {
+                    0 => {}
+                    1 => appended.push_str(&format!("<{CELL}/>")),
+                    gap => appended.push_str(&format!("<{CELL} {COLUMNS_REPEATED}=\"{gap}\"/>")),
+                }
+                appended.push_str(&new_cell);
+                next = column + 1;
+            }
+            replacements.push((end..end, appended));
+        }
+        replacements
+    }
+}
+
+impl Cell {
+    /// The empty cell repeated over `count` columns.
+    fn repeated(&self, xml: &str, count: u32) -> String {
+        let tag = &self.start_tag;
+        let mut copy = match &self.repeat {
+            Some(repeat) => format!(
+                "{}{count}{}",
+                &xml[tag.start..repeat.start],
+                &xml[repeat.end..tag.end]
+            ),
+            None => {
+                let end = if xml[tag.clone()].ends_with("/>") {
+                    tag.end - 2
+                } else {
+                    tag.end - 1
+                };
+                format!(
+                    "{} {COLUMNS_REPEATED}=\"{count}\"{}",
+                    &xml[tag.start..end],
+                    &xml[end..tag.end]
+                )
+            }
+        };
+        if let Some(end_tag) = &self.end_tag {
+            copy.push_str(&xml[end_tag.clone()]);
+        }
+        copy
+    }
+}
+
+struct Table {
+    rows: Vec<Row>,
+}
+
+impl Table {
+    fn used_columns(&self) -> Vec<u32> {
+        self.rows
+            .iter()
+            .flat_map(|row| &row.cells)
+            .filter(|cell| cell.kind != Kind::Empty)
+            .flat_map(|cell| cell.column..cell.column + cell.span)
+            .collect()
+    }
+}
+
+/// Text cell of the translation column, styled as the source cell.
+fn new_cell(xml: &str, cell: &Cell, translated: &HashMap<usize, &str>) -> String {
+    let style = cell
+        .style
+        .as_ref()
+        .map(|style| format!(" table:style-name=\"{}\"", &xml[style.clone()]))
+        .unwrap_or_default();
+    let paragraphs: String = cell
+        .paragraphs
+        .iter()
+        .map(|paragraph| {
+            translated
+                .get(&paragraph.start)
+                .copied()
+                .unwrap_or(&xml[paragraph.clone()])
+        })
+        .collect();
+    format!("<{CELL} office:value-type=\"string\"{style}>{paragraphs}</{CELL}>")
+}
+
+/// Paragraphs to translate in document order.
+fn translated(tables: &[Table]) -> impl Iterator<Item = Range<usize>> + '_ {
+    tables
+        .iter()
+        .flat_map(|table| &table.rows)
+        .flat_map(|row| &row.cells)
+        .filter(|cell| cell.selected)
+        .flat_map(|cell| cell.paragraphs.iter().cloned())
+}
+
+fn tables(xml: &str, options: &DocumentOptions) -> DocumentResult<Vec<Table>> {
+    let nodes = markup::parse(xml, Dialect::Xml)
+        .map_err(|err| DocumentErrors::Malformed(err.to_string()))?;
+    let mut tables = Vec::new();
+    for table in ooxml::elements(&nodes, "table:table") {
+        let name = ooxml::attribute(table, "table:name").unwrap_or_default();
+        let selected = spreadsheet::sheet_selected(options, &name);
+        let mut rows = Vec::new();
+        let mut number = 1;
+        for row in ooxml::elements(&table.children, "table:table-row") {
+            let mut cells = Vec::new();
+            let mut column = 1;
+            for node in &row.children {
+                let Node::Element(cell) = node else {
+                    continue;
+                };
+                if cell.name != CELL && cell.name != COVERED_CELL {
+                    continue;
+                }
+                let span = repeat(cell, COLUMNS_REPEATED);
+                let kind = kind(xml, cell);
+                let paragraphs = match kind {
+                    Kind::Text => cell
+                        .children
+                        .iter()
+                        .filter_map(|node| match node {
+                            Node::Element(paragraph)
+                                if (paragraph.name == "text:p" || paragraph.name == "text:h")
+                                    && has_text(xml, &paragraph.children) =>
+                            {
+                                Some(paragraph.range())
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                cells.push(Cell {
+                    column,
+                    span,
+                    range: cell.range(),
+                    start_tag: cell.start_tag.clone(),
+                    end_tag: cell.end_tag.clone(),
+                    repeat: cell.attribute_range(COLUMNS_REPEATED),
+                    style: cell.attribute_range("table:style-name"),
+                    kind,
+                    paragraphs,
+                    selected: selected
+                        && spreadsheet::cell_selected(options, &name, column, number),
+                });
+                column += span;
+            }
+            rows.push(Row {
+                end: row.end_tag.as_ref().map(|end_tag| end_tag.start),
+                width: column,
+                cells,
+            });
+            number += repeat(row, ROWS_REPEATED);
+        }
+        tables.push(Table { rows });
+    }
+    Ok(tables)
+}
+
+fn repeat(element: &Element, attribute: &str) -> u32 {
+    element
+        .attribute(attribute)
+        .and_then(|count| count.parse().ok())
+        .filter(|count| *count > 0)
+        .unwrap_or(1)
+}
+
+fn kind(xml: &str, cell: &Element) -> Kind {
+    if cell.name == COVERED_CELL || cell.attribute("table:formula").is_some() {
+        return Kind::Other;
+    }
+    match cell.attribute("office:value-type") {
+        Some("string") => Kind::Text,
+        Some(_) => Kind::Other,
+        None if has_text(xml, &cell.children) => Kind::Other,
+        None => Kind::Empty,
+    }
+}
+
+fn has_text(xml: &str, nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| match node {
+        Node::Text(range) => !xml[range.clone()].trim().is_empty(),
+        Node::Element(element) => has_text(xml, &element.children),
+        Node::Other(_) => false,
+    })
+}
+
+#[cfg(test)]
+mod test_ods {
+    use std::io::{Cursor, Read, Write};
+
+    use zip::write::SimpleFileOptions;
+    use zip::{ZipArchive, ZipWriter};
+
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::models::{DocumentOptions, SpreadsheetOutput};
+    use crate::modules::documents::ods::OdsDocument;
+
+    const CONTENT: &str = r#"<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"><office:body><office:spreadsheet><table:table table:name="Terms"><table:table-column table:number-columns-repeated="3"/><table:table-row><table:table-cell office:value-type="string" table:style-name="ce1"><text:p>Term</text:p></table:table-cell><table:table-cell office:value-type="float" office:value="42"><text:p>42</text:p></table:table-cell><table:table-cell table:number-columns-repeated="1022"/></table:table-row><table:table-row table:number-rows-repeated="2"><table:table-cell office:value-type="string"><text:p>Night <text:span text:style-name="T1">watch</text:span></text:p></table:table-cell><table:table-cell table:formula="of:=1+1" office:value-type="float" office:value="2"><text:p>2</text:p></table:table-cell></table:table-row><table:table-row><table:table-cell table:number-columns-repeated="2"/><table:table-cell office:value-type="string"><text:p>Convoy</text:p></table:table-cell></table:table-row></table:table></office:spreadsheet></office:body></office:document-content>"#;
+
+    fn content(data: &[u8]) -> String {
+        let mut archive = ZipArchive::new(Cursor::new(data)).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name("content.xml")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn test_ods_roundtrip() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file("content.xml", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(CONTENT.as_bytes()).unwrap();
+        let data = writer.finish().unwrap().into_inner();
+
+        let segments = OdsDocument
+            .extract(&data, &DocumentOptions::default())
+            .unwrap();
+        assert_eq!(segments.len(), 3);
+        let options = DocumentOptions::new(
+            Vec::new(),
+            vec!["A".parse().unwrap()],
+            SpreadsheetOutput::NewColumn,
+        );
+        let segments = OdsDocument.extract(&data, &options).unwrap();
+        let fragments: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(
+            fragments,
+            vec![
+                "<text:p>Term</text:p>",
+                "<text:p>Night <text:span text:style-name=\"T1\">watch</text:span></text:p>",
+            ]
+        );
+
+        let translations = vec![
+            "<text:p>Термин</text:p>".to_owned(),
+            "<text:p>Ночной <text:span text:style-name=\"T1\">дозор</text:span></text:p>"
+                .to_owned(),
+        ];
+        let rebuilt = content(&OdsDocument.rebuild(&data, &options, &translations).unwrap());
+        // Column D is the first one right of A without values.
+        assert!(rebuilt.contains(
+            "<table:table-cell table:number-columns-repeated=\"1\"/>\
+             <table:table-cell office:value-type=\"string\" table:style-name=\"ce1\"><text:p>Термин</text:p></table:table-cell>\
+             <table:table-cell table:number-columns-repeated=\"1020\"/></table:table-row>"
+        ));
+        assert!(rebuilt.contains(
+            "<text:p>2</text:p></table:table-cell><table:table-cell/>\
+             <table:table-cell office:value-type=\"string\"><text:p>Ночной <text:span text:style-name=\"T1\">дозор</text:span></text:p></table:table-cell></table:table-row>"
+        ));
+        assert!(rebuilt.contains("<text:p>Term</text:p>"));
+
+        let options = DocumentOptions::new(
+            vec!["terms".to_owned()],
+            vec!["A1".parse().unwrap()],
+            SpreadsheetOutput::Overwrite,
+        );
+        let rebuilt = content(
+            &OdsDocument
+                .rebuild(&data, &options, &translations[..1])
+                .unwrap(),
+        );
+        assert!(rebuilt.contains("table:style-name=\"ce1\"><text:p>Термин</text:p>"));
+        assert!(rebuilt.contains("<text:p>Convoy</text:p>"));
+    }
+}
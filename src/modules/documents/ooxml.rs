@@ -0,0 +1,526 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use quick_xml::escape::{minimal_escape, partial_escape, unescape};
+
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::DocumentSegment;
+use crate::modules::documents::package::Package;
+use crate::modules::formats::markup::{self, Dialect, Element, Node};
+use crate::modules::formats::models::TextFormat;
+
+/// Element names of paragraphs and runs in one Office Open XML vocabulary.
+pub struct Vocabulary {
+    pub paragraph: &'static str,
+    pub run: &'static str,
+    pub run_properties: &'static str,
+    pub text: &'static str,
+    /// Run content written as a tab character.
+    pub tab: Option<&'static str>,
+    /// Run content written as a line break character.
+    pub line_break: Option<&'static str>,
+    /// Run content without meaning for the text, dropped when the run is rewritten.
+    pub ignored: &'static [&'static str],
+    /// Elements around runs (hyperlinks, tracked insertions) that stay around
+    /// the translated runs.
+    pub containers: &'static [&'static str],
+    /// The text element needs `xml:space="preserve"` to keep edge whitespace.
+    pub preserve_space: bool,
+}
+
+enum Item {
+    /// Text of adjacent runs with the same run properties.
+    Text { group: usize, text: String },
+    /// Start tag of a container.
+    Open(usize, Range<usize>),
+    /// End tag of a container.
+    Close(usize, Range<usize>),
+    /// Anything else the paragraph holds, kept byte for byte: properties,
+    /// bookmarks, fields, drawings, runs with other content.
+    Raw(usize, Range<usize>),
+}
+
+/// Paragraph of a part with its runs merged by formatting.
+pub struct Paragraph {
+    range: Range<usize>,
+    start_tag: Range<usize>,
+    end_tag: Range<usize>,
+    items: Vec<Item>,
+    /// Run properties of every formatting group, empty for runs without them.
+    groups: Vec<String>,
+}
+
+/// Paragraphs of a part in document order. Paragraphs inside other
+/// paragraphs (text boxes in drawings) stay part of the outer one.
+pub fn paragraphs(xml: &str, vocabulary: &Vocabulary) -> DocumentResult<Vec<Paragraph>> {
+    let nodes = markup::parse(xml, Dialect::Xml)
+        .map_err(|err| DocumentErrors::Malformed(err.to_string()))?;
+    let mut paragraphs = Vec::new();
+    find_paragraphs(xml, &nodes, vocabulary, &mut paragraphs);
+    Ok(paragraphs)
+}
+
+/// Fragments of the paragraphs with text of the parts, in order.
+pub fn extract(
+    package: &mut Package,
+    parts: &[String],
+    vocabulary: &Vocabulary,
+) -> DocumentResult<Vec<DocumentSegment>> {
+    let mut segments = Vec::new();
+    for name in parts {
+        let xml = package.read(name)?;
+        for paragraph in paragraphs(&xml, vocabulary)? {
+            if let Some(fragment) = paragraph.fragment() {
+                segments.push(DocumentSegment::new(fragment, TextFormat::Xml));
+            }
+        }
+    }
+    Ok(segments)
+}
+
+/// The parts with their paragraphs rebuilt from `translations`, given in the
+/// order of [`extract`]. Parts without text are left out.
+pub fn rebuild(
+    package: &mut Package,
+    parts: &[String],
+    vocabulary: &Vocabulary,
+    translations: &[String],
+) -> DocumentResult<HashMap<String, String>> {
+    let mut translations = translations.iter();
+    let mut rewritten = HashMap::new();
+    for name in parts {
+        let xml = package.read(name)?;
+        let mut replacements = Vec::new();
+        for paragraph in paragraphs(&xml, vocabulary)? {
+            if paragraph.fragment().is_none() {
+                continue;
+            }
+            let translation = translations.next().ok_or_else(|| {
+                DocumentErrors::Malformed("fewer translations than paragraphs".to_string())
+            })?;
+            let rebuilt = paragraph.rebuild(&xml, translation, vocabulary)?;
+            replacements.push((paragraph.range().clone(), rebuilt));
+        }
+        if !replacements.is_empty() {
+            rewritten.insert(name.clone(), replace(&xml, &replacements));
+        }
+    }
+    Ok(rewritten)
+}
+
+fn find_paragraphs(
+    xml: &str,
+    nodes: &[Node],
+    vocabulary: &Vocabulary,
+    paragraphs: &mut Vec<Paragraph>,
+) {
+    for node in nodes {
+        let Node::Element(element) = node else {
+            continue;
+        };
+        match &element.end_tag {
+            Some(end_tag) if element.name == vocabulary.paragraph => {
+                let mut paragraph = Paragraph {
+                    range: element.range(),
+                    start_tag: element.start_tag.clone(),
+                    end_tag: end_tag.clone(),
+                    items: Vec::new(),
+                    groups: Vec::new(),
+                };
+                paragraph.collect(xml, &element.children, vocabulary);
+                paragraphs.push(paragraph);
+            }
+            _ => find_paragraphs(xml, &element.children, vocabulary, paragraphs),
+        }
+    }
+}
+
+impl Paragraph {
+    fn collect(&mut self, xml: &str, nodes: &[Node], vocabulary: &Vocabulary) {
+        for node in nodes {
+            let element = match node {
+                Node::Element(element) => element,
+                // Whitespace between elements has no meaning in these vocabularies.
+                Node::Text(range) if xml[range.clone()].trim().is_empty() => continue,
+                Node::Text(range) | Node::Other(range) => {
+                    self.items.push(Item::Raw(self.items.len(), range.clone()));
+                    continue;
+                }
+            };
+            if element.name == vocabulary.run
+                && let Some((properties, text)) = run_text(xml, element, vocabulary)
+            {
+                self.push_text(properties, text);
+            } else if element.name == vocabulary.text
+                && let Some(text) = text_content(xml, &element.children)
+            {
+                // Plain shared strings hold their text without a run.
+                self.push_text(String::new(), text);
+            } else if vocabulary.containers.contains(&element.name.as_str())
+                && let Some(end_tag) = &element.end_tag
+            {
+                let index = self.items.len();
+                self.items
+                    .push(Item::Open(index, element.start_tag.clone()));
+                self.collect(xml, &element.children, vocabulary);
+                self.items.push(Item::Close(index, end_tag.clone()));
+            } else {
+                self.items
+                    .push(Item::Raw(self.items.len(), element.range()));
+            }
+        }
+    }
+
+    fn push_text(&mut self, properties: String, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        let group = match self.groups.iter().position(|group| *group == properties) {
+            Some(group) => group,
+            None => {
+                self.groups.push(properties);
+                self.groups.len() - 1
+            }
+        };
+        if let Some(Item::Text {
+            group: last,
+            text: last_text,
+        }) = self.items.last_mut()
+            && *last == group
+        {
+            last_text.push_str(&text);
+            return;
+        }
+        self.items.push(Item::Text { group, text });
+    }
+
+    /// Items from the first to the last one with text. Raw items outside of
+    /// it (paragraph properties, leading bookmarks) are not shown to the model.
+    fn translated_items(&self) -> Range<usize> {
+        let is_text = |item: &Item| matches!(item, Item::Text { .. });
+        let Some(first) = self.items.iter().position(is_text) else {
+            return 0..0;
+        };
+        let last = self.items.iter().rposition(is_text).unwrap_or(first);
+        // Containers around the text are moved into the range as a whole.
+        let mut range = first..last + 1;
+        for (index, item) in self.items.iter().enumerate() {
+            if let Item::Open(id, _) = item {
+                let close = self
+                    .items
+                    .iter()
+                    .position(|item| matches!(item, Item::Close(close_id, _) if close_id == id));
+                if let Some(close) = close
+                    && index < range.end
+                    && close >= range.start
+                {
+                    range = range.start.min(index)..range.end.max(close + 1);
+                }
+            }
+        }
+        range
+    }
+
+    /// Group holding most of the text. Its text goes to the model without tags.
+    fn main_group(&self) -> usize {
+        let mut sizes = vec![0; self.groups.len()];
+        for item in &self.items {
+            if let Item::Text { group, text } = item {
+                sizes[*group] += text.chars().count();
+            }
+        }
+        (0..sizes.len())
+            .max_by_key(|group| (sizes[*group], usize::MAX - group))
+            .unwrap_or_default()
+    }
+
+    /// XML fragment sent for translation: text of the main group as is,
+    /// other groups as `<gN>` elements, containers as `<lN>` and raw items
+    /// as empty `<xN/>` elements. None for paragraphs without text.
+    pub fn fragment(&self) -> Option<String> {
+        let has_text = self
+            .items
+            .iter()
+            .any(|item| matches!(item, Item::Text { text, .. } if !text.trim().is_empty()));
+        if !has_text {
+            return None;
+        }
+        let range = self.translated_items();
+        let main = self.main_group();
+        let mut fragment = String::from("<p>");
+        for item in &self.items[range] {
+            match item {
+                Item::Text { group, text } if *group == main => {
+                    fragment.push_str(&minimal_escape(text.as_str()))
+                }
+                Item::Text { group, text } => fragment.push_str(&format!(
+                    "<g{group}>{}</g{group}>",
+                    minimal_escape(text.as_str())
+                )),
+                Item::Open(id, _) => fragment.push_str(&format!("<l{id}>")),
+                Item::Close(id, _) => fragment.push_str(&format!("</l{id}>")),
+                Item::Raw(id, _) => fragment.push_str(&format!("<x{id}/>")),
+            }
+        }
+        fragment.push_str("</p>");
+        Some(fragment)
+    }
+
+    /// The paragraph with the translated fragment written back as runs.
+    /// Paragraph properties and everything outside the text are kept.
+    pub fn rebuild(
+        &self,
+        xml: &str,
+        translation: &str,
+        vocabulary: &Vocabulary,
+    ) -> DocumentResult<String> {
+        let nodes = markup::parse(translation, Dialect::Xml)
+            .map_err(|err| DocumentErrors::Malformed(format!("translated paragraph: {err}")))?;
+        let Some(Node::Element(root)) = nodes.iter().find(|node| matches!(node, Node::Element(_)))
+        else {
+            return Err(DocumentErrors::Malformed(
+                "translated paragraph has no root element".to_string(),
+            ));
+        };
+
+        let range = self.translated_items();
+        let mut output = String::with_capacity(self.range.len());
+        output.push_str(&xml[self.start_tag.clone()]);
+        for item in &self.items[..range.start] {
+            self.push_item(xml, item, &mut output);
+        }
+        let mut writer = RunWriter {
+            xml,
+            paragraph: self,
+            vocabulary,
+            translation,
+            output: &mut output,
+        };
+        writer.write(&root.children, self.main_group());
+        for item in &self.items[range.end..] {
+            self.push_item(xml, item, &mut output);
+        }
+        output.push_str(&xml[self.end_tag.clone()]);
+        Ok(output)
+    }
+
+    fn push_item(&self, xml: &str, item: &Item, output: &mut String) {
+        match item {
+            Item::Open(_, range) | Item::Close(_, range) | Item::Raw(_, range) => {
+                output.push_str(&xml[range.clone()])
+            }
+            // Never outside the translated range.
+            Item::Text { .. } => {}
+        }
+    }
+
+    pub fn range(&self) -> &Range<usize> {
+        &self.range
+    }
+}
+
+/// Properties and text of a run holding nothing but text, tabs and breaks.
+fn run_text(xml: &str, run: &Element, vocabulary: &Vocabulary) -> Option<(String, String)> {
+    let mut properties = String::new();
+    let mut text = String::new();
+    for node in &run.children {
+        let element = match node {
+            Node::Element(element) => element,
+            Node::Text(range) if xml[range.clone()].trim().is_empty() => continue,
+            _ => return None,
+        };
+        let name = element.name.as_str();
+        if name == vocabulary.run_properties {
+            properties = xml[element.range()].to_owned();
+        } else if name == vocabulary.text {
+            text.push_str(&text_content(xml, &element.children)?);
+        } else if Some(name) == vocabulary.tab {
+            text.push('\t');
+        } else if Some(name) == vocabulary.line_break && element.attribute("w:type").is_none() {
+            text.push('\n');
+        } else if !vocabulary.ignored.contains(&name) {
+            return None;
+        }
+    }
+    Some((properties, text))
+}
+
+/// Unescaped text of text nodes; None when the element holds anything else.
+fn text_content(xml: &str, nodes: &[Node]) -> Option<String> {
+    let mut raw = String::new();
+    for node in nodes {
+        match node {
+            Node::Text(range) => raw.push_str(&xml[range.clone()]),
+            _ => return None,
+        }
+    }
+    Some(unescape_lossy(&raw).into_owned())
+}
+
+fn unescape_lossy(raw: &str) -> Cow<'_, str> {
+    unescape(raw).unwrap_or(Cow::Borrowed(raw))
+}
+
+/// Writes a translated fragment back as runs of the original groups.
+struct RunWriter<'a> {
+    xml: &'a str,
+    paragraph: &'a Paragraph,
+    vocabulary: &'a Vocabulary,
+    translation: &'a str,
+    output: &'a mut String,
+}
+
+impl RunWriter<'_> {
+    fn write(&mut self, nodes: &[Node], group: usize) {
+        let mut text = String::new();
+        for node in nodes {
+            match node {
+                Node::Text(range) => text.push_str(&self.translation[range.clone()]),
+                Node::Other(_) => {}
+                Node::Element(element) => {
+                    self.run(group, &unescape_lossy(&text));
+                    text.clear();
+                    self.element(element, group);
+                }
+            }
+        }
+        self.run(group, &unescape_lossy(&text));
+    }
+
+    fn element(&mut self, element: &Element, group: usize) {
+        let (kind, id) = element.name.split_at(1.min(element.name.len()));
+        let Ok(id) = id.parse::<usize>() else {
+            self.write(&element.children, group);
+            return;
+        };
+        match kind {
+            "g" if id < self.paragraph.groups.len() => self.write(&element.children, id),
+            "l" => {
+                let open = self.paragraph.items.iter().find_map(|item| match item {
+                    Item::Open(open_id, range) if *open_id == id => Some(range.clone()),
+                    _ => None,
+                });
+                let close = self.paragraph.items.iter().find_map(|item| match item {
+                    Item::Close(close_id, range) if *close_id == id => Some(range.clone()),
+                    _ => None,
+                });
+                if let Some(open) = open {
+                    self.output.push_str(&self.xml[open]);
+                }
+                self.write(&element.children, group);
+                if let Some(close) = close {
+                    self.output.push_str(&self.xml[close]);
+                }
+            }
+            "x" => {
+                let raw = self.paragraph.items.iter().find_map(|item| match item {
+                    Item::Raw(raw_id, range) if *raw_id == id => Some(range.clone()),
+                    _ => None,
+                });
+                if let Some(raw) = raw {
+                    self.output.push_str(&self.xml[raw]);
+                }
+            }
+            _ => self.write(&element.children, group),
+        }
+    }
+
+    fn run(&mut self, group: usize, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let vocabulary = self.vocabulary;
+        let output = &mut *self.output;
+        output.push_str(&format!("<{}>", vocabulary.run));
+        if let Some(properties) = self.paragraph.groups.get(group) {
+            output.push_str(properties);
+        }
+        let mut chunk = String::new();
+        for ch in text.chars() {
+            let element = match ch {
+                '\t' => vocabulary.tab,
+                '\n' => vocabulary.line_break,
+                _ => None,
+            };
+            match element {
+                Some(element) => {
+                    push_text(output, vocabulary, &chunk);
+                    chunk.clear();
+                    output.push_str(&format!("<{element}/>"));
+                }
+                None => chunk.push(ch),
+            }
+        }
+        push_text(output, vocabulary, &chunk);
+        output.push_str(&format!("</{}>", vocabulary.run));
+    }
+}
+
+fn push_text(output: &mut String, vocabulary: &Vocabulary, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    let space = if vocabulary.preserve_space {
+        " xml:space=\"preserve\""
+    } else {
+        ""
+    };
+    output.push_str(&format!(
+        "<{name}{space}>{}</{name}>",
+        partial_escape(text),
+        name = vocabulary.text
+    ));
+}
+
+/// Elements with the given name below `nodes`, not looking inside matches.
+/// A name without a prefix matches any prefix.
+pub(crate) fn elements<'n>(nodes: &'n [Node], name: &str) -> Vec<&'n Element> {
+    let mut found = Vec::new();
+    for node in nodes {
+        if let Node::Element(element) = node {
+            if has_name(element, name) {
+                found.push(element);
+            } else {
+                found.extend(elements(&element.children, name));
+            }
+        }
+    }
+    found
+}
+
+/// Child elements with the given name.
+pub(crate) fn children<'n>(element: &'n Element, name: &str) -> impl Iterator<Item = &'n Element> {
+    element.children.iter().filter_map(move |node| match node {
+        Node::Element(child) if has_name(child, name) => Some(child),
+        _ => None,
+    })
+}
+
+fn has_name(element: &Element, name: &str) -> bool {
+    element.name == name
+        || (!name.contains(':')
+            && element
+                .name
+                .rsplit_once(':')
+                .is_some_and(|(_, local)| local == name))
+}
+
+/// Unescaped attribute value.
+pub(crate) fn attribute(element: &Element, name: &str) -> Option<String> {
+    let value = element.attribute(name)?;
+    Some(unescape(value).unwrap_or(Cow::Borrowed(value)).into_owned())
+}
+
+/// `xml` with the byte ranges replaced, ranges given in document order.
+pub fn replace(xml: &str, replacements: &[(Range<usize>, String)]) -> String {
+    let mut output = String::with_capacity(xml.len());
+    let mut last = 0;
+    for (range, replacement) in replacements {
+        output.push_str(&xml[last..range.start]);
+        output.push_str(replacement);
+        last = range.end;
+    }
+    output.push_str(&xml[last..]);
+    output
+}
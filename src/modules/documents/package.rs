@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::ooxml;
+use crate::modules::formats::markup::{self, Dialect};
+
+/// Relationship of a part to another part of the package.
+pub struct Relationship {
+    pub id: String,
+    /// Relationship type URI.
+    pub kind: String,
+    /// Package path of the target part.
+    pub target: String,
+}
+
+/// Zip container of an office document.
+pub struct Package<'a> {
+    archive: ZipArchive<Cursor<&'a [u8]>>,
+}
+
+impl<'a> Package<'a> {
+    pub fn open(data: &'a [u8]) -> DocumentResult<Self> {
+        let archive = ZipArchive::new(Cursor::new(data))?;
+        Ok(Self { archive })
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.archive.file_names().map(str::to_owned).collect()
+    }
+
+    /// Part as UTF-8 text.
+    pub fn read(&mut self, name: &str) -> DocumentResult<String> {
+        let mut file = self.archive.by_name(name)?;
+        let mut text = String::new();
+        file.read_to_string(&mut text)
+            .map_err(|err| DocumentErrors::Malformed(format!("{name}: {err}")))?;
+        Ok(text)
+    }
+
+    /// Relationships of a part to other parts of the package; external
+    /// targets are left out. Parts without relationships have none.
+    pub fn relationships(&mut self, part: &str) -> DocumentResult<Vec<Relationship>> {
+        let (directory, file) = part.rsplit_once('/').unwrap_or(("", part));
+        let name = match directory {
+            "" => format!("_rels/{file}.rels"),
+            directory => format!("{directory}/_rels/{file}.rels"),
+        };
+        if self.archive.index_for_name(&name).is_none() {
+            return Ok(Vec::new());
+        }
+        let xml = self.read(&name)?;
+        let nodes = markup::parse(&xml, Dialect::Xml)
+            .map_err(|err| DocumentErrors::Malformed(format!("{name}: {err}")))?;
+        let relationships = ooxml::elements(&nodes, "Relationship")
+            .into_iter()
+            .filter(|relationship| relationship.attribute("TargetMode") != Some("External"))
+            .filter_map(|relationship| {
+                let target = ooxml::attribute(relationship, "Target")?;
+                Some(Relationship {
+                    id: relationship.attribute("Id")?.to_owned(),
+                    kind: relationship
+                        .attribute("Type")
+                        .unwrap_or_default()
+                        .to_owned(),
+                    target: resolve(directory, &target),
+                })
+            })
+            .collect();
+        Ok(relationships)
+    }
+
+    /// The package with the given parts replaced. Other entries are copied
+    /// without recompression, in their original order.
+    pub fn rewrite(mut self, parts: &HashMap<String, String>) -> DocumentResult<Vec<u8>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for index in 0..self.archive.len() {
+            let file = self.archive.by_index_raw(index)?;
+            match parts.get(file.name()) {
+                Some(part) => {
+                    let name = file.name().to_owned();
+                    drop(file);
+                    writer.start_file(name, options)?;
+                    writer
+                        .write_all(part.as_bytes())
+                        .map_err(|err| DocumentErrors::Malformed(err.to_string()))?;
+                }
+                None => writer.raw_copy_file(file)?,
+            }
+        }
+        Ok(writer.finish()?.into_inner())
+    }
+}
+
+/// Package path of a relationship target given relative to `directory`.
+fn resolve(directory: &str, target: &str) -> String {
+    let mut path: Vec<&str> = match target.strip_prefix('/') {
+        Some(_) => Vec::new(),
+        None => directory
+            .split('/')
+            .filter(|name| !name.is_empty())
+            .collect(),
+    };
+    for name in target.split('/') {
+        match name {
+            "" | "." => {}
+            ".." => {
+                path.pop();
+            }
+            name => path.push(name),
+        }
+    }
+    path.join("/")
+}
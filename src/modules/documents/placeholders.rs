@@ -0,0 +1,142 @@
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+use quick_xml::escape::{minimal_escape, unescape};
+use regex::Regex;
+
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+
+/// Interpolation of resource strings: `{{name}}` (i18next), `%{count}`
+/// (Rails), `{name}` and `{0}` (vue-i18n), `$t(key)` nesting, `@:key` linked
+/// messages, `%#@name@` substitutions (Apple) and printf conversions.
+const INTERPOLATION_PATTERN: &str = r"\{\{[^{}]*\}\}|%\{[^{}]*\}|\{[^{}\s][^{}]*\}|\$t\([^)]*\)|@(?:\.\w+)?:(?:\([^)]*\)|[\w.-]+)|%#@\w+@|%(?:\d+\$)?[-+0#]*\d*(?:\.\d+)?(?:hh?|ll?|[qjzt])?[sdifuxXeEgGc@]";
+
+/// printf conversions of Android and Apple strings with their position and
+/// type (length modifier and conversion). `%%` is matched to be skipped.
+const SPECIFIER_PATTERN: &str = r"%%|%#@\w+@|%(?:(\d+)\$)?[-+ 0#']*(?:\d+|\*)?(?:\.(?:\d+|\*))?((?:hh?|ll?|[qjztL])?[sSdDiuUoOxXfFeEgGaAcCp@])";
+
+static INTERPOLATION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(INTERPOLATION_PATTERN).expect("interpolation pattern is valid"));
+
+static SPECIFIER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(SPECIFIER_PATTERN).expect("specifier pattern is valid"));
+
+/// Resource string as XML for the model, with its interpolation tokens as
+/// `<xN/>` tags.
+pub struct Protected {
+    pub text: String,
+    pub tokens: Vec<String>,
+}
+
+impl Protected {
+    /// The string has words to translate besides its tokens.
+    pub fn has_text(&self) -> bool {
+        let mut rest = self.text.as_str();
+        while !rest.is_empty() {
+            let (piece, _, next) = next_placeholder(rest);
+            if piece.chars().any(char::is_alphabetic) {
+                return true;
+            }
+            rest = next;
+        }
+        false
+    }
+}
+
+pub fn protect(text: &str) -> Protected {
+    let mut protected = String::with_capacity(text.len());
+    let mut tokens = Vec::new();
+    let mut last = 0;
+    for found in INTERPOLATION.find_iter(text) {
+        protected.push_str(&minimal_escape(&text[last..found.start()]));
+        tokens.push(found.as_str().to_owned());
+        protected.push_str(&format!("<x{}/>", tokens.len()));
+        last = found.end();
+    }
+    protected.push_str(&minimal_escape(&text[last..]));
+    Protected {
+        text: protected,
+        tokens,
+    }
+}
+
+/// Translation of a protected string with its tokens put back.
+pub fn restore(translation: &str, tokens: &[String]) -> String {
+    let mut restored = String::with_capacity(translation.len());
+    let mut rest = translation;
+    while !rest.is_empty() {
+        let (piece, token, next) = next_placeholder(rest);
+        restored.push_str(&unescape(piece).unwrap_or(Cow::Borrowed(piece)));
+        if let Some(token) = token.and_then(|index| tokens.get(index)) {
+            restored.push_str(token);
+        }
+        rest = next;
+    }
+    restored
+}
+
+/// Text up to the first `<xN/>` tag, the zero-based index of the tag and the rest.
+pub fn next_placeholder(text: &str) -> (&str, Option<usize>, &str) {
+    let mut search = 0;
+    while let Some(start) = text[search..].find("<x").map(|start| start + search) {
+        let tag = &text[start + 2..];
+        let digits = tag.len()
+            - tag
+                .trim_start_matches(|char: char| char.is_ascii_digit())
+                .len();
+        let after = tag[digits..].trim_start();
+        if digits > 0 && after.starts_with("/>") {
+            let index = tag[..digits].parse::<usize>().unwrap_or_default();
+            let end = text.len() - after.len() + 2;
+            return (&text[..start], index.checked_sub(1), &text[end..]);
+        }
+        search = start + 2;
+    }
+    (text, None, "")
+}
+
+/// Fails when the translation has other format specifiers than the source:
+/// the same types in the same order, or by position when they are numbered.
+pub fn check_specifiers(key: &str, source: &str, translation: &str) -> DocumentResult<()> {
+    let expected = specifiers(source);
+    let found = specifiers(translation);
+    if expected != found {
+        let list = |specifiers: &[(Option<usize>, String)]| {
+            specifiers
+                .iter()
+                .map(|(position, kind)| match position {
+                    Some(position) => format!("%{position}${kind}"),
+                    None => format!("%{kind}"),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        return Err(DocumentErrors::SpecifiersChanged(format!(
+            "`{key}`: expected [{}], found [{}]",
+            list(&expected),
+            list(&found)
+        )));
+    }
+    Ok(())
+}
+
+fn specifiers(text: &str) -> Vec<(Option<usize>, String)> {
+    let mut specifiers: Vec<(Option<usize>, String)> = SPECIFIER
+        .captures_iter(text)
+        .filter(|captures| &captures[0] != "%%")
+        .map(|captures| {
+            let position = captures
+                .get(1)
+                .and_then(|position| position.as_str().parse().ok());
+            let kind = match captures.get(2) {
+                Some(kind) => kind.as_str().to_owned(),
+                None => captures[0][1..].to_owned(),
+            };
+            (position, kind)
+        })
+        .collect();
+    if specifiers.iter().all(|(position, _)| position.is_some()) {
+        specifiers.sort();
+    }
+    specifiers
+}
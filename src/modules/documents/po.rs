@@ -0,0 +1,417 @@
+use std::ops::Range;
+
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::documents::ooxml;
+use crate::modules::formats::models::TextFormat;
+
+const FUZZY: &str = "fuzzy";
+
+/// Gettext catalog. Entries without a translation or marked fuzzy are filled
+/// in; translated and obsolete entries and the header are left as they are.
+pub struct PoDocument;
+
+impl DocumentFormat for PoDocument {
+    fn name(&self) -> &'static str {
+        "po"
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        &["text/x-gettext-translation", "text/x-po"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["po", "pot"]
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let catalog = Catalog::parse(data)?;
+        Ok(catalog
+            .pending()
+            .flat_map(|entry| entry.id.iter().chain(&entry.plural))
+            .map(|text| DocumentSegment::new(text.clone(), TextFormat::Plain))
+            .collect())
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let catalog = Catalog::parse(data)?;
+        let mut translations = translations.iter();
+        let mut next = || {
+            translations.next().ok_or_else(|| {
+                DocumentErrors::Malformed("fewer translations than entries".to_string())
+            })
+        };
+
+        let mut replacements = Vec::new();
+        for entry in catalog.pending() {
+            let singular = next()?;
+            let strings = match &entry.plural {
+                None => vec![("msgstr".to_string(), singular.as_str())],
+                Some(_) => {
+                    let plural = next()?;
+                    let forms = catalog
+                        .plural_forms
+                        .unwrap_or(entry.translations.len().max(2));
+                    (0..forms)
+                        .map(|form| {
+                            let text = if form == 0 { singular } else { plural };
+                            (format!("msgstr[{form}]"), text.as_str())
+                        })
+                        .collect()
+                }
+            };
+            let translation = strings
+                .into_iter()
+                .map(|(keyword, text)| quote(&keyword, text, entry.multiline, catalog.newline))
+                .collect::<Vec<_>>()
+                .join(catalog.newline);
+            replacements.push((entry.translation_range.clone(), translation));
+
+            if entry.is_fuzzy() {
+                if let Some(line) = &entry.flags_line {
+                    let flags: Vec<&str> = entry
+                        .flags
+                        .iter()
+                        .map(String::as_str)
+                        .filter(|flag| *flag != FUZZY)
+                        .collect();
+                    let line_break =
+                        &catalog.text[line.clone()][catalog.text[line.clone()].trim_end().len()..];
+                    let flags = if flags.is_empty() {
+                        String::new()
+                    } else {
+                        format!("#, {}{line_break}", flags.join(", "))
+                    };
+                    replacements.push((line.clone(), flags));
+                }
+                // The previous source text only explains the fuzzy match.
+                for line in &entry.previous {
+                    replacements.push((line.clone(), String::new()));
+                }
+            }
+        }
+        replacements.sort_by_key(|(range, _)| range.start);
+        Ok(ooxml::replace(catalog.text, &replacements).into_bytes())
+    }
+}
+
+struct Catalog<'a> {
+    text: &'a str,
+    newline: &'static str,
+    /// `nplurals` of the header.
+    plural_forms: Option<usize>,
+    entries: Vec<Entry>,
+}
+
+#[derive(Default)]
+struct Entry {
+    /// `#,` line with its line break.
+    flags_line: Option<Range<usize>>,
+    flags: Vec<String>,
+    /// `#|` lines with their line breaks.
+    previous: Vec<Range<usize>>,
+    context: Option<String>,
+    id: Option<String>,
+    plural: Option<String>,
+    /// The msgid starts with an empty string and continues on the next lines.
+    multiline: bool,
+    translations: Vec<String>,
+    /// The `msgstr` lines, without the last line break.
+    translation_range: Range<usize>,
+    obsolete: bool,
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Context,
+    Id,
+    Plural,
+    Translation(usize),
+}
+
+impl Entry {
+    fn is_fuzzy(&self) -> bool {
+        self.flags.iter().any(|flag| flag == FUZZY)
+    }
+
+    fn is_pending(&self) -> bool {
+        !self.obsolete
+            && self.id.as_ref().is_some_and(|id| !id.is_empty())
+            && (self.is_fuzzy() || self.translations.iter().all(String::is_empty))
+    }
+
+    fn field(&mut self, field: Field) -> &mut String {
+        match field {
+            Field::Context => self.context.get_or_insert_default(),
+            Field::Id => self.id.get_or_insert_default(),
+            Field::Plural => self.plural.get_or_insert_default(),
+            Field::Translation(form) => {
+                if self.translations.len() <= form {
+                    self.translations.resize(form + 1, String::new());
+                }
+                &mut self.translations[form]
+            }
+        }
+    }
+}
+
+impl<'a> Catalog<'a> {
+    fn parse(data: &'a [u8]) -> DocumentResult<Self> {
+        let text = std::str::from_utf8(data)
+            .map_err(|err| DocumentErrors::Malformed(format!("catalog is not UTF-8: {err}")))?;
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+
+        let mut entries = Vec::new();
+        let mut entry = Entry::default();
+        let mut field = None;
+        let mut offset = 0;
+        for (number, line) in text.split_inclusive('\n').enumerate() {
+            let range = offset..offset + line.len();
+            let content = line.trim_end_matches(['\n', '\r']);
+            let content_end = offset + content.len();
+            offset += line.len();
+            let content = content.trim().trim_start_matches('\u{feff}');
+            let malformed = || {
+                DocumentErrors::Malformed(format!("invalid catalog line {}: {content}", number + 1))
+            };
+
+            // A comment or keyword other than a plural form after the
+            // translation starts the next entry, as does a blank line.
+            let starts_entry = content.is_empty()
+                || (!content.starts_with('"')
+                    && !content.starts_with("msgstr")
+                    && !entry.translations.is_empty());
+            if starts_entry && (entry.id.is_some() || content.is_empty()) {
+                if entry.id.is_some() {
+                    entries.push(std::mem::take(&mut entry));
+                } else {
+                    entry = Entry::default();
+                }
+                field = None;
+            }
+            if content.is_empty() {
+                continue;
+            }
+
+            if content.starts_with("#~") {
+                entry.obsolete = true;
+            } else if let Some(flags) = content.strip_prefix("#,") {
+                entry.flags_line = Some(range);
+                entry.flags = flags
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|flag| !flag.is_empty())
+                    .map(str::to_owned)
+                    .collect();
+            } else if content.starts_with("#|") {
+                entry.previous.push(range);
+            } else if content.starts_with('#') {
+                continue;
+            } else if content.starts_with('"') {
+                let value = unquote(content).ok_or_else(malformed)?;
+                let field = field.ok_or_else(malformed)?;
+                if matches!(field, Field::Id) && entry.id.as_ref().is_some_and(String::is_empty) {
+                    entry.multiline = true;
+                }
+                if let Field::Translation(_) = field {
+                    entry.translation_range.end = content_end;
+                }
+                entry.field(field).push_str(&value);
+            } else {
+                let (keyword, value) = content
+                    .split_once(|char: char| char.is_whitespace())
+                    .ok_or_else(malformed)?;
+                let value = unquote(value.trim()).ok_or_else(malformed)?;
+                let current = match keyword {
+                    "msgctxt" => Field::Context,
+                    "msgid" => Field::Id,
+                    "msgid_plural" => Field::Plural,
+                    "msgstr" => Field::Translation(0),
+                    _ => keyword
+                        .strip_prefix("msgstr[")
+                        .and_then(|form| form.strip_suffix(']'))
+                        .and_then(|form| form.parse().ok())
+                        .map(Field::Translation)
+                        .ok_or_else(malformed)?,
+                };
+                if let Field::Translation(_) = current {
+                    if entry.translations.is_empty() {
+                        entry.translation_range.start = range.start;
+                    }
+                    entry.translation_range.end = content_end;
+                }
+                *entry.field(current) = value;
+                field = Some(current);
+            }
+        }
+        if entry.id.is_some() {
+            entries.push(entry);
+        }
+
+        let plural_forms = entries
+            .iter()
+            .find(|entry| {
+                !entry.obsolete && entry.context.is_none() && entry.id.as_deref() == Some("")
+            })
+            .and_then(|header| header.translations.first())
+            .and_then(|header| header.split("nplurals=").nth(1))
+            .and_then(|count| {
+                count
+                    .split(|char: char| !char.is_ascii_digit())
+                    .next()?
+                    .parse()
+                    .ok()
+            });
+        Ok(Self {
+            text,
+            newline,
+            plural_forms,
+            entries,
+        })
+    }
+
+    fn pending(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(|entry| entry.is_pending())
+    }
+}
+
+/// Value of a quoted C string.
+fn unquote(text: &str) -> Option<String> {
+    let inner = text.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            value.push(char);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            't' => value.push('\t'),
+            'r' => value.push('\r'),
+            other => value.push(other),
+        }
+    }
+    Some(value)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+/// `keyword "text"`, or an empty first string and one line per line of the
+/// text when the source is written that way.
+fn quote(keyword: &str, text: &str, multiline: bool, newline: &str) -> String {
+    if !multiline || !text.trim_end_matches('\n').contains('\n') {
+        return format!("{keyword} \"{}\"", escape(text));
+    }
+    let mut quoted = format!("{keyword} \"\"");
+    for line in text.split_inclusive('\n') {
+        quoted.push_str(newline);
+        quoted.push_str(&format!("\"{}\"", escape(line)));
+    }
+    quoted
+}
+
+#[cfg(test)]
+mod test_po {
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::models::DocumentOptions;
+    use crate::modules::documents::po::PoDocument;
+
+    const CATALOG: &str = r#"msgid ""
+msgstr ""
+"Language: ru\n"
+"Plural-Forms: nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);\n"
+
+#: src/menu.c:10
+msgid "Open"
+msgstr "Открыть"
+
+#. Button label
+#, fuzzy, c-format
+#| msgid "Save %s"
+msgctxt "toolbar"
+msgid "Save %s as"
+msgstr "Сохранить %s"
+
+msgid "%d file"
+msgid_plural "%d files"
+msgstr[0] ""
+msgstr[1] ""
+
+msgid ""
+"First line\n"
+"Second line"
+msgstr ""
+
+#~ msgid "Old"
+#~ msgstr ""
+"#;
+
+    #[test]
+    fn test_po_roundtrip() {
+        let document = PoDocument;
+        let options = DocumentOptions::default();
+        let segments = document.extract(CATALOG.as_bytes(), &options).unwrap();
+        let texts: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Save %s as",
+                "%d file",
+                "%d files",
+                "First line\nSecond line"
+            ]
+        );
+
+        let translations = vec![
+            "Сохранить %s как".to_string(),
+            "%d файл".to_string(),
+            "%d файлов".to_string(),
+            "Первая \"строка\"\nВторая строка".to_string(),
+        ];
+        let rebuilt = document
+            .rebuild(CATALOG.as_bytes(), &options, &translations)
+            .unwrap();
+        let expected = CATALOG
+            .replace(
+                "#, fuzzy, c-format\n#| msgid \"Save %s\"\n",
+                "#, c-format\n",
+            )
+            .replace("msgstr \"Сохранить %s\"", "msgstr \"Сохранить %s как\"")
+            .replace(
+                "msgstr[0] \"\"\nmsgstr[1] \"\"",
+                "msgstr[0] \"%d файл\"\nmsgstr[1] \"%d файлов\"\nmsgstr[2] \"%d файлов\"",
+            )
+            .replace(
+                "\"Second line\"\nmsgstr \"\"",
+                "\"Second line\"\nmsgstr \"\"\n\"Первая \\\"строка\\\"\\n\"\n\"Вторая строка\"",
+            );
+        assert_eq!(String::from_utf8(rebuilt).unwrap(), expected);
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::HashSet;
+
+use quick_xml::escape::unescape;
+
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::documents::ooxml::{self, Vocabulary};
+use crate::modules::documents::package::Package;
+use crate::modules::formats::markup::{self, Dialect, Node};
+
+const DRAWING: Vocabulary = Vocabulary {
+    paragraph: "a:p",
+    run: "a:r",
+    run_properties: "a:rPr",
+    text: "a:t",
+    tab: None,
+    line_break: None,
+    ignored: &[],
+    containers: &[],
+    preserve_space: false,
+};
+
+const PRESENTATION: &str = "ppt/presentation.xml";
+
+/// Text boxes with less source text are not checked for overflow.
+const MIN_OVERFLOW_CHARS: usize = 10;
+
+#[derive(Clone, Copy, PartialEq)]
+enum PartKind {
+    Slide,
+    Chart,
+    Notes,
+}
+
+/// Part of the deck with text and the 1-based number of its slide.
+struct Part {
+    name: String,
+    slide: usize,
+    kind: PartKind,
+}
+
+/// PowerPoint deck. Text frames, tables, charts and speaker notes of every
+/// slide are translated in slide order; runs with the same formatting are
+/// merged as in Word paragraphs. Layouts and masters are left alone.
+pub struct PptxDocument {
+    overflow_ratio: f64,
+}
+
+impl PptxDocument {
+    pub fn new(overflow_ratio: f64) -> Self {
+        Self { overflow_ratio }
+    }
+}
+
+impl DocumentFormat for PptxDocument {
+    fn name(&self) -> &'static str {
+        "pptx"
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        &["application/vnd.openxmlformats-officedocument.presentationml.presentation"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pptx"]
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let mut package = Package::open(data)?;
+        let parts = part_names(&parts(&mut package)?);
+        ooxml::extract(&mut package, &parts, &DRAWING)
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let mut package = Package::open(data)?;
+        let parts = part_names(&parts(&mut package)?);
+        let rewritten = ooxml::rebuild(&mut package, &parts, &DRAWING, translations)?;
+        package.rewrite(&rewritten)
+    }
+
+    /// Slide shapes whose translated text is longer than the source by more
+    /// than the overflow ratio. Shapes resizing to fit their text are skipped.
+    fn warnings(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<String>> {
+        let mut package = Package::open(data)?;
+        let mut translations = translations.iter();
+        let mut warnings = Vec::new();
+        for part in parts(&mut package)? {
+            let xml = package.read(&part.name)?;
+            let mut translated = Vec::new();
+            for paragraph in ooxml::paragraphs(&xml, &DRAWING)? {
+                let Some(fragment) = paragraph.fragment() else {
+                    continue;
+                };
+                let translation = translations.next().ok_or_else(|| {
+                    DocumentErrors::Malformed("fewer translations than paragraphs".to_string())
+                })?;
+                translated.push((
+                    paragraph.range().start,
+                    text_length(&fragment),
+                    text_length(translation),
+                ));
+            }
+            if part.kind != PartKind::Slide || translated.is_empty() {
+                continue;
+            }
+
+            let nodes = markup::parse(&xml, Dialect::Xml)
+                .map_err(|err| DocumentErrors::Malformed(err.to_string()))?;
+            for shape in ooxml::elements(&nodes, "p:sp") {
+                if !ooxml::elements(&shape.children, "a:spAutoFit").is_empty() {
+                    continue;
+                }
+                let range = shape.range();
+                let (source, translation) = translated
+                    .iter()
+                    .filter(|(start, _, _)| range.contains(start))
+                    .fold((0, 0), |(source, translation), (_, length, translated)| {
+                        (source + length, translation + translated)
+                    });
+                if source < MIN_OVERFLOW_CHARS
+                    || (translation as f64) <= source as f64 * self.overflow_ratio
+                {
+                    continue;
+                }
+                let name = ooxml::elements(&shape.children, "p:cNvPr")
+                    .first()
+                    .and_then(|properties| ooxml::attribute(properties, "name"))
+                    .unwrap_or_default();
+                let longer = (translation as f64 / source as f64 - 1.0) * 100.0;
+                warnings.push(format!(
+                    "Slide {}, shape \"{name}\": translation is {longer:.0}% longer than the source and may overflow",
+                    part.slide
+                ));
+            }
+        }
+        Ok(warnings)
+    }
+}
+
+/// Slides in presentation order, each followed by its charts and notes.
+fn parts(package: &mut Package) -> DocumentResult<Vec<Part>> {
+    let names: HashSet<String> = package.names().into_iter().collect();
+    let relationships = package.relationships(PRESENTATION)?;
+    let presentation = package.read(PRESENTATION)?;
+    let nodes = markup::parse(&presentation, Dialect::Xml)
+        .map_err(|err| DocumentErrors::Malformed(err.to_string()))?;
+
+    let mut parts = Vec::new();
+    let mut seen = HashSet::new();
+    let slides = ooxml::elements(&nodes, "p:sldId");
+    for (number, slide) in slides.iter().enumerate() {
+        let Some(slide) = slide.attribute("r:id").and_then(|id| {
+            relationships
+                .iter()
+                .find(|relationship| relationship.id == id)
+                .map(|relationship| relationship.target.clone())
+        }) else {
+            continue;
+        };
+        let mut related = vec![(slide.clone(), PartKind::Slide)];
+        let slide_relationships = package.relationships(&slide)?;
+        for (suffix, kind) in [
+            ("/chart", PartKind::Chart),
+            ("/notesSlide", PartKind::Notes),
+        ] {
+            for relationship in &slide_relationships {
+                if relationship.kind.ends_with(suffix) {
+                    related.push((relationship.target.clone(), kind));
+                }
+            }
+        }
+        for (name, kind) in related {
+            if names.contains(&name) && seen.insert(name.clone()) {
+                parts.push(Part {
+                    name,
+                    slide: number + 1,
+                    kind,
+                });
+            }
+        }
+    }
+    Ok(parts)
+}
+
+fn part_names(parts: &[Part]) -> Vec<String> {
+    parts.iter().map(|part| part.name.clone()).collect()
+}
+
+/// Characters of text in a paragraph fragment, without its tags.
+fn text_length(fragment: &str) -> usize {
+    fn length(fragment: &str, nodes: &[Node]) -> usize {
+        nodes
+            .iter()
+            .map(|node| match node {
+                Node::Text(range) => {
+                    let raw = &fragment[range.clone()];
+                    unescape(raw).map_or(raw.chars().count(), |text| text.chars().count())
+                }
+                Node::Element(element) => length(fragment, &element.children),
+                Node::Other(_) => 0,
+            })
+            .sum()
+    }
+    markup::parse(fragment, Dialect::Xml)
+        .map(|nodes| length(fragment, &nodes))
+        .unwrap_or_else(|_| fragment.chars().count())
+}
+
+#[cfg(test)]
+mod test_pptx {
+    use std::io::{Cursor, Read, Write};
+
+    use zip::write::SimpleFileOptions;
+    use zip::{ZipArchive, ZipWriter};
+
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::models::DocumentOptions;
+    use crate::modules::documents::pptx::PptxDocument;
+
+    const PRESENTATION: &str = r#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:sldIdLst><p:sldId id="256" r:id="rId3"/><p:sldId id="257" r:id="rId2"/></p:sldIdLst></p:presentation>"#;
+
+    const PRESENTATION_RELATIONSHIPS: &str = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="slideMasters/slideMaster1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide1.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide2.xml"/></Relationships>"#;
+
+    const TITLE_SLIDE: &str = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="2" name="Title 1"/></p:nvSpPr><p:txBody><a:bodyPr><a:normAutofit/></a:bodyPr><a:p><a:pPr algn="ctr"/><a:r><a:rPr lang="en-US" b="1"/><a:t>Night march</a:t></a:r><a:br><a:rPr lang="en-US"/></a:br><a:r><a:rPr lang="en-US"/><a:t>Briefing</a:t></a:r><a:endParaRPr lang="en-US"/></a:p></p:txBody></p:sp><p:sp><p:nvSpPr><p:cNvPr id="3" name="Subtitle 2"/></p:nvSpPr><p:txBody><a:bodyPr><a:spAutoFit/></a:bodyPr><a:p><a:r><a:rPr lang="en-US"/><a:t>Company level</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
+
+    const TITLE_SLIDE_RELATIONSHIPS: &str = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide1.xml"/></Relationships>"#;
+
+    const NOTES: &str = r#"<p:notes xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree><p:sp><p:txBody><a:p><a:r><a:t>Keep it short.</a:t></a:r></a:p></p:txBody></p:sp><p:sp><p:txBody><a:p><a:fld id="{1}" type="slidenum"><a:t>1</a:t></a:fld></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:notes>"#;
+
+    const TABLE_SLIDE: &str = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree><p:graphicFrame><a:graphic><a:graphicData><a:tbl><a:tr><a:tc><a:txBody><a:bodyPr/><a:p><a:r><a:t>Water</a:t></a:r></a:p></a:txBody></a:tc></a:tr></a:tbl></a:graphicData></a:graphic></p:graphicFrame><p:graphicFrame><a:graphic><a:graphicData><c:chart r:id="rId2"/></a:graphicData></a:graphic></p:graphicFrame></p:spTree></p:cSld></p:sld>"#;
+
+    const TABLE_SLIDE_RELATIONSHIPS: &str = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart" Target="../charts/chart1.xml"/></Relationships>"#;
+
+    const CHART: &str = r#"<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><c:chart><c:title><c:tx><c:rich><a:bodyPr/><a:p><a:r><a:t>Supplies</a:t></a:r></a:p></c:rich></c:tx></c:title></c:chart></c:chartSpace>"#;
+
+    const LAYOUT: &str = r#"<p:sldLayout xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><a:p><a:r><a:t>Click to edit Master title style</a:t></a:r></a:p></p:sldLayout>"#;
+
+    fn package() -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in [
+            ("ppt/presentation.xml", PRESENTATION),
+            (
+                "ppt/_rels/presentation.xml.rels",
+                PRESENTATION_RELATIONSHIPS,
+            ),
+            ("ppt/slides/slide1.xml", TABLE_SLIDE),
+            (
+                "ppt/slides/_rels/slide1.xml.rels",
+                TABLE_SLIDE_RELATIONSHIPS,
+            ),
+            ("ppt/slides/slide2.xml", TITLE_SLIDE),
+            (
+                "ppt/slides/_rels/slide2.xml.rels",
+                TITLE_SLIDE_RELATIONSHIPS,
+            ),
+            ("ppt/notesSlides/notesSlide1.xml", NOTES),
+            ("ppt/charts/chart1.xml", CHART),
+            ("ppt/slideLayouts/slideLayout1.xml", LAYOUT),
+        ] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_pptx_roundtrip() {
+        let data = package();
+        let document = PptxDocument::new(1.3);
+        let options = DocumentOptions::default();
+        let segments = document.extract(&data, &options).unwrap();
+        let fragments: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(
+            fragments,
+            vec![
+                "<p>Night march<x2/><g1>Briefing</g1></p>",
+                "<p>Company level</p>",
+                "<p>Keep it short.</p>",
+                "<p>Water</p>",
+                "<p>Supplies</p>",
+            ]
+        );
+
+        let translations = vec![
+            "<p>Ночной марш-бросок<x2/><g1>Постановка задачи</g1></p>".to_owned(),
+            "<p>Уровень роты и батальона</p>".to_owned(),
+            "<p>Коротко.</p>".to_owned(),
+            "<p>Вода</p>".to_owned(),
+            "<p>Запасы</p>".to_owned(),
+        ];
+        let warnings = document.warnings(&data, &options, &translations).unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                "Slide 1, shape \"Title 1\": translation is 84% longer than the source and may overflow"
+            ]
+        );
+
+        let rebuilt = document.rebuild(&data, &options, &translations).unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(rebuilt.as_slice())).unwrap();
+        let mut slide = String::new();
+        archive
+            .by_name("ppt/slides/slide2.xml")
+            .unwrap()
+            .read_to_string(&mut slide)
+            .unwrap();
+        assert!(slide.contains(
+            "<a:p><a:pPr algn=\"ctr\"/><a:r><a:rPr lang=\"en-US\" b=\"1\"/><a:t>Ночной марш-бросок</a:t></a:r>\
+             <a:br><a:rPr lang=\"en-US\"/></a:br><a:r><a:rPr lang=\"en-US\"/><a:t>Постановка задачи</a:t></a:r>\
+             <a:endParaRPr lang=\"en-US\"/></a:p>"
+        ));
+        let mut layout = String::new();
+        archive
+            .by_name("ppt/slideLayouts/slideLayout1.xml")
+            .unwrap()
+            .read_to_string(&mut layout)
+            .unwrap();
+        assert_eq!(layout, LAYOUT);
+    }
+}
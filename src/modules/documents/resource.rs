@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::documents::placeholders::{self, Protected};
+use crate::modules::documents::{DocumentFormat, json, ooxml, yaml};
+use crate::modules::formats::icu;
+use crate::modules::formats::models::TextFormat;
+
+const BOM: &str = "\u{feff}";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResourceSyntax {
+    Json,
+    Yaml,
+}
+
+/// How a string leaf is written, so its translation is written the same way.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ScalarStyle {
+    Json,
+    Plain,
+    SingleQuoted,
+    DoubleQuoted,
+    /// YAML `|` or `>` block with its header and the indentation of its lines.
+    Block {
+        header: String,
+        indent: String,
+        folded: bool,
+    },
+}
+
+/// String value of a resource with its key path.
+pub struct Leaf {
+    pub path: Vec<String>,
+    pub value: String,
+    /// The value as written, quotes and block header included.
+    pub range: Range<usize>,
+    pub style: ScalarStyle,
+}
+
+/// String values of a JSON or YAML resource in document order.
+pub struct Resource {
+    pub leaves: Vec<Leaf>,
+    /// Key of the only top-level entry, with its range: the locale of a Rails
+    /// style file.
+    pub root: Option<(String, Range<usize>)>,
+}
+
+impl Resource {
+    /// Path of a leaf below the root when the root is the given locale.
+    fn relative<'l>(&self, leaf: &'l Leaf, language: &str) -> &'l [String] {
+        match &self.root {
+            Some((root, _)) if same_language(root, language) => &leaf.path[1..],
+            _ => &leaf.path,
+        }
+    }
+}
+
+/// Nested key-value i18n resource (i18next, vue-i18n, Rails). Only string
+/// leaves are translated, with interpolation tokens kept as `<xN/>` tags and
+/// ICU messages with plural or select arguments translated as such; keys,
+/// ordering, comments and plural sub-keys stay as they are.
+pub struct ResourceDocument {
+    syntax: ResourceSyntax,
+}
+
+impl ResourceDocument {
+    pub fn new(syntax: ResourceSyntax) -> Self {
+        Self { syntax }
+    }
+
+    fn parse<'d>(&self, data: &'d [u8]) -> DocumentResult<(&'d str, Resource)> {
+        let text = std::str::from_utf8(data)
+            .map_err(|err| DocumentErrors::Malformed(format!("resource is not UTF-8: {err}")))?;
+        let resource = match self.syntax {
+            ResourceSyntax::Json => json::parse(text)?,
+            ResourceSyntax::Yaml => yaml::parse(text)?,
+        };
+        Ok((text, resource))
+    }
+
+    /// Values of the existing target file by path below its locale root.
+    fn existing(&self, options: &DocumentOptions) -> DocumentResult<HashMap<Vec<String>, String>> {
+        let Some(data) = options.existing_target() else {
+            return Ok(HashMap::new());
+        };
+        let (_, existing) = self.parse(data)?;
+        Ok(existing
+            .leaves
+            .iter()
+            .filter(|leaf| !leaf.value.trim().is_empty())
+            .map(|leaf| {
+                let path = existing.relative(leaf, options.target_language()).to_vec();
+                (path, leaf.value.clone())
+            })
+            .collect())
+    }
+
+    /// Leaves to translate: with text besides their tokens and missing from
+    /// the existing target file.
+    fn pending<'r>(
+        &self,
+        resource: &'r Resource,
+        existing: &HashMap<Vec<String>, String>,
+        options: &DocumentOptions,
+    ) -> Vec<(&'r Leaf, Option<Protected>)> {
+        resource
+            .leaves
+            .iter()
+            .filter(|leaf| {
+                !existing.contains_key(resource.relative(leaf, options.source_language()))
+            })
+            .filter_map(|leaf| {
+                if icu::has_branches(&leaf.value) {
+                    return Some((leaf, None));
+                }
+                let protected = placeholders::protect(&leaf.value);
+                protected.has_text().then_some((leaf, Some(protected)))
+            })
+            .collect()
+    }
+
+    fn encode(&self, text: &str, style: &ScalarStyle, value: &str) -> String {
+        match self.syntax {
+            ResourceSyntax::Json => json::encode(value),
+            ResourceSyntax::Yaml => {
+                let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+                yaml::encode(style, value, newline)
+            }
+        }
+    }
+}
+
+impl DocumentFormat for ResourceDocument {
+    fn name(&self) -> &'static str {
+        match self.syntax {
+            ResourceSyntax::Json => "json",
+            ResourceSyntax::Yaml => "yaml",
+        }
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        match self.syntax {
+            ResourceSyntax::Json => &["application/json"],
+            ResourceSyntax::Yaml => &[
+                "application/yaml",
+                "application/x-yaml",
+                "text/yaml",
+                "text/x-yaml",
+            ],
+        }
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        match self.syntax {
+            ResourceSyntax::Json => &["json"],
+            ResourceSyntax::Yaml => &["yaml", "yml"],
+        }
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let (_, resource) = self.parse(data)?;
+        let existing = self.existing(options)?;
+        Ok(self
+            .pending(&resource, &existing, options)
+            .into_iter()
+            .map(|(leaf, protected)| match protected {
+                Some(protected) => DocumentSegment::new(protected.text, TextFormat::Xml),
+                None => DocumentSegment::new(leaf.value.clone(), TextFormat::Icu),
+            })
+            .collect())
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let (text, resource) = self.parse(data)?;
+        let existing = self.existing(options)?;
+        let pending = self.pending(&resource, &existing, options);
+        if pending.len() != translations.len() {
+            return Err(DocumentErrors::Malformed(format!(
+                "{} translations for {} strings",
+                translations.len(),
+                pending.len()
+            )));
+        }
+
+        let mut replacements = Vec::new();
+        if let Some((root, range)) = &resource.root
+            && same_language(root, options.source_language())
+            && !options.target_language().is_empty()
+        {
+            replacements.push((range.clone(), options.target_language().to_owned()));
+        }
+        for leaf in &resource.leaves {
+            if let Some(value) = existing.get(resource.relative(leaf, options.source_language())) {
+                replacements.push((leaf.range.clone(), self.encode(text, &leaf.style, value)));
+            }
+        }
+        for ((leaf, protected), translation) in pending.into_iter().zip(translations) {
+            let value = match protected {
+                Some(protected) => placeholders::restore(translation, &protected.tokens),
+                None => translation.clone(),
+            };
+            replacements.push((leaf.range.clone(), self.encode(text, &leaf.style, &value)));
+        }
+        replacements.sort_by_key(|(range, _)| range.start);
+        Ok(ooxml::replace(text, &replacements).into_bytes())
+    }
+}
+
+/// Same language by primary subtag: `en`, `en-US` and `en_GB` all match `en`.
+fn same_language(locale: &str, language: &str) -> bool {
+    let primary = |tag: &str| {
+        tag.trim_start_matches(BOM)
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    !language.is_empty() && primary(locale) == primary(language)
+}
+
+#[cfg(test)]
+mod test_resource {
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::models::DocumentOptions;
+    use crate::modules::documents::resource::{ResourceDocument, ResourceSyntax};
+    use crate::modules::formats::models::TextFormat;
+
+    const JSON: &str = r#"{
+  "nav": {
+    "home": "Home",
+    "greeting": "Hello, {{name}}!"
+  },
+  "items_one": "{{count}} item",
+  "items_other": "{{count}} items",
+  "limit": 10,
+  "tags": ["New", "{{count}}"],
+  "files": "{count, plural, one {# file} other {# files}}"
+}"#;
+
+    const YAML: &str = r#"# Rails locale
+en:
+  activerecord:
+    errors:
+      messages:
+        blank: "can't be blank"
+        too_long:
+          one: is too long (maximum is %{count} character)
+          other: 'is too long (maximum is %{count} characters)'
+  help: |
+    Read the manual.
+    Ask the sergeant.
+  enabled: true
+"#;
+
+    const EXISTING_YAML: &str = r#"ru:
+  activerecord:
+    errors:
+      messages:
+        blank: не может быть пустым
+"#;
+
+    fn options() -> DocumentOptions {
+        DocumentOptions::default().with_languages("en".to_string(), "ru".to_string())
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let document = ResourceDocument::new(ResourceSyntax::Json);
+        let segments = document.extract(JSON.as_bytes(), &options()).unwrap();
+        let texts: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Home",
+                "Hello, <x1/>!",
+                "<x1/> item",
+                "<x1/> items",
+                "New",
+                "{count, plural, one {# file} other {# files}}"
+            ]
+        );
+        assert_eq!(segments[5].format(), TextFormat::Icu);
+
+        let translations = vec![
+            "Главная".to_string(),
+            "Привет, <x1/>!".to_string(),
+            "<x1/> \"предмет\"".to_string(),
+            "<x1/> предметов".to_string(),
+            "Новое".to_string(),
+            "{count, plural, one {# файл} few {# файла} many {# файлов} other {# файла}}"
+                .to_string(),
+        ];
+        let rebuilt = document
+            .rebuild(JSON.as_bytes(), &options(), &translations)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(rebuilt).unwrap(),
+            r#"{
+  "nav": {
+    "home": "Главная",
+    "greeting": "Привет, {{name}}!"
+  },
+  "items_one": "{{count}} \"предмет\"",
+  "items_other": "{{count}} предметов",
+  "limit": 10,
+  "tags": ["Новое", "{{count}}"],
+  "files": "{count, plural, one {# файл} few {# файла} many {# файлов} other {# файла}}"
+}"#
+        );
+    }
+
+    #[test]
+    fn test_yaml_fills_missing_keys() {
+        let document = ResourceDocument::new(ResourceSyntax::Yaml);
+        let options = options().with_existing_target(EXISTING_YAML.as_bytes().to_vec());
+        let segments = document.extract(YAML.as_bytes(), &options).unwrap();
+        let texts: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "is too long (maximum is <x1/> character)",
+                "is too long (maximum is <x1/> characters)",
+                "Read the manual.\nAsk the sergeant.",
+            ]
+        );
+
+        let translations = vec![
+            "слишком длинный (не более <x1/> символа)".to_string(),
+            "слишком длинный (не более <x1/> символов)".to_string(),
+            "Прочитайте руководство.\nСпросите сержанта.".to_string(),
+        ];
+        let rebuilt = document
+            .rebuild(YAML.as_bytes(), &options, &translations)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(rebuilt).unwrap(),
+            r#"# Rails locale
+ru:
+  activerecord:
+    errors:
+      messages:
+        blank: "не может быть пустым"
+        too_long:
+          one: слишком длинный (не более %{count} символа)
+          other: 'слишком длинный (не более %{count} символов)'
+  help: |
+    Прочитайте руководство.
+    Спросите сержанта.
+  enabled: true
+"#
+        );
+    }
+}
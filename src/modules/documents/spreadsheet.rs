@@ -0,0 +1,182 @@
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+
+use crate::modules::documents::errors::DocumentErrors;
+use crate::modules::documents::models::DocumentOptions;
+
+/// Largest column of a sheet, `XFD`.
+const MAX_COLUMN: u32 = 16384;
+
+/// Cells of a sheet in A1 notation: `B`, `B:D`, `2:5`, `B2`, `A1:C20`,
+/// optionally prefixed with a sheet name as in `Terms!B:B` or `'Q1 report'!A2:A40`.
+/// Columns and rows are 1-based and inclusive.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CellRange {
+    sheet: Option<String>,
+    columns: Option<(u32, u32)>,
+    rows: Option<(u32, u32)>,
+}
+
+impl CellRange {
+    pub fn contains(&self, sheet: &str, column: u32, row: u32) -> bool {
+        let within = |bounds: Option<(u32, u32)>, value: u32| {
+            bounds.is_none_or(|(first, last)| (first..=last).contains(&value))
+        };
+        self.sheet
+            .as_deref()
+            .is_none_or(|name| same_sheet(name, sheet))
+            && within(self.columns, column)
+            && within(self.rows, row)
+    }
+}
+
+impl FromStr for CellRange {
+    type Err = DocumentErrors;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || DocumentErrors::InvalidOption(format!("invalid cell range `{value}`"));
+        let (sheet, cells) = match value.rsplit_once('!') {
+            Some((sheet, cells)) => {
+                let sheet = match sheet.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+                    Some(quoted) => quoted.replace("''", "'"),
+                    None => sheet.to_owned(),
+                };
+                (Some(sheet).filter(|sheet| !sheet.is_empty()), cells)
+            }
+            None => (None, value),
+        };
+        let (first, last) = cells.split_once(':').unwrap_or((cells, cells));
+        let first = parse_reference(first.trim()).ok_or_else(invalid)?;
+        let last = parse_reference(last.trim()).ok_or_else(invalid)?;
+        let bounds = |first: Option<u32>, last: Option<u32>| match (first, last) {
+            (Some(first), Some(last)) => Ok(Some((first.min(last), first.max(last)))),
+            (None, None) => Ok(None),
+            _ => Err(invalid()),
+        };
+        let columns = bounds(first.0, last.0)?;
+        let rows = bounds(first.1, last.1)?;
+        if columns.is_none() && rows.is_none() {
+            return Err(invalid());
+        }
+        Ok(CellRange {
+            sheet,
+            columns,
+            rows,
+        })
+    }
+}
+
+/// Column and row of a reference such as `B12`, `B` or `12`.
+fn parse_reference(reference: &str) -> Option<(Option<u32>, Option<u32>)> {
+    let reference = reference.replace('$', "");
+    let split = reference
+        .find(|ch: char| !ch.is_ascii_alphabetic())
+        .unwrap_or(reference.len());
+    let (letters, digits) = reference.split_at(split);
+    if letters.is_empty() && digits.is_empty() {
+        return None;
+    }
+    let column = match letters {
+        "" => None,
+        letters => Some(column_number(letters)?),
+    };
+    let row = match digits {
+        "" => None,
+        digits => Some(digits.parse::<u32>().ok().filter(|row| *row > 0)?),
+    };
+    Some((column, row))
+}
+
+/// Column and row of a cell reference such as `B12`.
+pub fn cell_reference(reference: &str) -> Option<(u32, u32)> {
+    match parse_reference(reference)? {
+        (Some(column), Some(row)) => Some((column, row)),
+        _ => None,
+    }
+}
+
+/// 1-based number of a column name: `A` is 1, `AA` is 27.
+pub fn column_number(name: &str) -> Option<u32> {
+    if name.is_empty() || name.len() > 3 {
+        return None;
+    }
+    let mut number = 0;
+    for ch in name.chars() {
+        if !ch.is_ascii_alphabetic() {
+            return None;
+        }
+        number = number * 26 + (ch.to_ascii_uppercase() as u32 - 'A' as u32 + 1);
+    }
+    Some(number).filter(|number| *number <= MAX_COLUMN)
+}
+
+/// Name of a 1-based column number.
+pub fn column_name(mut number: u32) -> String {
+    let mut name = Vec::new();
+    while number > 0 {
+        let rest = (number - 1) % 26;
+        name.push(char::from(b'A' + rest as u8));
+        number = (number - 1) / 26;
+    }
+    name.iter().rev().collect()
+}
+
+fn same_sheet(name: &str, other: &str) -> bool {
+    name.to_lowercase() == other.to_lowercase()
+}
+
+/// The sheet is requested by the sheet list, or by a range when there is no list.
+pub fn sheet_selected(options: &DocumentOptions, sheet: &str) -> bool {
+    if !options.sheets().is_empty() {
+        return options.sheets().iter().any(|name| same_sheet(name, sheet));
+    }
+    // Without a sheet list, ranges naming a sheet pick that sheet and ranges
+    // without one apply to every sheet.
+    options.ranges().is_empty()
+        || options.ranges().iter().any(|range| {
+            range
+                .sheet
+                .as_deref()
+                .is_none_or(|name| same_sheet(name, sheet))
+        })
+}
+
+/// The cell is on a selected sheet and in one of the ranges, if any are given.
+pub fn cell_selected(options: &DocumentOptions, sheet: &str, column: u32, row: u32) -> bool {
+    sheet_selected(options, sheet)
+        && (options.ranges().is_empty()
+            || options
+                .ranges()
+                .iter()
+                .any(|range| range.contains(sheet, column, row)))
+}
+
+/// Columns translations are written to when they go next to the source
+/// column: the first column right of it that holds no value and is not taken
+/// by another source column.
+pub struct NewColumns {
+    used: BTreeSet<u32>,
+    targets: HashMap<u32, u32>,
+}
+
+impl NewColumns {
+    pub fn new(used: impl IntoIterator<Item = u32>) -> Self {
+        Self {
+            used: used.into_iter().collect(),
+            targets: HashMap::new(),
+        }
+    }
+
+    pub fn target(&mut self, source: u32) -> u32 {
+        if let Some(target) = self.targets.get(&source) {
+            return *target;
+        }
+        let mut target = source + 1;
+        while self.used.contains(&target) {
+            target += 1;
+        }
+        self.used.insert(target);
+        self.targets.insert(source, target);
+        target
+    }
+}
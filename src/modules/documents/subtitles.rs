@@ -0,0 +1,811 @@
+use std::ops::Range;
+
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::formats::models::TextFormat;
+
+const BOM: &str = "\u{feff}";
+
+/// Longest pause between two cues that still continue one sentence.
+const MAX_SENTENCE_GAP_MS: u64 = 1500;
+
+/// Most cues merged into one sentence, so a missing full stop does not glue a whole file.
+const MAX_SENTENCE_CUES: usize = 6;
+
+/// Pause kept before the next cue when a cue is lengthened for reading speed.
+const MIN_CUE_GAP_MS: u64 = 40;
+
+/// Characters that end a sentence, looked up after closing quotes and brackets.
+const SENTENCE_ENDS: &[char] = &['.', '!', '?', '…', '。', '！', '？'];
+
+const CLOSING_PUNCTUATION: &[char] = &['"', '\'', '”', '’', '»', ')', ']'];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SubtitleFormat {
+    Srt,
+    WebVtt,
+}
+
+/// Subtitle file translated sentence by sentence. Cues that split a sentence
+/// are merged for translation and the translation is cut back across them in
+/// proportion to their duration; numbering, timestamps, cue settings and
+/// styling tags stay as they are.
+pub struct SubtitleDocument {
+    format: SubtitleFormat,
+}
+
+impl SubtitleDocument {
+    pub fn new(format: SubtitleFormat) -> Self {
+        Self { format }
+    }
+}
+
+impl DocumentFormat for SubtitleDocument {
+    fn name(&self) -> &'static str {
+        match self.format {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::WebVtt => "webvtt",
+        }
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        match self.format {
+            SubtitleFormat::Srt => &["application/x-subrip", "text/srt"],
+            SubtitleFormat::WebVtt => &["text/vtt"],
+        }
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        match self.format {
+            SubtitleFormat::Srt => &["srt"],
+            SubtitleFormat::WebVtt => &["vtt"],
+        }
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let subtitles = Subtitles::parse(data, self.format)?;
+        Ok(subtitles
+            .sentences()
+            .into_iter()
+            .map(|sentence| {
+                let format = if sentence.text.contains('<') {
+                    TextFormat::Html
+                } else {
+                    TextFormat::Plain
+                };
+                DocumentSegment::new(sentence.text, format)
+            })
+            .collect())
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let subtitles = Subtitles::parse(data, self.format)?;
+        let (layouts, _) = subtitles.layout(options, translations)?;
+
+        let mut replacements = Vec::new();
+        for layout in layouts {
+            let cue = &subtitles.cues[layout.cue];
+            replacements.push((
+                cue.text_range.clone(),
+                cue.styling.apply(&layout.lines, subtitles.newline),
+            ));
+            if layout.end != cue.end {
+                replacements.push((
+                    cue.end_range.clone(),
+                    format_timestamp(layout.end, self.format, cue.end_hours),
+                ));
+            }
+        }
+        replacements.sort_by_key(|(range, _)| range.start);
+
+        let mut output = String::with_capacity(subtitles.text.len());
+        let mut position = 0;
+        for (range, replacement) in replacements {
+            output.push_str(&subtitles.text[position..range.start]);
+            output.push_str(&replacement);
+            position = range.end;
+        }
+        output.push_str(&subtitles.text[position..]);
+        Ok(output.into_bytes())
+    }
+
+    fn warnings(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<String>> {
+        let subtitles = Subtitles::parse(data, self.format)?;
+        let (_, warnings) = subtitles.layout(options, translations)?;
+        Ok(warnings)
+    }
+}
+
+struct Subtitles<'a> {
+    text: &'a str,
+    newline: &'static str,
+    cues: Vec<Cue>,
+}
+
+struct Cue {
+    start: u64,
+    end: u64,
+    /// Start timestamp as written, to point reviewers at the cue.
+    start_text: String,
+    end_range: Range<usize>,
+    /// Whether the end timestamp is written with hours; WebVTT may leave them out.
+    end_hours: bool,
+    text_range: Range<usize>,
+    lines: usize,
+    styling: Styling,
+    /// Text inside the styling, lines joined by spaces or, for dialogue, by newlines.
+    text: String,
+    /// Lines of different speakers, each starting with a dash.
+    dialogue: bool,
+}
+
+/// Tags wrapping the text of a cue, such as `<i>…</i>`, `{\an8}` or `<v Speaker>`.
+#[derive(Default, PartialEq, Eq)]
+struct Styling {
+    prefix: String,
+    suffix: String,
+    /// The tags wrap every line rather than the text as a whole.
+    per_line: bool,
+}
+
+impl Styling {
+    fn apply(&self, lines: &[String], newline: &str) -> String {
+        if self.per_line {
+            lines
+                .iter()
+                .map(|line| format!("{}{line}{}", self.prefix, self.suffix))
+                .collect::<Vec<_>>()
+                .join(newline)
+        } else {
+            format!("{}{}{}", self.prefix, lines.join(newline), self.suffix)
+        }
+    }
+}
+
+/// Cues translated together, with their joined text.
+struct Sentence {
+    cues: Range<usize>,
+    text: String,
+}
+
+/// Translated text of a cue and its end time, moved for reading speed.
+struct Layout {
+    cue: usize,
+    lines: Vec<String>,
+    end: u64,
+}
+
+impl<'a> Subtitles<'a> {
+    fn parse(data: &'a [u8], format: SubtitleFormat) -> DocumentResult<Self> {
+        let text = std::str::from_utf8(data)
+            .map_err(|err| DocumentErrors::Malformed(format!("subtitles are not UTF-8: {err}")))?;
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let lines = lines(text);
+
+        let mut cues = Vec::new();
+        let mut index = 0;
+        let mut header = format == SubtitleFormat::WebVtt;
+        while index < lines.len() {
+            if text[lines[index].clone()].trim().is_empty() {
+                index += 1;
+                continue;
+            }
+            let end = (index..lines.len())
+                .find(|&line| text[lines[line].clone()].trim().is_empty())
+                .unwrap_or(lines.len());
+            let block = &lines[index..end];
+            index = end;
+
+            let first = text[block[0].clone()].trim_start_matches(BOM);
+            if header {
+                if !first.starts_with("WEBVTT") {
+                    return Err(DocumentErrors::Malformed(
+                        "WebVTT file does not start with WEBVTT".to_string(),
+                    ));
+                }
+                header = false;
+                continue;
+            }
+            if format == SubtitleFormat::WebVtt
+                && ["NOTE", "STYLE", "REGION"]
+                    .iter()
+                    .any(|keyword| first.starts_with(keyword))
+            {
+                continue;
+            }
+            if let Some(cue) = Cue::parse(text, block)? {
+                cues.push(cue);
+            }
+        }
+        Ok(Self {
+            text,
+            newline,
+            cues,
+        })
+    }
+
+    /// Runs of cues forming one sentence, without cues that have no text.
+    fn sentences(&self) -> Vec<Sentence> {
+        let mut sentences = Vec::new();
+        let mut start = 0;
+        for (index, cue) in self.cues.iter().enumerate() {
+            let joins = self.cues.get(index + 1).is_some_and(|next| {
+                !cue.dialogue
+                    && !next.dialogue
+                    && !ends_sentence(&cue.text)
+                    && next.start.saturating_sub(cue.end) <= MAX_SENTENCE_GAP_MS
+                    && index + 1 - start < MAX_SENTENCE_CUES
+                    && next.styling == cue.styling
+            });
+            if joins {
+                continue;
+            }
+            let text = self.cues[start..=index]
+                .iter()
+                .map(|cue| cue.text.as_str())
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            if !visible(&text).trim().is_empty() {
+                sentences.push(Sentence {
+                    cues: start..index + 1,
+                    text,
+                });
+            }
+            start = index + 1;
+        }
+        sentences
+    }
+
+    /// Translations cut across the cues of their sentences and wrapped into
+    /// lines, with the limits of `options` applied and what breaks them anyway.
+    fn layout(
+        &self,
+        options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<(Vec<Layout>, Vec<String>)> {
+        let mut translations = translations.iter();
+        let mut layouts = Vec::new();
+        let mut warnings = Vec::new();
+        for sentence in self.sentences() {
+            let translation = translations.next().ok_or_else(|| {
+                DocumentErrors::Malformed("fewer translations than sentences".to_string())
+            })?;
+            let cues = &self.cues[sentence.cues.clone()];
+            let weights: Vec<u64> = cues
+                .iter()
+                .map(|cue| cue.end.saturating_sub(cue.start).max(1))
+                .collect();
+            for (offset, piece) in split(translation, &weights).into_iter().enumerate() {
+                let index = sentence.cues.start + offset;
+                let cue = &self.cues[index];
+                let mut lines: Vec<String> = Vec::new();
+                if cue.dialogue {
+                    lines = piece
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty())
+                        .map(str::to_owned)
+                        .collect();
+                }
+                if lines.len() < 2 {
+                    lines = wrap(&piece, cue.lines, options.max_line_chars());
+                }
+                if let Some(max) = options.max_line_chars() {
+                    for line in &lines {
+                        let length = visible(line).chars().count();
+                        if length > max {
+                            warnings.push(format!(
+                                "Cue {} at {}: line of {length} characters exceeds the limit of {max}",
+                                index + 1,
+                                cue.start_text
+                            ));
+                        }
+                    }
+                }
+                let end = self.reading_end(index, &lines, options, &mut warnings);
+                layouts.push(Layout {
+                    cue: index,
+                    lines,
+                    end,
+                });
+            }
+        }
+        Ok((layouts, warnings))
+    }
+
+    /// End time of a cue showing `lines`, moved later into the pause before
+    /// the next cue when the text is read too fast.
+    fn reading_end(
+        &self,
+        index: usize,
+        lines: &[String],
+        options: &DocumentOptions,
+        warnings: &mut Vec<String>,
+    ) -> u64 {
+        let cue = &self.cues[index];
+        let Some(max) = options.max_chars_per_second().filter(|max| *max > 0.0) else {
+            return cue.end;
+        };
+        let characters: usize = lines.iter().map(|line| visible(line).chars().count()).sum();
+        let needed = cue.start + (characters as f64 / max * 1000.0).ceil() as u64;
+        if needed <= cue.end {
+            return cue.end;
+        }
+
+        let latest = self
+            .cues
+            .get(index + 1)
+            .map_or(u64::MAX, |next| next.start.saturating_sub(MIN_CUE_GAP_MS));
+        let end = needed.min(latest).max(cue.end);
+        if end < needed {
+            let seconds = end.saturating_sub(cue.start).max(1) as f64 / 1000.0;
+            warnings.push(format!(
+                "Cue {} at {}: {:.1} characters per second exceeds the limit of {max}",
+                index + 1,
+                cue.start_text,
+                characters as f64 / seconds
+            ));
+        }
+        end
+    }
+}
+
+impl Cue {
+    /// Cue of a block of lines, or `None` when the block has no timing line or no text.
+    fn parse(text: &str, block: &[Range<usize>]) -> DocumentResult<Option<Self>> {
+        let Some(timing) = block
+            .iter()
+            .take(2)
+            .position(|line| text[line.clone()].contains("-->"))
+        else {
+            return Ok(None);
+        };
+        let text_lines = &block[timing + 1..];
+        let (Some(first), Some(last)) = (text_lines.first(), text_lines.last()) else {
+            return Ok(None);
+        };
+
+        let line = &block[timing];
+        let timing = &text[line.clone()];
+        let arrow = timing.find("-->").unwrap_or_default();
+        let start_text = timing[..arrow].trim().trim_start_matches(BOM);
+        let after = &timing[arrow + 3..];
+        let end_offset = line.start + arrow + 3 + after.len() - after.trim_start().len();
+        let end_text = after.split_whitespace().next().unwrap_or_default();
+        let invalid = |timestamp: &str| {
+            DocumentErrors::Malformed(format!("invalid subtitle timestamp \"{timestamp}\""))
+        };
+        let start = parse_timestamp(start_text).ok_or_else(|| invalid(start_text))?;
+        let end = parse_timestamp(end_text).ok_or_else(|| invalid(end_text))?;
+
+        let lines: Vec<&str> = text_lines.iter().map(|line| &text[line.clone()]).collect();
+        let wrapped: Vec<(&str, &str, &str)> = lines.iter().map(|line| unwrap(line)).collect();
+        let (prefix, _, suffix) = wrapped[0];
+        let per_line = lines.len() > 1
+            && !(prefix.is_empty() && suffix.is_empty())
+            && wrapped.iter().all(|(other_prefix, _, other_suffix)| {
+                (*other_prefix, *other_suffix) == (prefix, suffix)
+            });
+        let (styling, inner): (Styling, Vec<&str>) = if per_line {
+            (
+                Styling {
+                    prefix: prefix.to_owned(),
+                    suffix: suffix.to_owned(),
+                    per_line,
+                },
+                wrapped.iter().map(|(_, inner, _)| *inner).collect(),
+            )
+        } else {
+            let whole = &text[first.start..last.end];
+            let (prefix, inner, suffix) = unwrap(whole);
+            (
+                Styling {
+                    prefix: prefix.to_owned(),
+                    suffix: suffix.to_owned(),
+                    per_line,
+                },
+                inner.lines().collect(),
+            )
+        };
+        let inner: Vec<String> = inner
+            .iter()
+            .map(|line| strip_timestamps(line).trim().to_owned())
+            .filter(|line| !line.is_empty())
+            .collect();
+        let dialogue = inner.len() > 1 && inner.iter().all(|line| line.starts_with('-'));
+
+        Ok(Some(Self {
+            start,
+            end,
+            start_text: start_text.to_owned(),
+            end_range: end_offset..end_offset + end_text.len(),
+            end_hours: end_text.matches(':').count() == 2,
+            text_range: first.start..last.end,
+            lines: lines.len(),
+            styling,
+            text: inner.join(if dialogue { "\n" } else { " " }),
+            dialogue,
+        }))
+    }
+}
+
+/// Byte ranges of the lines of `text`, without their line breaks.
+fn lines(text: &str) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for piece in text.split_inclusive('\n') {
+        let content = piece.trim_end_matches(['\n', '\r']);
+        lines.push(offset..offset + content.len());
+        offset += piece.len();
+    }
+    lines
+}
+
+/// Milliseconds of `HH:MM:SS,mmm` (SRT) or `[HH:]MM:SS.mmm` (WebVTT).
+fn parse_timestamp(text: &str) -> Option<u64> {
+    let (clock, millis) = text.split_once([',', '.'])?;
+    if millis.len() != 3 {
+        return None;
+    }
+    let parts: Vec<&str> = clock.split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        return None;
+    }
+    let mut seconds = 0;
+    for part in parts {
+        seconds = seconds * 60 + part.parse::<u64>().ok()?;
+    }
+    Some(seconds * 1000 + millis.parse::<u64>().ok()?)
+}
+
+fn format_timestamp(millis: u64, format: SubtitleFormat, hours: bool) -> String {
+    let (hour, minute, second, milli) = (
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000,
+    );
+    match format {
+        SubtitleFormat::Srt => format!("{hour:02}:{minute:02}:{second:02},{milli:03}"),
+        SubtitleFormat::WebVtt if hours || hour > 0 => {
+            format!("{hour:02}:{minute:02}:{second:02}.{milli:03}")
+        }
+        SubtitleFormat::WebVtt => format!("{minute:02}:{second:02}.{milli:03}"),
+    }
+}
+
+/// Lowercase name of a tag given its content between `<` and `>`; empty for
+/// WebVTT timestamp tags.
+fn tag_name(content: &str) -> String {
+    let content = content.trim_start_matches('/');
+    if content.starts_with(|char: char| char.is_ascii_digit()) {
+        return String::new();
+    }
+    content
+        .split(|char: char| char.is_whitespace() || char == '.' || char == '/')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Splits `text` into the tags wrapping all of it, the text inside and the
+/// closing tags. An opening tag only counts when its closing tag ends the text,
+/// except for `{…}` overrides and `<v …>` voices that need none.
+fn unwrap(text: &str) -> (&str, &str, &str) {
+    let mut opening = Vec::new();
+    let mut offset = 0;
+    loop {
+        let rest = &text[offset..];
+        if rest.starts_with('{')
+            && let Some(end) = rest.find('}')
+        {
+            offset += end + 1;
+            opening.push((offset, None));
+        } else if rest.starts_with('<')
+            && !rest.starts_with("</")
+            && let Some(end) = rest.find('>')
+            && !tag_name(&rest[1..end]).is_empty()
+        {
+            opening.push((offset + end + 1, Some(tag_name(&rest[1..end]))));
+            offset += end + 1;
+        } else {
+            break;
+        }
+    }
+
+    let mut closing = Vec::new();
+    let mut end = text.len();
+    while text[..end].ends_with('>')
+        && let Some(start) = text[..end].rfind('<')
+        && start >= offset
+        && text[start..end].starts_with("</")
+    {
+        closing.push((start, tag_name(&text[start + 2..end - 1])));
+        end = start;
+    }
+
+    let mut closing = closing.into_iter().peekable();
+    let (mut prefix_end, mut suffix_start) = (0, text.len());
+    for (after, name) in opening {
+        match name {
+            None => prefix_end = after,
+            Some(name) => {
+                if let Some((start, _)) = closing.next_if(|(_, close)| *close == name) {
+                    prefix_end = after;
+                    suffix_start = start;
+                } else if name == "v" {
+                    prefix_end = after;
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+    (
+        &text[..prefix_end],
+        &text[prefix_end..suffix_start],
+        &text[suffix_start..],
+    )
+}
+
+/// `text` without WebVTT karaoke timestamps, which lose their meaning once the text is translated.
+fn strip_timestamps(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        output.push_str(&rest[..start]);
+        match rest[start..].find('>') {
+            Some(end) if rest[start + 1..].starts_with(|char: char| char.is_ascii_digit()) => {
+                rest = &rest[start + end + 1..];
+            }
+            _ => {
+                output.push('<');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Text as shown on screen, without tags and `{…}` overrides.
+fn visible(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut closer = None;
+    for char in text.chars() {
+        match closer {
+            Some(end) if char == end => closer = None,
+            Some(_) => {}
+            None if char == '<' => closer = Some('>'),
+            None if char == '{' => closer = Some('}'),
+            None => output.push(char),
+        }
+    }
+    output
+}
+
+fn ends_sentence(text: &str) -> bool {
+    visible(text)
+        .trim_end()
+        .trim_end_matches(CLOSING_PUNCTUATION)
+        .ends_with(SENTENCE_ENDS)
+}
+
+/// Places in `text` outside tags where it can be cut: whitespace runs when
+/// `words`, otherwise every character boundary.
+fn boundaries(text: &str, words: bool) -> Vec<Range<usize>> {
+    let mut boundaries: Vec<Range<usize>> = Vec::new();
+    let mut depth = 0usize;
+    let mut skip_to = 0;
+    for (position, char) in text.char_indices() {
+        if position < skip_to {
+            continue;
+        }
+        if char == '<'
+            && let Some(end) = text[position..].find('>')
+        {
+            let content = &text[position + 1..position + end];
+            if content.starts_with('/') {
+                depth = depth.saturating_sub(1);
+            } else if !content.ends_with('/')
+                && !matches!(tag_name(content).as_str(), "" | "v" | "br")
+            {
+                depth += 1;
+            }
+            skip_to = position + end + 1;
+            continue;
+        }
+        if char == '{'
+            && let Some(end) = text[position..].find('}')
+        {
+            skip_to = position + end + 1;
+            continue;
+        }
+        if depth > 0 || position == 0 {
+            continue;
+        }
+        if !words {
+            boundaries.push(position..position);
+        } else if char.is_whitespace() {
+            match boundaries.last_mut() {
+                Some(last) if last.end == position => last.end = position + char.len_utf8(),
+                _ => boundaries.push(position..position + char.len_utf8()),
+            }
+        }
+    }
+    boundaries.retain(|boundary| boundary.end < text.len());
+    boundaries
+}
+
+/// `text` cut at word boundaries into one piece per weight, sized like the weights.
+fn split(text: &str, weights: &[u64]) -> Vec<String> {
+    let text = text.trim();
+    if weights.len() < 2 {
+        return vec![text.to_owned()];
+    }
+    let mut breaks = boundaries(text, true);
+    if breaks.len() + 1 < weights.len() {
+        breaks = boundaries(text, false);
+    }
+
+    let total = weights.iter().sum::<u64>().max(1) as f64;
+    let length = visible(text).chars().count() as f64;
+    let position =
+        |boundary: &Range<usize>| visible(&text[..boundary.start]).chars().count() as f64;
+
+    let mut pieces = Vec::with_capacity(weights.len());
+    let (mut from, mut next, mut cumulative) = (0, 0, 0);
+    for (index, weight) in weights[..weights.len() - 1].iter().enumerate() {
+        cumulative += weight;
+        let target = length * cumulative as f64 / total;
+        let cuts_left = weights.len() - 2 - index;
+        let last = breaks.len().saturating_sub(cuts_left);
+        let best = (next..last).min_by(|a, b| {
+            (position(&breaks[*a]) - target)
+                .abs()
+                .total_cmp(&(position(&breaks[*b]) - target).abs())
+        });
+        match best {
+            Some(best) => {
+                pieces.push(text[from..breaks[best].start].trim().to_owned());
+                from = breaks[best].end;
+                next = best + 1;
+            }
+            None => pieces.push(String::new()),
+        }
+    }
+    pieces.push(text[from..].trim().to_owned());
+    pieces
+}
+
+/// Lines of `text`: filled up to `max_chars` when given, otherwise as many
+/// balanced lines as the cue had.
+fn wrap(text: &str, lines: usize, max_chars: Option<usize>) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut from = 0;
+    for boundary in boundaries(text, true) {
+        words.push(&text[from..boundary.start]);
+        from = boundary.end;
+    }
+    words.push(&text[from..]);
+    let length = |text: &str| visible(text).chars().count();
+
+    let target = match max_chars {
+        Some(max) => max,
+        None if lines < 2 || words.len() < 2 => return vec![text.to_owned()],
+        None => length(text).div_ceil(lines),
+    };
+    let mut output: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in words {
+        let joined = length(&current) + 1 + length(word);
+        let breaks = match max_chars {
+            Some(max) => joined > max,
+            None => {
+                output.len() + 1 < lines
+                    && joined > target
+                    && joined - target > target.saturating_sub(length(&current))
+            }
+        };
+        if !current.is_empty() && breaks {
+            output.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    output.push(current);
+    output
+}
+
+#[cfg(test)]
+mod test_subtitles {
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+    use crate::modules::documents::subtitles::{SubtitleDocument, SubtitleFormat};
+
+    const SRT: &str = "1\r\n00:00:01,000 --> 00:00:03,000\r\n<i>The column moves</i>\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\n<i>at dawn.</i>\r\n\r\n3\r\n00:00:05,000 --> 00:00:07,000 X1:10 X2:100\r\n{\\an8}Keep radios off.\r\n";
+
+    const VTT: &str = "WEBVTT\n\nNOTE recorded briefing\n\nintro\n00:01.000 --> 00:02.000 align:start\n<v Sergeant>Water and ammunition first.\n\n00:02.500 --> 00:06.000\n- Who leads?\n- I do.\n";
+
+    fn texts(segments: Vec<DocumentSegment>) -> Vec<String> {
+        segments
+            .into_iter()
+            .map(|segment| segment.text().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_srt_roundtrip() {
+        let document = SubtitleDocument::new(SubtitleFormat::Srt);
+        let options = DocumentOptions::default();
+        let segments = document.extract(SRT.as_bytes(), &options).unwrap();
+        assert_eq!(
+            texts(segments),
+            vec!["The column moves at dawn.", "Keep radios off."]
+        );
+
+        let translations = vec![
+            "Колонна выдвигается на рассвете.".to_string(),
+            "Рации выключить.".to_string(),
+        ];
+        let rebuilt = document
+            .rebuild(SRT.as_bytes(), &options, &translations)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(rebuilt).unwrap(),
+            "1\r\n00:00:01,000 --> 00:00:03,000\r\n<i>Колонна выдвигается на</i>\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\n<i>рассвете.</i>\r\n\r\n3\r\n00:00:05,000 --> 00:00:07,000 X1:10 X2:100\r\n{\\an8}Рации выключить.\r\n"
+        );
+    }
+
+    #[test]
+    fn test_vtt_limits() {
+        let document = SubtitleDocument::new(SubtitleFormat::WebVtt);
+        let options = DocumentOptions::default().with_subtitle_limits(Some(16), Some(15.0));
+        let segments = document.extract(VTT.as_bytes(), &options).unwrap();
+        assert_eq!(
+            texts(segments),
+            vec!["Water and ammunition first.", "- Who leads?\n- I do."]
+        );
+
+        let translations = vec![
+            "Сначала вода и боеприпасы.".to_string(),
+            "- Кто ведёт?\n- Я веду.".to_string(),
+        ];
+        let rebuilt = document
+            .rebuild(VTT.as_bytes(), &options, &translations)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(rebuilt).unwrap(),
+            "WEBVTT\n\nNOTE recorded briefing\n\nintro\n00:01.000 --> 00:02.460 align:start\n<v Sergeant>Сначала вода и\nбоеприпасы.\n\n00:02.500 --> 00:06.000\n- Кто ведёт?\n- Я веду.\n"
+        );
+
+        let warnings = document
+            .warnings(VTT.as_bytes(), &options, &translations)
+            .unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                "Cue 1 at 00:01.000: 17.1 characters per second exceeds the limit of 15"
+                    .to_string()
+            ]
+        );
+    }
+}
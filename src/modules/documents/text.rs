@@ -0,0 +1,78 @@
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::formats::models::TextFormat;
+
+const BOM: &str = "\u{feff}";
+
+/// UTF-8 text file translated as a whole in one of the request text formats.
+pub struct TextDocument {
+    format: TextFormat,
+}
+
+impl TextDocument {
+    pub fn new(format: TextFormat) -> Self {
+        Self { format }
+    }
+}
+
+impl DocumentFormat for TextDocument {
+    fn name(&self) -> &'static str {
+        match self.format {
+            TextFormat::Plain => "text",
+            TextFormat::Markdown => "markdown",
+            TextFormat::Html => "html",
+            TextFormat::Xml => "xml",
+            TextFormat::Icu => "icu",
+        }
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        match self.format {
+            TextFormat::Plain => &["text/plain"],
+            TextFormat::Markdown => &["text/markdown", "text/x-markdown"],
+            TextFormat::Html => &["text/html", "application/xhtml+xml"],
+            TextFormat::Xml => &["application/xml", "text/xml"],
+            TextFormat::Icu => &[],
+        }
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        match self.format {
+            TextFormat::Plain => &["txt", "text"],
+            TextFormat::Markdown => &["md", "markdown"],
+            TextFormat::Html => &["html", "htm", "xhtml"],
+            TextFormat::Xml => &["xml"],
+            TextFormat::Icu => &[],
+        }
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let text = decode(data)?;
+        Ok(vec![DocumentSegment::new(
+            text.strip_prefix(BOM).unwrap_or(text).to_owned(),
+            self.format,
+        )])
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let text = decode(data)?;
+        let translation = translations.first().map(String::as_str).unwrap_or_default();
+        let bom = if text.starts_with(BOM) { BOM } else { "" };
+        Ok(format!("{bom}{translation}").into_bytes())
+    }
+}
+
+fn decode(data: &[u8]) -> DocumentResult<&str> {
+    std::str::from_utf8(data)
+        .map_err(|err| DocumentErrors::Malformed(format!("text is not UTF-8: {err}")))
+}
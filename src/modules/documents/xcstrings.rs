@@ -0,0 +1,344 @@
+use std::io::Write;
+
+use serde::Serialize;
+use serde_json::ser::{Formatter, PrettyFormatter};
+use serde_json::{Map, Value};
+
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::documents::placeholders::{self, Protected};
+use crate::modules::formats::models::TextFormat;
+
+/// Target states that are kept; any other asks for a translation.
+const DONE_STATES: [&str; 2] = ["translated", "stale"];
+
+/// Xcode string catalog (`.xcstrings`). Every string unit of the source
+/// language, plural and device variations and substitutions included, gets a
+/// `translated` unit at the same place of the target language unless it
+/// already has one. Strings with `shouldTranslate: false` are skipped.
+pub struct XcstringsDocument;
+
+impl DocumentFormat for XcstringsDocument {
+    fn name(&self) -> &'static str {
+        "xcstrings"
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        &["application/json"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xcstrings"]
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let catalog = parse(data)?;
+        Ok(units(&catalog, options.target_language())?
+            .into_iter()
+            .map(|unit| DocumentSegment::new(unit.protected.text, TextFormat::Xml))
+            .collect())
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let mut catalog = parse(data)?;
+        let target = options.target_language();
+        let units = units(&catalog, target)?;
+        if units.len() != translations.len() {
+            return Err(DocumentErrors::Malformed(format!(
+                "{} translations for {} strings",
+                translations.len(),
+                units.len()
+            )));
+        }
+
+        for (unit, translation) in units.into_iter().zip(translations) {
+            let value = placeholders::restore(translation, &unit.protected.tokens);
+            placeholders::check_specifiers(&unit.key, &unit.source, &value)?;
+            let mut slot = catalog
+                .pointer_mut(&format!("/strings/{}", escape_pointer(&unit.key)))
+                .and_then(Value::as_object_mut)
+                .ok_or_else(|| DocumentErrors::Malformed(format!("string `{}` moved", unit.key)))?;
+            for key in ["localizations", target]
+                .into_iter()
+                .chain(unit.path.iter().map(String::as_str))
+            {
+                slot = slot
+                    .entry(key)
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .ok_or_else(|| {
+                        DocumentErrors::Malformed(format!(
+                            "`{key}` of `{}` is not an object",
+                            unit.key
+                        ))
+                    })?;
+            }
+            slot.insert(
+                "stringUnit".to_owned(),
+                serde_json::json!({ "state": "translated", "value": value }),
+            );
+        }
+
+        let mut output = Vec::with_capacity(data.len());
+        let mut serializer = serde_json::Serializer::with_formatter(
+            &mut output,
+            XcodeFormatter(PrettyFormatter::with_indent(b"  ")),
+        );
+        catalog
+            .serialize(&mut serializer)
+            .map_err(|err| DocumentErrors::Malformed(err.to_string()))?;
+        Ok(output)
+    }
+}
+
+/// Source string unit to translate.
+struct Unit {
+    key: String,
+    /// Keys from the localization to the unit, such as `variations/plural/one`.
+    path: Vec<String>,
+    source: String,
+    protected: Protected,
+}
+
+fn parse(data: &[u8]) -> DocumentResult<Value> {
+    let catalog: Value = serde_json::from_slice(data)
+        .map_err(|err| DocumentErrors::Malformed(format!("invalid string catalog: {err}")))?;
+    if !catalog.get("strings").is_some_and(Value::is_object) {
+        return Err(DocumentErrors::Malformed(
+            "string catalog has no `strings`".to_string(),
+        ));
+    }
+    Ok(catalog)
+}
+
+/// Units of the source language without a finished unit of the target, in
+/// key order. A string without a source localization is its own key.
+fn units(catalog: &Value, target: &str) -> DocumentResult<Vec<Unit>> {
+    if target.is_empty() {
+        return Err(DocumentErrors::InvalidOption(
+            "string catalogs need a target language".to_string(),
+        ));
+    }
+    let source_language = catalog
+        .get("sourceLanguage")
+        .and_then(Value::as_str)
+        .unwrap_or("en");
+    let Some(strings) = catalog.get("strings").and_then(Value::as_object) else {
+        return Ok(Vec::new());
+    };
+
+    let mut units = Vec::new();
+    for (key, string) in strings {
+        if string.get("shouldTranslate") == Some(&Value::Bool(false)) {
+            continue;
+        }
+        let localizations = string.get("localizations");
+        let existing = localizations.and_then(|localizations| localizations.get(target));
+        let mut sources = Vec::new();
+        match localizations.and_then(|localizations| localizations.get(source_language)) {
+            Some(localization) => string_units(localization, &mut Vec::new(), &mut sources),
+            None => sources.push((Vec::new(), key.clone())),
+        }
+        for (path, source) in sources {
+            let done = path
+                .iter()
+                .try_fold(existing, |value, key| Some(value?.get(key)))
+                .flatten()
+                .and_then(|value| value.pointer("/stringUnit/state"))
+                .and_then(Value::as_str)
+                .is_some_and(|state| DONE_STATES.contains(&state));
+            let protected = placeholders::protect(&source);
+            if done || !protected.has_text() {
+                continue;
+            }
+            units.push(Unit {
+                key: key.clone(),
+                path,
+                source,
+                protected,
+            });
+        }
+    }
+    Ok(units)
+}
+
+/// Values of the `stringUnit`s below a localization with their paths.
+fn string_units(value: &Value, path: &mut Vec<String>, units: &mut Vec<(Vec<String>, String)>) {
+    let Some(object) = value.as_object() else {
+        return;
+    };
+    for (key, child) in object {
+        if key == "stringUnit" {
+            if let Some(text) = child.get("value").and_then(Value::as_str) {
+                units.push((path.clone(), text.to_owned()));
+            }
+            continue;
+        }
+        path.push(key.clone());
+        string_units(child, path, units);
+        path.pop();
+    }
+}
+
+/// JSON pointer token of a key.
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Pretty printing as Xcode writes catalogs: two spaces and ` : ` between key and value.
+struct XcodeFormatter<'a>(PrettyFormatter<'a>);
+
+impl Formatter for XcodeFormatter<'_> {
+    fn begin_array<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.0.begin_array(writer)
+    }
+
+    fn end_array<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.0.end_array(writer)
+    }
+
+    fn begin_array_value<W: ?Sized + Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> std::io::Result<()> {
+        self.0.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.0.end_array_value(writer)
+    }
+
+    fn begin_object<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.0.begin_object(writer)
+    }
+
+    fn end_object<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.0.end_object(writer)
+    }
+
+    fn begin_object_key<W: ?Sized + Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> std::io::Result<()> {
+        self.0.begin_object_key(writer, first)
+    }
+
+    fn begin_object_value<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(b" : ")
+    }
+
+    fn end_object_value<W: ?Sized + Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.0.end_object_value(writer)
+    }
+}
+
+#[cfg(test)]
+mod test_xcstrings {
+    use serde_json::Value;
+
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::models::DocumentOptions;
+    use crate::modules::documents::xcstrings::XcstringsDocument;
+
+    const CATALOG: &str = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "Cancel" : {
+
+    },
+    "greeting" : {
+      "localizations" : {
+        "en" : {
+          "stringUnit" : {
+            "state" : "translated",
+            "value" : "Hello, %@!"
+          }
+        },
+        "ru" : {
+          "stringUnit" : {
+            "state" : "translated",
+            "value" : "Привет, %@!"
+          }
+        }
+      }
+    },
+    "rounds" : {
+      "localizations" : {
+        "en" : {
+          "variations" : {
+            "plural" : {
+              "one" : {
+                "stringUnit" : {
+                  "state" : "translated",
+                  "value" : "%lld round"
+                }
+              },
+              "other" : {
+                "stringUnit" : {
+                  "state" : "translated",
+                  "value" : "%lld rounds"
+                }
+              }
+            }
+          }
+        }
+      }
+    },
+    "v1.2" : {
+      "shouldTranslate" : false
+    }
+  },
+  "version" : "1.0"
+}"#;
+
+    #[test]
+    fn test_xcstrings_roundtrip() {
+        let document = XcstringsDocument;
+        let options = DocumentOptions::default().with_languages("en".to_string(), "ru".to_string());
+        let segments = document.extract(CATALOG.as_bytes(), &options).unwrap();
+        let texts: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(texts, vec!["Cancel", "<x1/> round", "<x1/> rounds"]);
+
+        let translations = vec![
+            "Отмена".to_string(),
+            "<x1/> выстрел".to_string(),
+            "<x1/> выстрелов".to_string(),
+        ];
+        let rebuilt = document
+            .rebuild(CATALOG.as_bytes(), &options, &translations)
+            .unwrap();
+        let rebuilt = String::from_utf8(rebuilt).unwrap();
+        assert!(rebuilt.starts_with("{\n  \"sourceLanguage\" : \"en\",\n"));
+        let catalog: Value = serde_json::from_str(&rebuilt).unwrap();
+        assert_eq!(
+            catalog.pointer("/strings/Cancel/localizations/ru/stringUnit/value"),
+            Some(&Value::from("Отмена"))
+        );
+        assert_eq!(
+            catalog.pointer(
+                "/strings/rounds/localizations/ru/variations/plural/other/stringUnit/value"
+            ),
+            Some(&Value::from("%lld выстрелов"))
+        );
+        assert_eq!(
+            catalog.pointer("/strings/greeting/localizations/ru/stringUnit/value"),
+            Some(&Value::from("Привет, %@!"))
+        );
+        assert!(catalog.pointer("/strings/v1.2/localizations").is_none());
+    }
+}
@@ -0,0 +1,341 @@
+use std::ops::Range;
+
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment};
+use crate::modules::documents::ooxml;
+use crate::modules::formats::markup::{self, Dialect, Element, Node};
+use crate::modules::formats::models::TextFormat;
+
+/// Target states of XLIFF 1.2 that still need a translation.
+const PENDING_STATES: [&str; 7] = [
+    "new",
+    "needs-translation",
+    "needs-adaptation",
+    "needs-l10n",
+    "needs-review-translation",
+    "needs-review-adaptation",
+    "needs-review-l10n",
+];
+
+/// Segment state of XLIFF 2.0 that still needs a translation, also the default.
+const INITIAL_STATE: &str = "initial";
+
+const TRANSLATED_STATE: &str = "translated";
+
+/// XLIFF 1.2 or 2.0 file. Units without a target, with an empty one or in a
+/// state asking for translation are filled and marked translated; inline tags
+/// of the source (`<g>`, `<x/>`, `<pc>`, `<ph/>`) are kept in the target.
+pub struct XliffDocument;
+
+impl DocumentFormat for XliffDocument {
+    fn name(&self) -> &'static str {
+        "xliff"
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        &["application/xliff+xml", "application/x-xliff+xml"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xlf", "xliff"]
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let xml = decode(data)?;
+        let nodes = parse(xml)?;
+        Ok(units(&nodes)
+            .into_iter()
+            .filter_map(|unit| unit.pending(xml))
+            .map(|source| DocumentSegment::new(xml[source].to_owned(), TextFormat::Xml))
+            .collect())
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        _options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let xml = decode(data)?;
+        let nodes = parse(xml)?;
+        let mut translations = translations.iter();
+        let mut replacements = Vec::new();
+        for unit in units(&nodes) {
+            if unit.pending(xml).is_none() {
+                continue;
+            }
+            let translation = translations.next().ok_or_else(|| {
+                DocumentErrors::Malformed("fewer translations than units".to_string())
+            })?;
+            unit.fill(xml, translation, &mut replacements);
+        }
+        replacements.sort_by_key(|(range, _)| range.start);
+        Ok(ooxml::replace(xml, &replacements).into_bytes())
+    }
+}
+
+/// Source and target of a 1.2 trans-unit or a 2.0 segment.
+struct Unit<'n> {
+    source: &'n Element,
+    target: Option<&'n Element>,
+    /// Element with the state attribute: the target in 1.2, the segment in 2.0.
+    state_holder: Option<&'n Element>,
+    version: Version,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Version {
+    V1,
+    V2,
+}
+
+impl Unit<'_> {
+    /// Inner XML of the source when the unit needs a translation.
+    fn pending(&self, xml: &str) -> Option<Range<usize>> {
+        let source = inner(self.source)?;
+        if !has_text(xml, &self.source.children) {
+            return None;
+        }
+        let translated = self
+            .target
+            .is_some_and(|target| has_text(xml, &target.children));
+        let state = self
+            .state_holder
+            .and_then(|holder| holder.attribute("state"));
+        let pending = match self.version {
+            Version::V1 => {
+                !translated || state.is_some_and(|state| PENDING_STATES.contains(&state))
+            }
+            Version::V2 => !translated || state.unwrap_or(INITIAL_STATE) == INITIAL_STATE,
+        };
+        pending.then_some(source)
+    }
+
+    /// Replacements writing `translation` to the target and marking it translated.
+    fn fill(&self, xml: &str, translation: &str, replacements: &mut Vec<(Range<usize>, String)>) {
+        let state = format!(" state=\"{TRANSLATED_STATE}\"");
+        let target_state = if self.version == Version::V1 {
+            state.as_str()
+        } else {
+            ""
+        };
+        match self.target {
+            None => {
+                let end = self.source.range().end;
+                let name = self.source.name.replace("source", "target");
+                replacements.push((
+                    end..end,
+                    format!(
+                        "{}<{name}{target_state}>{translation}</{name}>",
+                        indent(xml, self.source)
+                    ),
+                ));
+            }
+            Some(target) => {
+                if self.version == Version::V1 {
+                    set_state(target, replacements);
+                }
+                match (inner(target), &target.start_tag) {
+                    (Some(inner), _) => replacements.push((inner, translation.to_owned())),
+                    (None, start_tag) => replacements.push((
+                        start_tag.end - 2..start_tag.end,
+                        format!(">{translation}</{}>", target.name),
+                    )),
+                }
+            }
+        }
+        if self.version == Version::V2
+            && let Some(segment) = self.state_holder
+        {
+            set_state(segment, replacements);
+        }
+    }
+}
+
+/// Replacement setting the state attribute of `element` to translated.
+fn set_state(element: &Element, replacements: &mut Vec<(Range<usize>, String)>) {
+    match element.attribute_range("state") {
+        Some(range) => replacements.push((range, TRANSLATED_STATE.to_owned())),
+        None => {
+            let position = element.start_tag.start + 1 + element.name.len();
+            replacements.push((position..position, format!(" state=\"{TRANSLATED_STATE}\"")));
+        }
+    }
+}
+
+fn decode(data: &[u8]) -> DocumentResult<&str> {
+    std::str::from_utf8(data)
+        .map_err(|err| DocumentErrors::Malformed(format!("XLIFF is not UTF-8: {err}")))
+}
+
+fn parse(xml: &str) -> DocumentResult<Vec<Node>> {
+    markup::parse(xml, Dialect::Xml).map_err(|err| DocumentErrors::Malformed(err.to_string()))
+}
+
+/// Translatable units in document order, without those marked `translate="no"`.
+fn units(nodes: &[Node]) -> Vec<Unit<'_>> {
+    let version = match ooxml::elements(nodes, "xliff")
+        .first()
+        .and_then(|root| root.attribute("version"))
+    {
+        Some(version) if version.starts_with('2') => Version::V2,
+        _ => Version::V1,
+    };
+    let translatable = |element: &Element| element.attribute("translate") != Some("no");
+
+    let mut units = Vec::new();
+    match version {
+        Version::V1 => {
+            for unit in ooxml::elements(nodes, "trans-unit") {
+                if !translatable(unit) {
+                    continue;
+                }
+                let Some(source) = ooxml::children(unit, "source").next() else {
+                    continue;
+                };
+                let target = ooxml::children(unit, "target").next();
+                units.push(Unit {
+                    source,
+                    target,
+                    state_holder: target,
+                    version,
+                });
+            }
+        }
+        Version::V2 => {
+            for unit in ooxml::elements(nodes, "unit") {
+                if !translatable(unit) {
+                    continue;
+                }
+                for segment in ooxml::children(unit, "segment") {
+                    let Some(source) = ooxml::children(segment, "source").next() else {
+                        continue;
+                    };
+                    units.push(Unit {
+                        source,
+                        target: ooxml::children(segment, "target").next(),
+                        state_holder: Some(segment),
+                        version,
+                    });
+                }
+            }
+        }
+    }
+    units
+}
+
+/// Content between the tags, `None` for an empty element.
+fn inner(element: &Element) -> Option<Range<usize>> {
+    let end_tag = element.end_tag.as_ref()?;
+    Some(element.start_tag.end..end_tag.start)
+}
+
+fn has_text(xml: &str, nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| match node {
+        Node::Text(range) => !xml[range.clone()].trim().is_empty(),
+        Node::Element(element) => has_text(xml, &element.children),
+        Node::Other(_) => false,
+    })
+}
+
+/// Line break and indentation before `element`, to put a sibling after it the same way.
+fn indent<'x>(xml: &'x str, element: &Element) -> &'x str {
+    let before = &xml[..element.start_tag.start];
+    match before.rfind('\n') {
+        Some(line) if before[line..].trim().is_empty() => &before[line..],
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod test_xliff {
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::models::DocumentOptions;
+    use crate::modules::documents::xliff::XliffDocument;
+
+    const XLIFF_1: &str = r#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file source-language="en" target-language="ru" datatype="plaintext" original="ui">
+    <body>
+      <trans-unit id="open">
+        <source>Open <g id="1">file</g></source>
+        <target state="final">Открыть <g id="1">файл</g></target>
+      </trans-unit>
+      <trans-unit id="save">
+        <source>Save<x id="2"/> now</source>
+        <target state="needs-translation"/>
+      </trans-unit>
+      <trans-unit id="close">
+        <source>Close</source>
+      </trans-unit>
+      <trans-unit id="brand" translate="no">
+        <source>Acme</source>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>"#;
+
+    const XLIFF_2: &str = r#"<xliff xmlns="urn:oasis:names:tc:xliff:document:2.0" version="2.0" srcLang="en" trgLang="ru">
+  <file id="f1">
+    <unit id="u1">
+      <segment state="reviewed">
+        <source>Done</source>
+        <target>Готово</target>
+      </segment>
+      <segment>
+        <source>Press <pc id="1">start</pc></source>
+      </segment>
+    </unit>
+  </file>
+</xliff>"#;
+
+    #[test]
+    fn test_xliff_roundtrip() {
+        let document = XliffDocument;
+        let options = DocumentOptions::default();
+        let segments = document.extract(XLIFF_1.as_bytes(), &options).unwrap();
+        let texts: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(texts, vec![r#"Save<x id="2"/> now"#, "Close"]);
+
+        let translations = vec![
+            r#"Сохранить<x id="2"/> сейчас"#.to_string(),
+            "Закрыть".to_string(),
+        ];
+        let rebuilt = document
+            .rebuild(XLIFF_1.as_bytes(), &options, &translations)
+            .unwrap();
+        let expected = XLIFF_1
+            .replace(
+                r#"<target state="needs-translation"/>"#,
+                r#"<target state="translated">Сохранить<x id="2"/> сейчас</target>"#,
+            )
+            .replace(
+                "<source>Close</source>",
+                "<source>Close</source>\n        <target state=\"translated\">Закрыть</target>",
+            );
+        assert_eq!(String::from_utf8(rebuilt).unwrap(), expected);
+
+        let segments = document.extract(XLIFF_2.as_bytes(), &options).unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].text(), r#"Press <pc id="1">start</pc>"#);
+        let rebuilt = document
+            .rebuild(
+                XLIFF_2.as_bytes(),
+                &options,
+                &[r#"Нажмите <pc id="1">старт</pc>"#.to_string()],
+            )
+            .unwrap();
+        let expected = XLIFF_2.replace(
+            "<segment>\n        <source>Press <pc id=\"1\">start</pc></source>",
+            "<segment state=\"translated\">\n        <source>Press <pc id=\"1\">start</pc></source>\n        <target>Нажмите <pc id=\"1\">старт</pc></target>",
+        );
+        assert_eq!(String::from_utf8(rebuilt).unwrap(), expected);
+    }
+}
@@ -0,0 +1,682 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use crate::modules::documents::DocumentFormat;
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::models::{DocumentOptions, DocumentSegment, SpreadsheetOutput};
+use crate::modules::documents::ooxml::{self, Paragraph, Vocabulary};
+use crate::modules::documents::package::Package;
+use crate::modules::documents::spreadsheet::{self, NewColumns};
+use crate::modules::formats::markup::{self, Dialect, Element, Node};
+use crate::modules::formats::models::TextFormat;
+
+const SHARED_STRING: Vocabulary = Vocabulary {
+    paragraph: "si",
+    run: "r",
+    run_properties: "rPr",
+    text: "t",
+    tab: None,
+    line_break: None,
+    ignored: &[],
+    containers: &[],
+    preserve_space: true,
+};
+
+const INLINE_STRING: Vocabulary = Vocabulary {
+    paragraph: "is",
+    ..SHARED_STRING
+};
+
+const WORKBOOK: &str = "xl/workbook.xml";
+
+/// Excel workbook. Shared strings and inline strings of the selected cells
+/// are translated; formulas, numbers, dates and booleans are left alone.
+/// Rich text runs are merged by formatting as in Word paragraphs.
+pub struct XlsxDocument;
+
+impl DocumentFormat for XlsxDocument {
+    fn name(&self) -> &'static str {
+        "xlsx"
+    }
+
+    fn content_types(&self) -> &'static [&'static str] {
+        &["application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"]
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xlsx"]
+    }
+
+    fn extract(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+    ) -> DocumentResult<Vec<DocumentSegment>> {
+        let mut package = Package::open(data)?;
+        let workbook = Workbook::read(&mut package, options)?;
+        let segments = workbook
+            .targets()
+            .into_iter()
+            .filter_map(|target| workbook.paragraph(&target).fragment())
+            .map(|fragment| DocumentSegment::new(fragment, TextFormat::Xml))
+            .collect();
+        Ok(segments)
+    }
+
+    fn rebuild(
+        &self,
+        data: &[u8],
+        options: &DocumentOptions,
+        translations: &[String],
+    ) -> DocumentResult<Vec<u8>> {
+        let mut package = Package::open(data)?;
+        let workbook = Workbook::read(&mut package, options)?;
+        let targets = workbook.targets();
+        if targets.len() != translations.len() {
+            return Err(DocumentErrors::Malformed(format!(
+                "{} translations for {} cells",
+                translations.len(),
+                targets.len()
+            )));
+        }
+        let mut rebuilt = HashMap::new();
+        for (target, translation) in targets.into_iter().zip(translations) {
+            let (xml, vocabulary) = match &target {
+                Target::Shared(_) => (workbook.shared_xml(), &SHARED_STRING),
+                Target::Inline { sheet, .. } => {
+                    (workbook.sheets[*sheet].xml.as_str(), &INLINE_STRING)
+                }
+            };
+            let paragraph = workbook
+                .paragraph(&target)
+                .rebuild(xml, translation, vocabulary)?;
+            rebuilt.insert(target, paragraph);
+        }
+
+        let mut rewritten = HashMap::new();
+        // Translations of shared strings used outside of the selection, or
+        // written to new cells, are added as new shared strings.
+        let mut added = Vec::new();
+        let mut added_references = 0;
+        for (sheet_index, sheet) in workbook.sheets.iter().enumerate() {
+            let mut columns = NewColumns::new(
+                sheet
+                    .rows
+                    .iter()
+                    .flat_map(|row| &row.cells)
+                    .filter(|cell| !matches!(cell.value, Value::Empty))
+                    .map(|cell| cell.column),
+            );
+            let mut last_column = 0;
+            let mut replacements = Vec::new();
+            for row in &sheet.rows {
+                for cell in row.cells.iter().filter(|cell| cell.selected) {
+                    let (kind, value, range) = match &cell.value {
+                        Value::Shared { index, text } => {
+                            if !rebuilt.contains_key(&Target::Shared(*index)) {
+                                continue;
+                            }
+                            if options.output() == SpreadsheetOutput::Overwrite
+                                && workbook.only_selected(*index)
+                            {
+                                // Rewritten in place in the shared strings.
+                                continue;
+                            }
+                            let new_index = match added.iter().position(|added| added == index) {
+                                Some(position) => workbook.shared_count() + position,
+                                None => {
+                                    added.push(*index);
+                                    workbook.shared_count() + added.len() - 1
+                                }
+                            };
+                            ("s", new_index.to_string(), text.clone())
+                        }
+                        Value::Inline(start) => {
+                            let target = Target::Inline {
+                                sheet: sheet_index,
+                                start: *start,
+                            };
+                            let Some(paragraph) = rebuilt.get(&target) else {
+                                continue;
+                            };
+                            let range = sheet.inline[start].range().clone();
+                            ("inlineStr", paragraph.clone(), range)
+                        }
+                        Value::Empty | Value::Other => continue,
+                    };
+                    match options.output() {
+                        SpreadsheetOutput::Overwrite => replacements.push((range, 0, value)),
+                        SpreadsheetOutput::NewColumn => {
+                            let column = columns.target(cell.column);
+                            let Some(position) = row.position(column) else {
+                                continue;
+                            };
+                            last_column = last_column.max(column);
+                            let content = match kind {
+                                "s" => {
+                                    added_references += 1;
+                                    format!("<v>{value}</v>")
+                                }
+                                _ => value,
+                            };
+                            let style = cell
+                                .style
+                                .as_ref()
+                                .map(|style| format!(" s=\"{style}\""))
+                                .unwrap_or_default();
+                            let new_cell = format!(
+                                "<c r=\"{}{}\"{style} t=\"{kind}\">{content}</c>",
+                                spreadsheet::column_name(column),
+                                row.number
+                            );
+                            replacements.push((position, column, new_cell));
+                        }
+                    }
+                }
+            }
+            if replacements.is_empty() {
+                continue;
+            }
+            if let Some(dimension) = sheet.widened_dimension(last_column) {
+                replacements.push(dimension);
+            }
+            // Cells inserted at the same place go in column order.
+            replacements.sort_by_key(|(range, column, _)| (range.start, range.end, *column));
+            let replacements: Vec<(Range<usize>, String)> = replacements
+                .into_iter()
+                .map(|(range, _, replacement)| (range, replacement))
+                .collect();
+            rewritten.insert(
+                sheet.part.clone(),
+                ooxml::replace(&sheet.xml, &replacements),
+            );
+        }
+
+        if let Some(strings) = &workbook.shared {
+            let in_place: Vec<(Range<usize>, String)> = strings
+                .paragraphs
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| {
+                    options.output() == SpreadsheetOutput::Overwrite
+                        && workbook.only_selected(*index)
+                })
+                .filter_map(|(index, paragraph)| {
+                    let translated = rebuilt.get(&Target::Shared(index))?;
+                    Some((paragraph.range().clone(), translated.clone()))
+                })
+                .collect();
+            if !in_place.is_empty() || !added.is_empty() {
+                let mut replacements = Vec::new();
+                if let Some((range, count)) = &strings.count {
+                    replacements.push((range.clone(), (count + added_references).to_string()));
+                }
+                if let Some(range) = &strings.unique_count {
+                    let unique_count = strings.paragraphs.len() + added.len();
+                    replacements.push((range.clone(), unique_count.to_string()));
+                }
+                replacements.sort_by_key(|(range, _)| range.start);
+                replacements.extend(in_place);
+                let appended: String = added
+                    .iter()
+                    .map(|index| rebuilt[&Target::Shared(*index)].as_str())
+                    .collect();
+                replacements.push((strings.end..strings.end, appended));
+                rewritten.insert(
+                    strings.part.clone(),
+                    ooxml::replace(&strings.xml, &replacements),
+                );
+            }
+        }
+        package.rewrite(&rewritten)
+    }
+}
+
+/// Translated text of a workbook: a shared string, translated once however
+/// many selected cells use it, or the inline string of a cell.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum Target {
+    Shared(usize),
+    Inline { sheet: usize, start: usize },
+}
+
+enum Value {
+    /// No value, only formatting.
+    Empty,
+    /// Index into the shared strings and the byte range of the index.
+    Shared { index: usize, text: Range<usize> },
+    /// Start of the inline string element.
+    Inline(usize),
+    /// Formula, number, date, boolean or error.
+    Other,
+}
+
+struct Cell {
+    column: u32,
+    range: Range<usize>,
+    style: Option<String>,
+    value: Value,
+    selected: bool,
+}
+
+struct Row {
+    number: u32,
+    /// Start of the end tag, None for rows without cells.
+    end: Option<usize>,
+    cells: Vec<Cell>,
+}
+
+impl Row {
+    /// Where a new cell of the column goes: over an empty cell of the column,
+    /// before the first cell right of it or at the end of the row.
+    fn position(&self, column: u32) -> Option<Range<usize>> {
+        for cell in &self.cells {
+            if cell.column == column && matches!(cell.value, Value::Empty) {
+                return Some(cell.range.clone());
+            }
+            if cell.column > column {
+                return Some(cell.range.start..cell.range.start);
+            }
+        }
+        self.end.map(|end| end..end)
+    }
+}
+
+struct Sheet {
+    part: String,
+    xml: String,
+    rows: Vec<Row>,
+    /// Value of the used range reference, `A1:C10`.
+    dimension: Option<(Range<usize>, String)>,
+    /// Inline strings by the start of their element.
+    inline: HashMap<usize, Paragraph>,
+}
+
+impl Sheet {
+    /// Used range widened to the last new column.
+    fn widened_dimension(&self, last_column: u32) -> Option<(Range<usize>, u32, String)> {
+        let (range, reference) = self.dimension.as_ref()?;
+        let (first, last) = reference.split_once(':').unwrap_or((reference, reference));
+        let (column, row) = spreadsheet::cell_reference(last)?;
+        if last_column <= column {
+            return None;
+        }
+        let widened = format!("{first}:{}{row}", spreadsheet::column_name(last_column));
+        Some((range.clone(), 0, widened))
+    }
+}
+
+struct SharedStrings {
+    part: String,
+    xml: String,
+    paragraphs: Vec<Paragraph>,
+    count: Option<(Range<usize>, usize)>,
+    unique_count: Option<Range<usize>>,
+    /// Start of the end tag of the table.
+    end: usize,
+}
+
+struct Workbook {
+    shared: Option<SharedStrings>,
+    sheets: Vec<Sheet>,
+    /// Cells using every shared string, all of them and the selected ones.
+    references: HashMap<usize, (usize, usize)>,
+}
+
+impl Workbook {
+    fn read(package: &mut Package, options: &DocumentOptions) -> DocumentResult<Self> {
+        let names: HashSet<String> = package.names().into_iter().collect();
+        let relationships = package.relationships(WORKBOOK)?;
+
+        let shared = relationships
+            .iter()
+            .find(|relationship| relationship.kind.ends_with("/sharedStrings"))
+            .map(|relationship| relationship.target.clone())
+            .filter(|part| names.contains(part))
+            .map(|part| SharedStrings::read(package, part))
+            .transpose()?;
+
+        let workbook = package.read(WORKBOOK)?;
+        let nodes = parse(&workbook)?;
+        let mut sheets = Vec::new();
+        for sheet in ooxml::elements(&nodes, "sheet") {
+            let (Some(name), Some(id)) = (ooxml::attribute(sheet, "name"), sheet.attribute("r:id"))
+            else {
+                continue;
+            };
+            let Some(part) = relationships
+                .iter()
+                .find(|relationship| relationship.id == id)
+                .map(|relationship| &relationship.target)
+            else {
+                continue;
+            };
+            if !names.contains(part) {
+                continue;
+            }
+            let xml = package.read(part)?;
+            let selected = spreadsheet::sheet_selected(options, &name);
+            sheets.push(Sheet::read(part.clone(), xml, &name, selected, options)?);
+        }
+
+        let mut references: HashMap<usize, (usize, usize)> = HashMap::new();
+        for cell in sheets
+            .iter()
+            .flat_map(|sheet| &sheet.rows)
+            .flat_map(|row| &row.cells)
+        {
+            if let Value::Shared { index, .. } = cell.value {
+                let entry = references.entry(index).or_default();
+                entry.0 += 1;
+                entry.1 += usize::from(cell.selected);
+            }
+        }
+        Ok(Workbook {
+            shared,
+            sheets,
+            references,
+        })
+    }
+
+    /// Text to translate in document order.
+    fn targets(&self) -> Vec<Target> {
+        let mut targets = Vec::new();
+        let mut seen = HashSet::new();
+        for (sheet_index, sheet) in self.sheets.iter().enumerate() {
+            for cell in sheet.rows.iter().flat_map(|row| &row.cells) {
+                if !cell.selected {
+                    continue;
+                }
+                let target = match cell.value {
+                    Value::Shared { index, .. } if index < self.shared_count() => {
+                        Target::Shared(index)
+                    }
+                    Value::Inline(start) => Target::Inline {
+                        sheet: sheet_index,
+                        start,
+                    },
+                    _ => continue,
+                };
+                if self.paragraph(&target).fragment().is_some() && seen.insert(target.clone()) {
+                    targets.push(target);
+                }
+            }
+        }
+        targets
+    }
+
+    fn paragraph(&self, target: &Target) -> &Paragraph {
+        match target {
+            Target::Shared(index) => {
+                &self.shared.as_ref().expect("shared strings").paragraphs[*index]
+            }
+            Target::Inline { sheet, start } => &self.sheets[*sheet].inline[start],
+        }
+    }
+
+    fn shared_xml(&self) -> &str {
+        self.shared
+            .as_ref()
+            .map_or("", |strings| strings.xml.as_str())
+    }
+
+    fn shared_count(&self) -> usize {
+        self.shared
+            .as_ref()
+            .map_or(0, |strings| strings.paragraphs.len())
+    }
+
+    /// Every cell using the shared string is selected.
+    fn only_selected(&self, index: usize) -> bool {
+        self.references
+            .get(&index)
+            .is_some_and(|(all, selected)| all == selected)
+    }
+}
+
+impl SharedStrings {
+    fn read(package: &mut Package, part: String) -> DocumentResult<Self> {
+        let xml = package.read(&part)?;
+        let paragraphs = ooxml::paragraphs(&xml, &SHARED_STRING)?;
+        let nodes = parse(&xml)?;
+        let Some(table) = ooxml::elements(&nodes, "sst").into_iter().next() else {
+            return Err(DocumentErrors::Malformed(format!(
+                "{part} has no string table"
+            )));
+        };
+        let number = |name: &str| {
+            let value = table.attribute(name)?.parse().ok()?;
+            Some((table.attribute_range(name)?, value))
+        };
+        let count = number("count");
+        let unique_count = table.attribute_range("uniqueCount");
+        let Some(end) = table.end_tag.as_ref().map(|end_tag| end_tag.start) else {
+            return Err(DocumentErrors::Malformed(format!(
+                "{part} has an empty string table"
+            )));
+        };
+        Ok(SharedStrings {
+            part,
+            xml,
+            paragraphs,
+            count,
+            unique_count,
+            end,
+        })
+    }
+}
+
+impl Sheet {
+    fn read(
+        part: String,
+        xml: String,
+        name: &str,
+        selected: bool,
+        options: &DocumentOptions,
+    ) -> DocumentResult<Self> {
+        let nodes = parse(&xml)?;
+        let inline = ooxml::paragraphs(&xml, &INLINE_STRING)?
+            .into_iter()
+            .map(|paragraph| (paragraph.range().start, paragraph))
+            .collect();
+        let dimension = ooxml::elements(&nodes, "dimension")
+            .first()
+            .and_then(|dimension| {
+                Some((
+                    dimension.attribute_range("ref")?,
+                    dimension.attribute("ref")?.to_owned(),
+                ))
+            });
+
+        let mut rows = Vec::new();
+        let mut number = 0;
+        for row in ooxml::elements(&nodes, "sheetData")
+            .into_iter()
+            .flat_map(|data| ooxml::children(data, "row"))
+        {
+            number = row
+                .attribute("r")
+                .and_then(|r| r.parse().ok())
+                .unwrap_or(number + 1);
+            let mut cells = Vec::new();
+            let mut column = 0;
+            for cell in ooxml::children(row, "c") {
+                column = cell
+                    .attribute("r")
+                    .and_then(spreadsheet::cell_reference)
+                    .map_or(column + 1, |(column, _)| column);
+                cells.push(Cell {
+                    column,
+                    range: cell.range(),
+                    style: cell.attribute("s").map(str::to_owned),
+                    value: value(&xml, cell),
+                    selected: selected && spreadsheet::cell_selected(options, name, column, number),
+                });
+            }
+            rows.push(Row {
+                number,
+                end: row.end_tag.as_ref().map(|end_tag| end_tag.start),
+                cells,
+            });
+        }
+        Ok(Sheet {
+            part,
+            xml,
+            rows,
+            dimension,
+            inline,
+        })
+    }
+}
+
+fn value(xml: &str, cell: &Element) -> Value {
+    if ooxml::children(cell, "f").next().is_some() {
+        return Value::Other;
+    }
+    let stored = ooxml::children(cell, "v").next();
+    match cell.attribute("t") {
+        Some("s") => {
+            let text = stored.and_then(|stored| match stored.children.first() {
+                Some(Node::Text(range)) => Some(range.clone()),
+                _ => None,
+            });
+            text.and_then(|text| {
+                let index = xml[text.clone()].trim().parse().ok()?;
+                Some(Value::Shared { index, text })
+            })
+            .unwrap_or(Value::Other)
+        }
+        Some("inlineStr") => match ooxml::children(cell, "is").next() {
+            Some(string) => Value::Inline(string.start_tag.start),
+            None => Value::Empty,
+        },
+        _ if stored.is_some() => Value::Other,
+        _ => Value::Empty,
+    }
+}
+
+fn parse(xml: &str) -> DocumentResult<Vec<Node>> {
+    markup::parse(xml, Dialect::Xml).map_err(|err| DocumentErrors::Malformed(err.to_string()))
+}
+
+#[cfg(test)]
+mod test_xlsx {
+    use std::io::{Cursor, Read, Write};
+
+    use zip::write::SimpleFileOptions;
+    use zip::{ZipArchive, ZipWriter};
+
+    use crate::modules::documents::DocumentFormat;
+    use crate::modules::documents::models::{DocumentOptions, SpreadsheetOutput};
+    use crate::modules::documents::spreadsheet::CellRange;
+    use crate::modules::documents::xlsx::XlsxDocument;
+
+    const WORKBOOK: &str = r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Terms" sheetId="1" r:id="rId1"/><sheet name="Notes" sheetId="2" r:id="rId2"/></sheets></workbook>"#;
+
+    const RELATIONSHIPS: &str = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings" Target="sharedStrings.xml"/></Relationships>"#;
+
+    const SHARED_STRINGS: &str = r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="4" uniqueCount="3"><si><t>Term</t></si><si><t>Count</t></si><si><r><rPr><b/></rPr><t>Night</t></r><r><t xml:space="preserve"> watch</t></r></si></sst>"#;
+
+    const TERMS: &str = r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><dimension ref="A1:B3"/><sheetData><row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c></row><row r="2"><c r="A2" s="2" t="s"><v>2</v></c><c r="B2"><v>42</v></c></row><row r="3"><c r="A3" t="inlineStr"><is><t>Check the radio</t></is></c><c r="B3"><f>B2*2</f><v>84</v></c></row></sheetData></worksheet>"#;
+
+    const NOTES: &str = r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData><row r="1"><c r="A1" t="s"><v>0</v></c></row></sheetData></worksheet>"#;
+
+    fn package() -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in [
+            ("xl/workbook.xml", WORKBOOK),
+            ("xl/_rels/workbook.xml.rels", RELATIONSHIPS),
+            ("xl/sharedStrings.xml", SHARED_STRINGS),
+            ("xl/worksheets/sheet1.xml", TERMS),
+            ("xl/worksheets/sheet2.xml", NOTES),
+        ] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn part(data: &[u8], name: &str) -> String {
+        let mut archive = ZipArchive::new(Cursor::new(data)).unwrap();
+        let mut part = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut part)
+            .unwrap();
+        part
+    }
+
+    #[test]
+    fn test_xlsx_roundtrip() {
+        let data = package();
+        let options = DocumentOptions::new(
+            vec!["Terms".to_owned()],
+            vec!["A:A".parse().unwrap()],
+            SpreadsheetOutput::Overwrite,
+        );
+        let segments = XlsxDocument.extract(&data, &options).unwrap();
+        let fragments: Vec<&str> = segments
+            .iter()
+            .map(|segment| segment.text().as_str())
+            .collect();
+        assert_eq!(
+            fragments,
+            vec![
+                "<p>Term</p>",
+                "<p><g0>Night</g0> watch</p>",
+                "<p>Check the radio</p>",
+            ]
+        );
+        let translations = vec![
+            "<p>Термин</p>".to_owned(),
+            "<p><g0>Ночной</g0> дозор</p>".to_owned(),
+            "<p>Проверьте рацию</p>".to_owned(),
+        ];
+
+        // "Term" is also used on the other sheet, so its translation is a new string.
+        let rebuilt = XlsxDocument
+            .rebuild(&data, &options, &translations)
+            .unwrap();
+        let strings = part(&rebuilt, "xl/sharedStrings.xml");
+        assert!(strings.contains(r#"count="4" uniqueCount="4"><si><t>Term</t></si>"#));
+        assert!(strings.contains(
+            "<si><r><rPr><b/></rPr><t xml:space=\"preserve\">Ночной</t></r>\
+             <r><t xml:space=\"preserve\"> дозор</t></r></si>"
+        ));
+        assert!(strings.ends_with("<si><r><t xml:space=\"preserve\">Термин</t></r></si></sst>"));
+        let terms = part(&rebuilt, "xl/worksheets/sheet1.xml");
+        assert!(terms.contains(r#"<c r="A1" t="s"><v>3</v></c><c r="B1" t="s"><v>1</v></c>"#));
+        assert!(terms.contains(
+            r#"<c r="A3" t="inlineStr"><is><r><t xml:space="preserve">Проверьте рацию</t></r></is></c>"#
+        ));
+        assert_eq!(part(&rebuilt, "xl/worksheets/sheet2.xml"), NOTES);
+
+        let options = DocumentOptions::new(
+            Vec::new(),
+            vec!["Terms!A1:A3".parse().unwrap()],
+            SpreadsheetOutput::NewColumn,
+        );
+        assert_eq!(XlsxDocument.extract(&data, &options).unwrap().len(), 3);
+        let rebuilt = XlsxDocument
+            .rebuild(&data, &options, &translations)
+            .unwrap();
+        let strings = part(&rebuilt, "xl/sharedStrings.xml");
+        assert!(strings.contains(r#"count="6" uniqueCount="5"><si><t>Term</t></si>"#));
+        let terms = part(&rebuilt, "xl/worksheets/sheet1.xml");
+        assert!(terms.contains(r#"<dimension ref="A1:C3"/>"#));
+        assert!(
+            terms.contains(r#"<c r="B1" t="s"><v>1</v></c><c r="C1" t="s"><v>3</v></c></row>"#)
+        );
+        assert!(
+            terms.contains(r#"<c r="B2"><v>42</v></c><c r="C2" s="2" t="s"><v>4</v></c></row>"#)
+        );
+        assert!(terms.contains(
+            r#"<c r="C3" t="inlineStr"><is><r><t xml:space="preserve">Проверьте рацию</t></r></is></c></row>"#
+        ));
+
+        assert!("B2:".parse::<CellRange>().is_err());
+    }
+}
@@ -0,0 +1,523 @@
+use std::ops::Range;
+
+use crate::modules::documents::errors::{DocumentErrors, DocumentResult};
+use crate::modules::documents::resource::{Leaf, Resource, ScalarStyle};
+
+const BOM: char = '\u{feff}';
+
+/// Plain scalars YAML 1.1 reads as something other than a string; Rails
+/// still loads locales with 1.1 rules.
+const NON_STRINGS: [&str; 22] = [
+    "~", "null", "Null", "NULL", "true", "True", "TRUE", "false", "False", "FALSE", "yes", "Yes",
+    "YES", "no", "No", "NO", "on", "On", "ON", "off", "Off", "OFF",
+];
+
+/// Characters that cannot start a plain scalar.
+const INDICATORS: [char; 19] = [
+    '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`',
+];
+
+/// String leaves of a YAML document with their byte ranges. Covers what
+/// i18n files use: block mappings and sequences, plain, quoted and block
+/// scalars, comments, anchors and tags. Flow collections and aliases are
+/// kept but not translated.
+pub fn parse(text: &str) -> DocumentResult<Resource> {
+    let mut parser = Parser {
+        text,
+        lines: lines(text),
+        frames: Vec::new(),
+        root_items: 0,
+        leaves: Vec::new(),
+        top_keys: Vec::new(),
+    };
+    let mut index = 0;
+    while index < parser.lines.len() {
+        index = parser.line(index)?;
+    }
+
+    let root = match parser.top_keys.as_slice() {
+        [(key, range)] => Some((key.clone(), range.clone())),
+        _ => None,
+    };
+    Ok(Resource {
+        leaves: parser.leaves,
+        root,
+    })
+}
+
+/// `value` written in `style`, or double-quoted when the style cannot hold it.
+pub fn encode(style: &ScalarStyle, value: &str, newline: &str) -> String {
+    match style {
+        ScalarStyle::Plain if is_plain_safe(value) => value.to_owned(),
+        ScalarStyle::SingleQuoted if !value.contains('\n') => {
+            format!("'{}'", value.replace('\'', "''"))
+        }
+        ScalarStyle::Block {
+            header,
+            indent,
+            folded,
+        } => {
+            // A single line break of a folded block reads as a space.
+            let text = if *folded {
+                value.replace('\n', "\n\n")
+            } else {
+                value.to_owned()
+            };
+            let mut encoded = header.clone();
+            for line in text.split('\n') {
+                encoded.push_str(newline);
+                if !line.is_empty() {
+                    encoded.push_str(indent);
+                    encoded.push_str(line);
+                }
+            }
+            encoded
+        }
+        _ => double_quote(value),
+    }
+}
+
+/// Mapping key or sequence item the following lines are nested in.
+struct Frame {
+    indent: usize,
+    key: String,
+    item: bool,
+    /// Items of the sequence nested in this frame.
+    items: usize,
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    lines: Vec<Range<usize>>,
+    frames: Vec<Frame>,
+    root_items: usize,
+    leaves: Vec<Leaf>,
+    top_keys: Vec<(String, Range<usize>)>,
+}
+
+impl Parser<'_> {
+    /// Reads the entry starting at line `index` and returns the next line to read.
+    fn line(&mut self, index: usize) -> DocumentResult<usize> {
+        let Range { mut start, end } = self.lines[index];
+        if index == 0 && self.text.starts_with(BOM) {
+            start += BOM.len_utf8();
+        }
+        let content = &self.text[start..end];
+        let trimmed = content.trim_start();
+        if trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || trimmed.starts_with("---")
+            || trimmed.starts_with("...")
+            || trimmed.starts_with('%')
+        {
+            return Ok(index + 1);
+        }
+
+        let mut column = content.len() - trimmed.len();
+        let mut rest = trimmed;
+        if rest == "-" || rest.starts_with("- ") {
+            while let Some(top) = self.frames.last()
+                && (top.indent > column || (top.item && top.indent == column))
+            {
+                self.frames.pop();
+            }
+            let items = match self.frames.last_mut() {
+                Some(parent) => &mut parent.items,
+                None => &mut self.root_items,
+            };
+            let key = items.to_string();
+            *items += 1;
+            self.frames.push(Frame {
+                indent: column,
+                key,
+                item: true,
+                items: 0,
+            });
+
+            let dash = column;
+            let after = rest[1..].trim_start();
+            column += rest.len() - after.len();
+            rest = after;
+            if rest.is_empty() {
+                return Ok(index + 1);
+            }
+            if split_key(rest).is_none() {
+                let path = self.path();
+                return Ok(self
+                    .value(path, dash, start + column, index)?
+                    .unwrap_or(index + 1));
+            }
+        }
+
+        let Some((key, key_range, value_offset)) = split_key(rest) else {
+            return Err(DocumentErrors::Malformed(format!(
+                "unsupported YAML on line {}: {trimmed}",
+                index + 1
+            )));
+        };
+        while let Some(top) = self.frames.last()
+            && top.indent >= column
+        {
+            self.frames.pop();
+        }
+        let line_offset = start + column;
+        if self.frames.is_empty() {
+            self.top_keys.push((
+                key.clone(),
+                line_offset + key_range.start..line_offset + key_range.end,
+            ));
+        }
+
+        let value = &rest[value_offset..];
+        let value_start = line_offset + value_offset + value.len() - value.trim_start().len();
+        let mut path = self.path();
+        path.push(key.clone());
+        match self.value(path, column, value_start, index)? {
+            Some(next) => Ok(next),
+            None => {
+                self.frames.push(Frame {
+                    indent: column,
+                    key,
+                    item: false,
+                    items: 0,
+                });
+                Ok(index + 1)
+            }
+        }
+    }
+
+    fn path(&self) -> Vec<String> {
+        self.frames.iter().map(|frame| frame.key.clone()).collect()
+    }
+
+    /// Reads the value starting at `offset` on line `index`, nested deeper
+    /// than `parent`. Returns the next line to read, or `None` when the value
+    /// is a nested block on the following lines.
+    fn value(
+        &mut self,
+        path: Vec<String>,
+        parent: usize,
+        mut offset: usize,
+        index: usize,
+    ) -> DocumentResult<Option<usize>> {
+        let line_end = self.lines[index].end;
+        let mut value = &self.text[offset..line_end];
+        // Anchors and tags before the value.
+        while value.starts_with(['&', '!']) {
+            let token = value.find(char::is_whitespace).unwrap_or(value.len());
+            let rest = value[token..].trim_start();
+            offset += value.len() - rest.len();
+            value = rest;
+        }
+        if value.is_empty() || value.starts_with('#') {
+            return Ok(None);
+        }
+
+        let malformed = |message: &str| {
+            DocumentErrors::Malformed(format!("{message} on YAML line {}", index + 1))
+        };
+        let (text, range, style, next) = match value.as_bytes()[0] {
+            b'*' | b'[' | b'{' => return Ok(Some(index + 1)),
+            b'|' | b'>' => return Ok(Some(self.block(path, parent, offset, index))),
+            b'"' => {
+                let close = closing_double_quote(value)
+                    .ok_or_else(|| malformed("multi-line quoted scalars are not supported"))?;
+                let text = unescape_double(&value[1..close]);
+                (
+                    text,
+                    offset..offset + close + 1,
+                    ScalarStyle::DoubleQuoted,
+                    index + 1,
+                )
+            }
+            b'\'' => {
+                let close = closing_single_quote(value)
+                    .ok_or_else(|| malformed("multi-line quoted scalars are not supported"))?;
+                let text = value[1..close].replace("''", "'");
+                (
+                    text,
+                    offset..offset + close + 1,
+                    ScalarStyle::SingleQuoted,
+                    index + 1,
+                )
+            }
+            _ => {
+                let first = strip_comment(value);
+                let mut text = first.to_owned();
+                let mut end = offset + first.len();
+                let mut next = index + 1;
+                // Plain scalars continue on more indented lines.
+                while let Some(line) = self.lines.get(next) {
+                    let content = &self.text[line.clone()];
+                    let trimmed = content.trim_start();
+                    let indent = content.len() - trimmed.len();
+                    if trimmed.is_empty()
+                        || trimmed.starts_with('#')
+                        || indent <= parent
+                        || trimmed.starts_with("- ")
+                        || split_key(trimmed).is_some()
+                    {
+                        break;
+                    }
+                    let part = strip_comment(trimmed);
+                    text.push(' ');
+                    text.push_str(part);
+                    end = line.start + indent + part.len();
+                    next += 1;
+                }
+                if !is_string(&text) {
+                    return Ok(Some(next));
+                }
+                (text, offset..end, ScalarStyle::Plain, next)
+            }
+        };
+        self.leaves.push(Leaf {
+            path,
+            value: text,
+            range,
+            style,
+        });
+        Ok(Some(next))
+    }
+
+    /// Reads a `|` or `>` block scalar whose header starts at `offset`.
+    fn block(&mut self, path: Vec<String>, parent: usize, offset: usize, index: usize) -> usize {
+        let header = self.text[offset..self.lines[index].end]
+            .trim_end()
+            .to_owned();
+        let folded = header.starts_with('>');
+        let indent_of = |line: &Range<usize>| {
+            let content = &self.text[line.clone()];
+            (
+                content.len() - content.trim_start().len(),
+                content.trim().is_empty(),
+            )
+        };
+
+        let Some(first) =
+            (index + 1..self.lines.len()).find(|line| !indent_of(&self.lines[*line]).1)
+        else {
+            return index + 1;
+        };
+        let indent = indent_of(&self.lines[first]).0;
+        if indent <= parent {
+            return index + 1;
+        }
+        let mut last = first;
+        for line in first..self.lines.len() {
+            let (line_indent, blank) = indent_of(&self.lines[line]);
+            if blank {
+                continue;
+            }
+            if line_indent < indent {
+                break;
+            }
+            last = line;
+        }
+
+        let lines: Vec<&str> = (first..=last)
+            .map(|line| {
+                let content = &self.text[self.lines[line].clone()];
+                content.get(indent..).unwrap_or_default()
+            })
+            .collect();
+        let value = if folded {
+            let mut value = String::new();
+            for line in &lines {
+                if line.is_empty() {
+                    value.push('\n');
+                } else {
+                    if !value.is_empty() && !value.ends_with('\n') {
+                        value.push(' ');
+                    }
+                    value.push_str(line);
+                }
+            }
+            value
+        } else {
+            lines.join("\n")
+        };
+        let line_start = self.lines[first].start;
+        self.leaves.push(Leaf {
+            path,
+            value,
+            range: offset..self.lines[last].end,
+            style: ScalarStyle::Block {
+                header,
+                indent: self.text[line_start..line_start + indent].to_owned(),
+                folded,
+            },
+        });
+        last + 1
+    }
+}
+
+/// Byte ranges of the lines of `text`, without their line breaks.
+fn lines(text: &str) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for piece in text.split_inclusive('\n') {
+        let content = piece.trim_end_matches(['\n', '\r']);
+        lines.push(offset..offset + content.len());
+        offset += piece.len();
+    }
+    lines
+}
+
+/// Key of a `key: value` entry, the range of its text and the offset of the value.
+fn split_key(line: &str) -> Option<(String, Range<usize>, usize)> {
+    let (key, range, after) = match line.as_bytes().first()? {
+        b'"' => {
+            let close = closing_double_quote(line)?;
+            (unescape_double(&line[1..close]), 1..close, close + 1)
+        }
+        b'\'' => {
+            let close = closing_single_quote(line)?;
+            (line[1..close].replace("''", "'"), 1..close, close + 1)
+        }
+        b'[' | b'{' | b'#' | b'?' => return None,
+        _ => {
+            let colon = line
+                .match_indices(':')
+                .map(|(position, _)| position)
+                .find(|position| {
+                    line[position + 1..].is_empty() || line[position + 1..].starts_with([' ', '\t'])
+                })?;
+            if line[..colon].contains(" #") {
+                return None;
+            }
+            let key = line[..colon].trim_end();
+            (key.to_owned(), 0..key.len(), colon)
+        }
+    };
+    let rest = &line[after..];
+    let colon = after + rest.len() - rest.trim_start().len();
+    let value = line[colon..].strip_prefix(':')?;
+    if !value.is_empty() && !value.starts_with([' ', '\t']) {
+        return None;
+    }
+    Some((key, range, colon + 1))
+}
+
+fn closing_double_quote(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut position = 1;
+    while position < bytes.len() {
+        match bytes[position] {
+            b'\\' => position += 2,
+            b'"' => return Some(position),
+            _ => position += 1,
+        }
+    }
+    None
+}
+
+fn closing_single_quote(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut position = 1;
+    while position < bytes.len() {
+        if bytes[position] == b'\'' {
+            if bytes.get(position + 1) == Some(&b'\'') {
+                position += 2;
+                continue;
+            }
+            return Some(position);
+        }
+        position += 1;
+    }
+    None
+}
+
+fn unescape_double(text: &str) -> String {
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            value.push(char);
+            continue;
+        }
+        let Some(escape) = chars.next() else {
+            value.push('\\');
+            break;
+        };
+        let digits = match escape {
+            'x' => 2,
+            'u' => 4,
+            'U' => 8,
+            _ => 0,
+        };
+        if digits > 0 {
+            let code: String = chars.by_ref().take(digits).collect();
+            if let Some(decoded) = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                value.push(decoded);
+            }
+            continue;
+        }
+        value.push(match escape {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            'e' => '\u{1b}',
+            'a' => '\u{7}',
+            'b' => '\u{8}',
+            'N' => '\u{85}',
+            '_' => '\u{a0}',
+            other => other,
+        });
+    }
+    value
+}
+
+fn double_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for char in value.chars() {
+        match char {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            char if char.is_control() => quoted.push_str(&format!("\\u{:04x}", char as u32)),
+            char => quoted.push(char),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn strip_comment(value: &str) -> &str {
+    let value = match value.find(" #") {
+        Some(comment) => &value[..comment],
+        None => value,
+    };
+    value.trim_end()
+}
+
+/// A plain scalar YAML reads as a string.
+fn is_string(value: &str) -> bool {
+    !value.is_empty() && !NON_STRINGS.contains(&value) && !is_number(value)
+}
+
+fn is_number(value: &str) -> bool {
+    let unsigned = value.strip_prefix(['-', '+']).unwrap_or(value);
+    matches!(
+        unsigned,
+        ".inf" | ".Inf" | ".INF" | ".nan" | ".NaN" | ".NAN"
+    ) || (unsigned.starts_with(|char: char| char.is_ascii_digit() || char == '.')
+        && (unsigned.replace('_', "").parse::<f64>().is_ok()
+            || unsigned.starts_with("0x")
+            || unsigned.starts_with("0o")))
+}
+
+/// `value` can be written without quotes and still read back as the same string.
+fn is_plain_safe(value: &str) -> bool {
+    is_string(value)
+        && value == value.trim()
+        && !value.starts_with(INDICATORS)
+        && !value.contains(['\n', '\t', '\r'])
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.ends_with(':')
+}
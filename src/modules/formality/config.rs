@@ -0,0 +1,12 @@
+use getset::CopyGetters;
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct FormalityConfig {
+    /// Check translations for pronouns and verb forms of the other register.
+    enabled: bool,
+    /// Retries with a stricter instruction when the other register is found.
+    #[serde(default)]
+    max_retries: u32,
+}
@@ -0,0 +1,309 @@
+pub mod config;
+pub mod models;
+
+use std::collections::{BTreeSet, HashMap};
+
+use regex::Regex;
+
+use crate::modules::formality::config::FormalityConfig;
+use crate::modules::formality::models::{Formality, FormalityIssue};
+
+/// Register instructions and marker patterns of one target language.
+struct Register {
+    language: &'static str,
+    formal: &'static str,
+    informal: &'static str,
+    /// Forms used only when addressing the reader formally. When a pattern
+    /// has a capture group, only the group is reported.
+    formal_markers: &'static str,
+    /// Forms used only when addressing the reader informally.
+    informal_markers: &'static str,
+}
+
+/// Target languages with a formal and an informal way to address the reader.
+const REGISTERS: [Register; 12] = [
+    Register {
+        language: "ru",
+        formal: "Address the reader formally: use «Вы» with a capital letter and its forms (Вас, Вам, Ваш) and imperatives ending in -те. Never use «ты».",
+        informal: "Address the reader informally: use «ты» and its forms (тебя, тебе, твой) and imperatives without -те. Do not use «Вы» for a single reader.",
+        formal_markers: r"\b(?:Вы|Вас|Вам|Вами|Ваш|Ваша|Ваше|Ваши|Вашего|Вашей|Вашему|Вашим|Вашими|Ваших|Вашу)\b|(?i)\b(?:нажмите|введите|выберите|укажите|откройте|закройте|проверьте|попробуйте|подождите|скажите|сделайте|свяжитесь|перейдите|используйте|скачайте|отправьте|примите|будьте)\b",
+        informal_markers: r"(?i)\b(?:ты|тебя|тебе|тобой|тобою|твой|твоя|твоё|твое|твои|твоего|твоей|твоему|твоим|твоими|твоих|твою)\b",
+    },
+    Register {
+        language: "uk",
+        formal: "Address the reader formally: use «Ви» with a capital letter and its forms (Вас, Вам, Ваш) and imperatives ending in -те. Never use «ти».",
+        informal: "Address the reader informally: use «ти» and its forms (тебе, тобі, твій) and imperatives without -те. Do not use «Ви» for a single reader.",
+        formal_markers: r"\b(?:Ви|Вас|Вам|Вами|Ваш|Ваша|Ваше|Ваші|Вашого|Вашої|Вашому|Вашим|Вашими|Ваших|Вашу)\b|(?i)\b(?:натисніть|введіть|виберіть|оберіть|вкажіть|відкрийте|закрийте|збережіть|перевірте|спробуйте|зачекайте|скажіть|зробіть|перейдіть|використовуйте|підтвердіть|завантажте|встановіть|заповніть|будьте)\b",
+        informal_markers: r"(?i)\b(?:ти|тебе|тобі|тобою|твій|твоя|твоє|твої|твого|твоєї|твоєму|твоїм|твоїми|твоїх|твою)\b",
+    },
+    Register {
+        language: "de",
+        formal: "Address the reader formally with «Sie» and its forms (Ihnen, Ihr) and the Sie-imperative. Never use «du» or «ihr».",
+        informal: "Address the reader informally with «du» and its forms (dich, dir, dein) and the du-imperative. Do not use «Sie» for the reader.",
+        formal_markers: r"\b(?:Ihnen|Ihrem|Ihren|Ihrer|Ihres)\b|\b\w+en Sie\b",
+        informal_markers: r"(?i)\b(?:du|dich|dir|dein|deine|deinem|deinen|deiner|deines|euch|euer|eure|eurem|euren|eurer)\b",
+    },
+    Register {
+        language: "fr",
+        formal: "Address the reader formally with «vous» and its forms (votre, vos). Never use «tu».",
+        informal: "Address the reader informally with «tu» and its forms (te, toi, ton, ta, tes). Do not use «vous» for a single reader.",
+        formal_markers: r"(?i)\b(?:vous|votre|vos)\b",
+        informal_markers: r"(?i)\b(?:tu|toi|ta|tes)\b|\bt'",
+    },
+    Register {
+        language: "es",
+        formal: "Address the reader formally with «usted» and third-person verb forms. Never use «tú» or «vosotros».",
+        informal: "Address the reader informally with «tú» and second-person verb forms. Do not use «usted».",
+        formal_markers: r"(?i)\b(?:usted|ustedes|ud|uds)\b",
+        informal_markers: r"(?i)\b(?:tú|ti|contigo|tus|vosotros|vosotras|os)\b",
+    },
+    Register {
+        language: "it",
+        formal: "Address the reader formally with «Lei» and third-person verb forms (Suo, Sua). Never use «tu».",
+        informal: "Address the reader informally with «tu» and second-person verb forms (ti, tuo, tua). Do not use «Lei».",
+        formal_markers: r"\b(?:Lei|Suo|Sua|Suoi|Sue)\b",
+        informal_markers: r"(?i)\b(?:tu|ti|te|tuo|tua|tuoi|tue)\b",
+    },
+    Register {
+        language: "pl",
+        formal: "Address the reader formally with «Pan/Pani» («Państwo» for several readers) and third-person verb forms. Never use «ty».",
+        informal: "Address the reader informally with «ty» and second-person verb forms. Do not use «Pan/Pani».",
+        formal_markers: r"[^\s.!?…]\s+(Pan|Pani|Pana|Panu|Panią|Panem|Państwo|Państwa|Państwu)\b",
+        informal_markers: r"(?i)\b(?:ty|cię|ciebie|tobie|tobą|twój|twoja|twoje|twoi|twojego|twojej|twojemu|twoim|twoich)\b",
+    },
+    Register {
+        language: "pt",
+        formal: "Address the reader formally with «o senhor/a senhora» and third-person verb forms. Never use «tu».",
+        informal: "Address the reader informally with «tu» or «você» and the matching verb forms. Do not use «o senhor/a senhora».",
+        formal_markers: r"(?i)\b(?:o senhor|a senhora|os senhores|as senhoras|vossa senhoria)\b",
+        informal_markers: r"(?i)\b(?:tu|ti|contigo|teu|tua|teus|tuas)\b",
+    },
+    Register {
+        language: "cs",
+        formal: "Address the reader formally with «Vy» with a capital letter and its forms (Vás, Vám, Váš) and second-person plural verb forms. Never use «ty».",
+        informal: "Address the reader informally with «ty» and its forms (tě, tebe, tvůj) and second-person singular verb forms. Do not use «Vy» for a single reader.",
+        formal_markers: r"\b(?:Vy|Vás|Vám|Vámi|Váš|Vaše|Vašeho|Vašemu|Vaším|Vaši|Vašich)\b",
+        informal_markers: r"(?i)\b(?:ty|tě|tebe|tobě|tebou|tvůj|tvoje|tvá|tvého|tvému|tvým|tvoji|tvých)\b",
+    },
+    Register {
+        language: "tr",
+        formal: "Address the reader formally with «siz» and second-person plural verb forms. Never use «sen».",
+        informal: "Address the reader informally with «sen» and second-person singular verb forms. Do not use «siz» for a single reader.",
+        formal_markers: r"(?i)\b(?:siz|sizi|size|sizin|sizde|sizden|sizinle)\b",
+        informal_markers: r"(?i)\b(?:sen|seni|sana|senin|sende|senden|seninle)\b",
+    },
+    Register {
+        language: "ja",
+        formal: "Use polite Japanese (keigo): です/ます forms, 尊敬語 for the reader's actions and 謙譲語 for the writer's. Do not use plain forms.",
+        informal: "Use casual Japanese: plain forms (だ, dictionary and た forms) without です/ます and without keigo.",
+        formal_markers: r"です|ます|ました|ません|ましょう|ください|ございます|いただ|いらっしゃ",
+        informal_markers: r"(?:だ|だよ|だね|だろう|じゃない|よね)(?:[。！？!?]|$)|お前|君は|君の",
+    },
+    Register {
+        language: "ko",
+        formal: "Use polite Korean speech levels (합니다체 or 해요체). Do not use 반말.",
+        informal: "Use casual Korean (반말) without polite endings such as -요 and -습니다.",
+        formal_markers: r"습니다|습니까|ㅂ니다|세요|십시오|(?:요)(?:[.?!]|$)",
+        informal_markers: r"너는|너의|네가|(?:야|니|지|어|아)(?:[.?!]|$)",
+    },
+];
+
+struct CompiledRegister {
+    formal_markers: Regex,
+    informal_markers: Regex,
+}
+
+/// Turns the requested register into target language instructions and checks
+/// translations for pronouns and verb forms of the other register.
+pub struct FormalityChecker {
+    config: FormalityConfig,
+    registers: HashMap<&'static str, CompiledRegister>,
+}
+
+impl FormalityChecker {
+    pub fn new(config: &FormalityConfig) -> Result<Self, regex::Error> {
+        let mut registers = HashMap::new();
+        for register in &REGISTERS {
+            registers.insert(
+                register.language,
+                CompiledRegister {
+                    formal_markers: Regex::new(register.formal_markers)?,
+                    informal_markers: Regex::new(register.informal_markers)?,
+                },
+            );
+        }
+        Ok(FormalityChecker {
+            config: config.to_owned(),
+            registers,
+        })
+    }
+
+    pub fn config(&self) -> &FormalityConfig {
+        &self.config
+    }
+
+    /// Reports forms of the other register. Informal forms always break a
+    /// formal translation; formal forms break an informal one only when no
+    /// informal form is present, as several of them double as plurals.
+    pub fn check(
+        &self,
+        target_language: &str,
+        formality: Formality,
+        translation: &str,
+    ) -> Option<FormalityIssue> {
+        if !self.config.enabled() || formality == Formality::Default {
+            return None;
+        }
+        let register = self.registers.get(target_language)?;
+        let formal = markers(&register.formal_markers, translation);
+        let informal = markers(&register.informal_markers, translation);
+        match formality {
+            Formality::Formal if !informal.is_empty() => {
+                Some(FormalityIssue::new(formality, informal))
+            }
+            Formality::Informal if !formal.is_empty() && informal.is_empty() => {
+                Some(FormalityIssue::new(formality, formal))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn markers(regex: &Regex, text: &str) -> Vec<String> {
+    let found: BTreeSet<String> = regex
+        .captures_iter(text)
+        .filter_map(|captures| captures.get(1).or_else(|| captures.get(0)))
+        .map(|found| {
+            found
+                .as_str()
+                .trim_end_matches(['。', '！', '？', '!', '?', '.'])
+                .to_owned()
+        })
+        .collect();
+    found.into_iter().collect()
+}
+
+/// True when the target language distinguishes formal and informal address.
+pub fn supported(target_language: &str) -> bool {
+    REGISTERS
+        .iter()
+        .any(|register| register.language == target_language)
+}
+
+/// Instruction for the `{{formality}}` template variable.
+pub fn instruction(target_language: &str, formality: Formality) -> Option<&'static str> {
+    let register = REGISTERS
+        .iter()
+        .find(|register| register.language == target_language)?;
+    match formality {
+        Formality::Default => None,
+        Formality::Formal => Some(register.formal),
+        Formality::Informal => Some(register.informal),
+    }
+}
+
+/// Extra system prompt instruction for a retry after the wrong register.
+pub fn retry_instruction(issue: &FormalityIssue, instruction: &str) -> String {
+    format!("The previous translation was rejected: {issue}. {instruction}")
+}
+
+#[cfg(test)]
+mod test_formality {
+    use crate::modules::formality::config::FormalityConfig;
+    use crate::modules::formality::models::Formality;
+    use crate::modules::formality::{self, FormalityChecker};
+
+    #[test]
+    fn test_formality_check() {
+        let config: FormalityConfig = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "max_retries": 1,
+        }))
+        .unwrap();
+        let checker = FormalityChecker::new(&config).unwrap();
+
+        let issue = checker
+            .check(
+                "ru",
+                Formality::Formal,
+                "Проверь свою рацию, твой позывной — Гром.",
+            )
+            .unwrap();
+        assert_eq!(issue.found(), &vec!["твой".to_owned()]);
+        assert!(
+            checker
+                .check("ru", Formality::Formal, "Проверьте Вашу рацию.")
+                .is_none()
+        );
+        assert!(
+            checker
+                .check("de", Formality::Informal, "Bitte prüfen Sie Ihren Funk.")
+                .is_some()
+        );
+        assert!(
+            checker
+                .check("ja", Formality::Formal, "無線を確認してください。")
+                .is_none()
+        );
+        assert!(
+            checker
+                .check("ja", Formality::Formal, "無線を確認するんだ。")
+                .is_some()
+        );
+        assert!(checker.check("en", Formality::Formal, "Hey you").is_none());
+
+        let issue = checker
+            .check("ru", Formality::Informal, "Нажмите кнопку ещё раз.")
+            .unwrap();
+        assert_eq!(issue.found(), &vec!["Нажмите".to_owned()]);
+        let issue = checker
+            .check("pl", Formality::Informal, "Czy może Pan to sprawdzić?")
+            .unwrap();
+        assert_eq!(issue.found(), &vec!["Pan".to_owned()]);
+    }
+
+    #[test]
+    fn test_formality_check_ignores_lookalikes() {
+        let config: FormalityConfig = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "max_retries": 1,
+        }))
+        .unwrap();
+        let checker = FormalityChecker::new(&config).unwrap();
+
+        // Locative nouns and future tense forms are not imperatives.
+        assert!(
+            checker
+                .check(
+                    "ru",
+                    Formality::Informal,
+                    "Подробности на сайте, если ответите до событийте.",
+                )
+                .is_none()
+        );
+        // «ton» is also the noun "tone".
+        assert!(
+            checker
+                .check(
+                    "fr",
+                    Formality::Formal,
+                    "Vous avez changé le ton de la lettre."
+                )
+                .is_none()
+        );
+        // «pan» and «Pan» at a sentence start are also "gentleman".
+        assert!(
+            checker
+                .check(
+                    "pl",
+                    Formality::Informal,
+                    "Ten pan to sąsiad. Pan Nowak też."
+                )
+                .is_none()
+        );
+
+        assert!(formality::supported("ko"));
+        assert!(!formality::supported("zh"));
+        assert!(formality::instruction("de", Formality::Default).is_none());
+    }
+}
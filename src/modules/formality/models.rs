@@ -0,0 +1,55 @@
+use std::fmt;
+
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Register the reader is addressed in.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Formality {
+    /// Whatever the model finds natural for the text.
+    #[default]
+    Default,
+    /// Russian «Вы», German «Sie», Japanese keigo.
+    Formal,
+    /// Russian «ты», German «du», Japanese plain forms.
+    Informal,
+}
+
+impl fmt::Display for Formality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Formality::Default => "default",
+            Formality::Formal => "formal",
+            Formality::Informal => "informal",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Pronouns and verb forms of the other register found in a translation.
+#[derive(Clone, Serialize, Deserialize, Getters, CopyGetters, PartialEq, Debug, ToSchema)]
+pub struct FormalityIssue {
+    #[getset(get_copy = "pub")]
+    expected: Formality,
+    #[getset(get = "pub")]
+    found: Vec<String>,
+}
+
+impl FormalityIssue {
+    pub fn new(expected: Formality, found: Vec<String>) -> Self {
+        Self { expected, found }
+    }
+}
+
+impl fmt::Display for FormalityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} register was requested, but the translation uses {}",
+            self.expected,
+            self.found.join(", ")
+        )
+    }
+}
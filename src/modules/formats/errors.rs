@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+pub type FormatResult<T> = Result<T, FormatErrors>;
+
+#[derive(Debug, Error)]
+pub enum FormatErrors {
+    #[error("Malformed document: {0}")]
+    Malformed(String),
+    #[error("Markup structure changed in translation: {0}")]
+    StructureChanged(String),
+}
+
+impl From<quick_xml::Error> for FormatErrors {
+    fn from(err: quick_xml::Error) -> Self {
+        FormatErrors::Malformed(err.to_string())
+    }
+}
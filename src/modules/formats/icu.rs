@@ -0,0 +1,797 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+use crate::modules::formats::errors::{FormatErrors, FormatResult};
+use crate::modules::formats::models::{Escape, Segment, SegmentedText};
+use crate::modules::masking::models::MaskedText;
+
+/// CLDR cardinal plural categories of a language with a number of each,
+/// written in place of `#` so the model sees which form to use.
+fn plural_categories(language: &str) -> &'static [(&'static str, &'static str)] {
+    let primary = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match primary.as_str() {
+        "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" | "lo" | "my" | "km" => &[("other", "5")],
+        "fr" | "es" | "it" | "pt" | "ca" => &[("one", "1"), ("many", "1000000"), ("other", "5")],
+        "ru" | "uk" | "be" | "pl" => &[("one", "1"), ("few", "3"), ("many", "5"), ("other", "1.5")],
+        "cs" | "sk" => &[("one", "1"), ("few", "3"), ("many", "1.5"), ("other", "5")],
+        "lt" => &[("one", "1"), ("few", "3"), ("many", "1.5"), ("other", "10")],
+        "lv" => &[("zero", "0"), ("one", "1"), ("other", "2")],
+        "ro" => &[("one", "1"), ("few", "3"), ("other", "20")],
+        "hr" | "sr" | "bs" => &[("one", "1"), ("few", "3"), ("other", "5")],
+        "sl" => &[("one", "1"), ("two", "2"), ("few", "3"), ("other", "5")],
+        "he" => &[("one", "1"), ("two", "2"), ("other", "5")],
+        "ar" => &[
+            ("zero", "0"),
+            ("one", "1"),
+            ("two", "2"),
+            ("few", "3"),
+            ("many", "11"),
+            ("other", "100"),
+        ],
+        "ga" => &[
+            ("one", "1"),
+            ("two", "2"),
+            ("few", "3"),
+            ("many", "7"),
+            ("other", "11"),
+        ],
+        "cy" => &[
+            ("zero", "0"),
+            ("one", "1"),
+            ("two", "2"),
+            ("few", "3"),
+            ("many", "6"),
+            ("other", "4"),
+        ],
+        "mt" => &[
+            ("one", "1"),
+            ("two", "2"),
+            ("few", "3"),
+            ("many", "11"),
+            ("other", "20"),
+        ],
+        _ => &[("one", "1"), ("other", "5")],
+    }
+}
+
+/// Number for `#` in a branch: from the target language when it has the
+/// category, otherwise a usual one. Exact `=N` branches get none.
+fn sample(categories: &[(&str, &'static str)], key: &str) -> Option<&'static str> {
+    const USUAL: [(&str, &str); 6] = [
+        ("zero", "0"),
+        ("one", "1"),
+        ("two", "2"),
+        ("few", "3"),
+        ("many", "5"),
+        ("other", "5"),
+    ];
+    categories
+        .iter()
+        .chain(USUAL.iter())
+        .find(|(category, _)| *category == key)
+        .map(|(_, number)| *number)
+}
+
+struct Pattern {
+    range: Range<usize>,
+    parts: Vec<Part>,
+}
+
+enum Part {
+    /// Literal text as written, quotes included.
+    Text(Range<usize>),
+    /// `#` of a plural branch.
+    Pound(Range<usize>),
+    Argument(Argument),
+}
+
+struct Argument {
+    range: Range<usize>,
+    name: String,
+    kind: ArgumentKind,
+}
+
+enum ArgumentKind {
+    /// `{name}` or `{name, number, ...}`.
+    Simple(String),
+    Plural {
+        ordinal: bool,
+        branches: Vec<Branch>,
+    },
+    Select {
+        branches: Vec<Branch>,
+    },
+}
+
+impl ArgumentKind {
+    fn name(&self) -> &str {
+        match self {
+            ArgumentKind::Simple(kind) => kind,
+            ArgumentKind::Plural { ordinal: false, .. } => "plural",
+            ArgumentKind::Plural { ordinal: true, .. } => "selectordinal",
+            ArgumentKind::Select { .. } => "select",
+        }
+    }
+
+    fn branches(&self) -> &[Branch] {
+        match self {
+            ArgumentKind::Simple(_) => &[],
+            ArgumentKind::Plural { branches, .. } | ArgumentKind::Select { branches } => branches,
+        }
+    }
+}
+
+struct Branch {
+    key: String,
+    key_range: Range<usize>,
+    pattern: Pattern,
+}
+
+/// Splits an ICU MessageFormat message into its literal text. Plural
+/// arguments get the categories of the target language that are missing,
+/// copied from `other`; the number written for `#` in each branch tells the
+/// model which form to use. Arguments inside the text are placeholders.
+pub fn segment(text: &str, target_language: &str) -> FormatResult<SegmentedText> {
+    let pattern = parse(text)?;
+    let categories = plural_categories(target_language);
+    let mut expanded = String::with_capacity(text.len());
+    write_expanded(text, &pattern, categories, &mut expanded);
+
+    let pattern = parse(&expanded)?;
+    let mut segments = Vec::new();
+    collect_segments(&expanded, &pattern, None, categories, &mut segments);
+    Ok(SegmentedText::new(&expanded, segments))
+}
+
+/// Checks that the translation is a valid message with the arguments of the
+/// source, and that plural branches keep `#` where the source has it. Added
+/// categories are held to the source `other` branch.
+pub fn check_structure(source: &str, translated: &str) -> FormatResult<()> {
+    let source = parse(source)?;
+    let translated = parse(translated)
+        .map_err(|err| FormatErrors::StructureChanged(format!("invalid ICU message: {err}")))?;
+    let expected = pound_branches(&source);
+    for ((name, key), found) in pound_branches(&translated) {
+        let expects = expected
+            .get(&(name.clone(), key.clone()))
+            .or_else(|| expected.get(&(name.clone(), "other".to_owned())))
+            .copied()
+            .unwrap_or_default();
+        if expects && !found {
+            return Err(FormatErrors::StructureChanged(format!(
+                "`#` lost in branch `{key}` of {{{name}}}"
+            )));
+        }
+    }
+
+    let source = arguments(&source);
+    let translated = arguments(&translated);
+    if source != translated {
+        let lost: Vec<String> = source
+            .difference(&translated)
+            .map(|(name, kind)| format!("{{{name}, {kind}}}"))
+            .collect();
+        let added: Vec<String> = translated
+            .difference(&source)
+            .map(|(name, kind)| format!("{{{name}, {kind}}}"))
+            .collect();
+        return Err(FormatErrors::StructureChanged(format!(
+            "arguments lost: [{}], added: [{}]",
+            lost.join(", "),
+            added.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// The message has plural, selectordinal or select arguments.
+pub fn has_branches(text: &str) -> bool {
+    parse(text).is_ok_and(|pattern| {
+        pattern.parts.iter().any(
+            |part| matches!(part, Part::Argument(argument) if !argument.kind.branches().is_empty()),
+        )
+    })
+}
+
+fn parse(text: &str) -> FormatResult<Pattern> {
+    let mut parser = Parser { text, position: 0 };
+    let pattern = parser.pattern(false, false)?;
+    Ok(pattern)
+}
+
+/// Argument names with their types, nested ones included.
+fn arguments(pattern: &Pattern) -> BTreeSet<(String, String)> {
+    let mut names = BTreeSet::new();
+    let mut patterns = vec![pattern];
+    while let Some(pattern) = patterns.pop() {
+        for part in &pattern.parts {
+            if let Part::Argument(argument) = part {
+                names.insert((argument.name.clone(), argument.kind.name().to_owned()));
+                patterns.extend(
+                    argument
+                        .kind
+                        .branches()
+                        .iter()
+                        .map(|branch| &branch.pattern),
+                );
+            }
+        }
+    }
+    names
+}
+
+/// Branches of plural and selectordinal arguments, by argument name and key,
+/// with whether their own text has `#`.
+fn pound_branches(pattern: &Pattern) -> BTreeMap<(String, String), bool> {
+    let mut branches = BTreeMap::new();
+    let mut patterns = vec![pattern];
+    while let Some(pattern) = patterns.pop() {
+        for part in &pattern.parts {
+            let Part::Argument(argument) = part else {
+                continue;
+            };
+            for branch in argument.kind.branches() {
+                if matches!(argument.kind, ArgumentKind::Plural { .. }) {
+                    let pound = branch
+                        .pattern
+                        .parts
+                        .iter()
+                        .any(|part| matches!(part, Part::Pound(_)));
+                    *branches
+                        .entry((argument.name.clone(), branch.key.clone()))
+                        .or_default() |= pound;
+                }
+                patterns.push(&branch.pattern);
+            }
+        }
+    }
+    branches
+}
+
+/// Writes the pattern with the missing plural categories added.
+fn write_expanded(text: &str, pattern: &Pattern, categories: &[(&str, &str)], output: &mut String) {
+    let mut cursor = pattern.range.start;
+    for part in &pattern.parts {
+        let Part::Argument(argument) = part else {
+            continue;
+        };
+        let branches = argument.kind.branches();
+        let missing: Vec<&str> = match &argument.kind {
+            ArgumentKind::Plural {
+                ordinal: false,
+                branches,
+            } => categories
+                .iter()
+                .map(|(category, _)| *category)
+                .filter(|category| !branches.iter().any(|branch| branch.key == *category))
+                .collect(),
+            _ => Vec::new(),
+        };
+        for branch in branches {
+            if branch.key == "other" && !missing.is_empty() {
+                output.push_str(&text[cursor..branch.key_range.start]);
+                for category in &missing {
+                    output.push_str(category);
+                    output.push_str(&text[branch.key_range.end..branch.pattern.range.start]);
+                    write_expanded(text, &branch.pattern, categories, output);
+                    output.push_str("} ");
+                }
+                cursor = branch.key_range.start;
+            }
+            output.push_str(&text[cursor..branch.pattern.range.start]);
+            write_expanded(text, &branch.pattern, categories, output);
+            cursor = branch.pattern.range.end;
+        }
+    }
+    output.push_str(&text[cursor..pattern.range.end]);
+}
+
+/// Segments of the literal text of a pattern, split at plural and select
+/// arguments. `count` is set inside plural branches: `Some` with the number
+/// for `#`, `None` for exact `=N` branches.
+fn collect_segments(
+    text: &str,
+    pattern: &Pattern,
+    count: Option<Option<&'static str>>,
+    categories: &[(&str, &'static str)],
+    segments: &mut Vec<Segment>,
+) {
+    let mut run: Vec<&Part> = Vec::new();
+    for part in &pattern.parts {
+        match part {
+            Part::Argument(argument) if !argument.kind.branches().is_empty() => {
+                segments.extend(run_segment(text, &run, count));
+                run.clear();
+                let plural = matches!(argument.kind, ArgumentKind::Plural { .. });
+                for branch in argument.kind.branches() {
+                    let count = plural.then(|| sample(categories, &branch.key));
+                    collect_segments(text, &branch.pattern, count, categories, segments);
+                }
+            }
+            _ => run.push(part),
+        }
+    }
+    segments.extend(run_segment(text, &run, count));
+}
+
+fn run_segment(text: &str, run: &[&Part], count: Option<Option<&'static str>>) -> Option<Segment> {
+    let range = |part: &Part| match part {
+        Part::Text(range) | Part::Pound(range) => range.clone(),
+        Part::Argument(argument) => argument.range.clone(),
+    };
+    let start = range(run.first()?).start;
+    let end = range(run.last()?).end;
+    let source = &text[start..end];
+    let start = start + (source.len() - source.trim_start().len());
+    let end = end - (source.len() - source.trim_end().len());
+
+    let plural = count.is_some();
+    let mut masked = MaskedText::default();
+    let mut plain = String::new();
+    for part in run {
+        let range = range(part);
+        let range = range.start.max(start)..range.end.min(end);
+        if range.is_empty() {
+            continue;
+        }
+        match part {
+            Part::Text(_) => {
+                let literal = unquote(&text[range], plural);
+                masked.push_text(&literal);
+                plain.push_str(&literal);
+            }
+            Part::Pound(_) => match count.flatten() {
+                Some(number) => {
+                    masked.push_text(number);
+                    plain.push_str(number);
+                }
+                None => masked.push_protected("#"),
+            },
+            Part::Argument(_) => masked.push_protected(&text[range]),
+        }
+    }
+    if !plain.chars().any(char::is_alphabetic) {
+        return None;
+    }
+    Some(Segment::new(
+        start..end,
+        text[start..end].to_owned(),
+        plain,
+        masked,
+        Escape::Message {
+            plural,
+            count: count.flatten(),
+        },
+    ))
+}
+
+/// Characters after an apostrophe that make it start quoted text.
+fn is_quotable(ch: char, plural: bool) -> bool {
+    matches!(ch, '{' | '}' | '|' | '\'') || (plural && ch == '#')
+}
+
+/// Literal text of a message part with its quoting removed.
+fn unquote(text: &str, plural: bool) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\'' {
+            output.push(ch);
+            continue;
+        }
+        match chars.peek() {
+            Some('\'') => {
+                chars.next();
+                output.push('\'');
+            }
+            Some(&next) if is_quotable(next, plural) => {
+                while let Some(ch) = chars.next() {
+                    if ch == '\'' {
+                        if chars.peek() != Some(&'\'') {
+                            break;
+                        }
+                        chars.next();
+                    }
+                    output.push(ch);
+                }
+            }
+            _ => output.push('\''),
+        }
+    }
+    output
+}
+
+/// Text written so that a message reads it back literally.
+pub fn quote(text: &str, plural: bool) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' if chars.peek().is_some_and(|next| is_quotable(*next, plural)) => {
+                output.push_str("''")
+            }
+            '{' | '}' => {
+                output.push('\'');
+                output.push(ch);
+                output.push('\'');
+            }
+            '#' if plural => output.push_str("'#'"),
+            _ => output.push(ch),
+        }
+    }
+    output
+}
+
+/// Model output of a message segment: literal text quoted, and the number
+/// written for `#` turned back into `#`.
+pub fn escape(text: &str, plural: bool, count: Option<&str>) -> String {
+    let Some(count) = count else {
+        return quote(text, plural);
+    };
+    let digits = |number: &str| -> String {
+        number
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>()
+    };
+    let count = digits(count);
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for range in numbers(text) {
+        if digits(&text[range.clone()]) != count {
+            continue;
+        }
+        output.push_str(&quote(&text[last..range.start], plural));
+        if output.ends_with('\'') && !output.ends_with("''") {
+            output.push('\'');
+        }
+        output.push('#');
+        last = range.end;
+    }
+    output.push_str(&quote(&text[last..], plural));
+    output
+}
+
+/// Byte ranges of numbers, with group and decimal separators between digits.
+fn numbers(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        if !ch.is_ascii_digit() {
+            continue;
+        }
+        let mut end = start + 1;
+        while let Some(&(index, ch)) = chars.peek() {
+            if ch.is_ascii_digit() {
+                end = index + 1;
+                chars.next();
+            } else if matches!(ch, '.' | ',' | ' ' | '\u{a0}' | '\u{202f}' | '\'')
+                && text[index + ch.len_utf8()..]
+                    .chars()
+                    .next()
+                    .is_some_and(|next| next.is_ascii_digit())
+            {
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let preceded = text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric);
+        if !preceded {
+            ranges.push(start..end);
+        }
+    }
+    ranges
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// Parts up to the end of the text, or up to the `}` closing a branch.
+    fn pattern(&mut self, plural: bool, nested: bool) -> FormatResult<Pattern> {
+        let start = self.position;
+        let mut parts = Vec::new();
+        let mut text_start = None;
+        loop {
+            let Some(ch) = self.peek() else {
+                if nested {
+                    return Err(self.error("unclosed branch"));
+                }
+                break;
+            };
+            match ch {
+                '}' if nested => break,
+                '}' => return Err(self.error("unmatched `}`")),
+                '{' => {
+                    if let Some(text_start) = text_start.take() {
+                        parts.push(Part::Text(text_start..self.position));
+                    }
+                    parts.push(Part::Argument(self.argument()?));
+                }
+                '#' if plural => {
+                    if let Some(text_start) = text_start.take() {
+                        parts.push(Part::Text(text_start..self.position));
+                    }
+                    parts.push(Part::Pound(self.position..self.position + 1));
+                    self.position += 1;
+                }
+                '\'' => {
+                    text_start.get_or_insert(self.position);
+                    self.quoted(plural);
+                }
+                _ => {
+                    text_start.get_or_insert(self.position);
+                    self.position += ch.len_utf8();
+                }
+            }
+        }
+        if let Some(text_start) = text_start {
+            parts.push(Part::Text(text_start..self.position));
+        }
+        Ok(Pattern {
+            range: start..self.position,
+            parts,
+        })
+    }
+
+    /// Skips an apostrophe with the text it quotes. An unterminated quote
+    /// runs to the end of the message.
+    fn quoted(&mut self, plural: bool) {
+        self.position += 1;
+        match self.peek() {
+            Some('\'') => self.position += 1,
+            Some(next) if is_quotable(next, plural) => {
+                while let Some(ch) = self.peek() {
+                    self.position += ch.len_utf8();
+                    if ch == '\'' {
+                        if self.peek() != Some('\'') {
+                            break;
+                        }
+                        self.position += 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn argument(&mut self) -> FormatResult<Argument> {
+        let start = self.position;
+        self.position += 1;
+        self.skip_whitespace();
+        let name = self.word();
+        if name.is_empty() {
+            return Err(self.error("expected an argument name"));
+        }
+        self.skip_whitespace();
+        let kind = match self.next() {
+            Some('}') => ArgumentKind::Simple("simple".to_owned()),
+            Some(',') => {
+                self.skip_whitespace();
+                let kind = self.word();
+                self.skip_whitespace();
+                match kind.as_str() {
+                    "plural" | "selectordinal" | "select" => {
+                        if self.next() != Some(',') {
+                            return Err(self.error("expected `,`"));
+                        }
+                        let plural = kind != "select";
+                        let branches = self.branches(plural)?;
+                        match kind.as_str() {
+                            "select" => ArgumentKind::Select { branches },
+                            _ => ArgumentKind::Plural {
+                                ordinal: kind == "selectordinal",
+                                branches,
+                            },
+                        }
+                    }
+                    "" => return Err(self.error("expected an argument type")),
+                    _ => {
+                        self.style()?;
+                        ArgumentKind::Simple(kind)
+                    }
+                }
+            }
+            _ => return Err(self.error("expected `,` or `}`")),
+        };
+        Ok(Argument {
+            range: start..self.position,
+            name,
+            kind,
+        })
+    }
+
+    /// Branches up to the `}` closing the argument.
+    fn branches(&mut self, plural: bool) -> FormatResult<Vec<Branch>> {
+        let mut branches = Vec::new();
+        self.skip_whitespace();
+        if plural && self.text[self.position..].starts_with("offset:") {
+            self.position += "offset:".len();
+            self.skip_whitespace();
+            if self.word().parse::<u32>().is_err() {
+                return Err(self.error("expected an offset"));
+            }
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some('}') {
+                self.position += 1;
+                break;
+            }
+            let key_start = self.position;
+            let key = self.word();
+            if key.is_empty() {
+                return Err(self.error("expected a branch key"));
+            }
+            let key_range = key_start..self.position;
+            self.skip_whitespace();
+            if self.next() != Some('{') {
+                return Err(self.error("expected `{`"));
+            }
+            let pattern = self.pattern(plural, true)?;
+            self.position += 1;
+            branches.push(Branch {
+                key,
+                key_range,
+                pattern,
+            });
+        }
+        if !branches.iter().any(|branch| branch.key == "other") {
+            return Err(self.error("no `other` branch"));
+        }
+        Ok(branches)
+    }
+
+    /// Skips the style of a simple argument and its closing `}`.
+    fn style(&mut self) -> FormatResult<()> {
+        match self.next() {
+            Some('}') => return Ok(()),
+            Some(',') => {}
+            _ => return Err(self.error("expected `,` or `}`")),
+        }
+        let mut depth = 0;
+        while let Some(ch) = self.peek() {
+            match ch {
+                '\'' => {
+                    self.quoted(false);
+                    continue;
+                }
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+            self.position += ch.len_utf8();
+        }
+        Err(self.error("unclosed argument"))
+    }
+
+    /// Name, type or key: everything up to whitespace or syntax characters.
+    fn word(&mut self) -> String {
+        let start = self.position;
+        while let Some(ch) = self.peek()
+            && !ch.is_whitespace()
+            && !matches!(ch, '{' | '}' | ',' | '\'' | '#')
+        {
+            self.position += ch.len_utf8();
+        }
+        self.text[start..self.position].to_owned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(ch) = self.peek()
+            && ch.is_whitespace()
+        {
+            self.position += ch.len_utf8();
+        }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.position += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn error(&self, message: &str) -> FormatErrors {
+        FormatErrors::Malformed(format!(
+            "invalid ICU message at byte {}: {message}",
+            self.position
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test_icu {
+    use crate::modules::formats::icu;
+
+    const MESSAGE: &str =
+        "{count, plural, =0 {No files} one {# file} other {# files}} in {folder}.";
+
+    #[test]
+    fn test_icu_expands_target_plurals() {
+        let document = icu::segment(MESSAGE, "ru").unwrap();
+        assert_eq!(
+            document.source(),
+            "{count, plural, =0 {No files} one {# file} few {# files} many {# files} other {# files}} in {folder}."
+        );
+        let texts: Vec<&str> = document
+            .segments()
+            .iter()
+            .map(|segment| segment.masked().text().as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "No files",
+                "1 file",
+                "3 files",
+                "5 files",
+                "1.5 files",
+                "in ⟦1⟧."
+            ]
+        );
+
+        let translations: Vec<String> = [
+            ("Нет файлов", document.segments()[0].escape()),
+            ("1 файл", document.segments()[1].escape()),
+            ("3 файла", document.segments()[2].escape()),
+            ("5 файлов", document.segments()[3].escape()),
+            ("1,5 файла {важно}", document.segments()[4].escape()),
+            ("в ⟦1⟧.", document.segments()[5].escape()),
+        ]
+        .into_iter()
+        .map(|(text, escape)| escape.apply(text).replace("⟦1⟧", "{folder}"))
+        .collect();
+        let rendered = document.render(&translations).unwrap();
+        assert_eq!(
+            rendered,
+            "{count, plural, =0 {Нет файлов} one {# файл} few {# файла} many {# файлов} other {# файла '{'важно'}'}} в {folder}."
+        );
+        assert!(icu::check_structure(MESSAGE, &rendered).is_ok());
+        assert!(
+            icu::check_structure(MESSAGE, "{count, plural, other {# файлов}} в папке").is_err()
+        );
+        let hard_coded = rendered.replacen("{# файла}", "{3 файла}", 1);
+        assert!(icu::check_structure(MESSAGE, &hard_coded).is_err());
+        let no_pound = "{count, plural, one {Один файл} other {Файлы}} в {folder}.";
+        assert!(
+            icu::check_structure(
+                "{count, plural, one {One file} other {Files}} in {folder}.",
+                no_pound
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_icu_quoting_and_errors() {
+        let document = icu::segment(
+            "{gender, select, female {She''s '{here}'} other {They are}}",
+            "de",
+        )
+        .unwrap();
+        assert_eq!(document.segments()[0].plain(), "She's {here}");
+        assert_eq!(icu::quote("l'homme {x} #", true), "l'homme '{'x'}' '#'");
+        assert!(icu::has_branches(MESSAGE));
+        assert!(!icu::has_branches("Hello, {name}!"));
+        assert!(icu::segment("{count, plural, one {# file}}", "en").is_err());
+        assert!(icu::segment("{count, plural, other {# files}", "en").is_err());
+    }
+}
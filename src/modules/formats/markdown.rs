@@ -0,0 +1,131 @@
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd};
+
+use crate::modules::formats::models::{Escape, Run, Segment, SegmentedText};
+
+/// Splits a Markdown document into the inline content of its paragraphs,
+/// headings, list items and table cells. Emphasis, link syntax and URLs,
+/// inline code and HTML inside a run become placeholders; code blocks, HTML
+/// blocks, front matter, list markers and table syntax are not segments at all.
+pub fn segment(text: &str) -> SegmentedText {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS;
+
+    let mut segments = Vec::new();
+    let mut run = Run::default();
+    let mut verbatim = false;
+    let mut autolink = false;
+    for (event, range) in Parser::new_ext(text, options).into_offset_iter() {
+        match event {
+            Event::Start(Tag::CodeBlock(_) | Tag::HtmlBlock | Tag::MetadataBlock(_)) => {
+                flush(text, &mut run, &mut segments);
+                verbatim = true;
+            }
+            Event::End(TagEnd::CodeBlock | TagEnd::HtmlBlock | TagEnd::MetadataBlock(_)) => {
+                verbatim = false;
+            }
+            _ if verbatim => {}
+            Event::Start(Tag::Link {
+                link_type: LinkType::Autolink | LinkType::Email,
+                ..
+            }) => {
+                autolink = true;
+                run.extend(&range);
+            }
+            Event::End(TagEnd::Link) if autolink => autolink = false,
+            Event::Start(tag) if is_inline(&tag) => run.extend(&range),
+            Event::End(tag) if is_inline_end(&tag) => run.extend(&range),
+            Event::Start(_)
+            | Event::End(_)
+            | Event::Html(_)
+            | Event::DisplayMath(_)
+            | Event::Rule => flush(text, &mut run, &mut segments),
+            Event::Text(_) if !autolink => run.push_text(range),
+            _ => run.extend(&range),
+        }
+    }
+    flush(text, &mut run, &mut segments);
+
+    SegmentedText::new(text, segments)
+}
+
+fn is_inline(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Emphasis
+            | Tag::Strong
+            | Tag::Strikethrough
+            | Tag::Superscript
+            | Tag::Subscript
+            | Tag::Link { .. }
+            | Tag::Image { .. }
+    )
+}
+
+fn is_inline_end(tag: &TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Superscript
+            | TagEnd::Subscript
+            | TagEnd::Link
+            | TagEnd::Image
+    )
+}
+
+fn flush(text: &str, run: &mut Run, segments: &mut Vec<Segment>) {
+    segments.extend(std::mem::take(run).finish(text, Escape::None));
+}
+
+#[cfg(test)]
+mod test_markdown {
+    use crate::modules::formats::markdown;
+
+    #[test]
+    fn test_markdown_segments() {
+        let text = "# Field *manual*\n\n\
+                    1. Check the [radio](https://example.com/r?id=1) before `start`.\n\
+                    2. Report to <ops@example.com>.\n\n\
+                    ```sh\necho keep\n```\n\n\
+                    | Item | Qty |\n|------|----:|\n| Water | 2 |\n";
+        let document = markdown::segment(text);
+        let masked: Vec<&str> = document
+            .segments()
+            .iter()
+            .map(|segment| segment.masked().text().as_str())
+            .collect();
+        assert_eq!(
+            masked,
+            vec![
+                "Field ⟦1⟧manual⟦2⟧",
+                "Check the ⟦1⟧radio⟦2⟧ before ⟦3⟧.",
+                "Report to ⟦1⟧.",
+                "Item",
+                "Qty",
+                "Water",
+                "2"
+            ]
+        );
+
+        let translations: Vec<String> = document
+            .segments()
+            .iter()
+            .map(|segment| segment.source().to_uppercase())
+            .collect();
+        let rendered = document.render(&translations).unwrap();
+        assert!(rendered.starts_with("# FIELD *MANUAL*\n\n1. CHECK THE [RADIO]"));
+        assert!(rendered.contains("```sh\necho keep\n```"));
+        assert!(rendered.contains("|------|----:|\n| WATER | 2 |"));
+
+        let sources: Vec<String> = document
+            .segments()
+            .iter()
+            .map(|segment| segment.source().to_owned())
+            .collect();
+        assert_eq!(document.render(&sources).unwrap(), text);
+    }
+}
@@ -0,0 +1,535 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::modules::formats::errors::{FormatErrors, FormatResult};
+use crate::modules::formats::models::{Escape, Run, Segment, SegmentedText};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dialect {
+    Html,
+    Xml,
+}
+
+/// HTML elements that are part of the surrounding text.
+const INLINE_ELEMENTS: [&str; 31] = [
+    "a", "abbr", "b", "bdi", "bdo", "br", "cite", "code", "data", "del", "dfn", "em", "font", "i",
+    "img", "ins", "kbd", "label", "mark", "q", "s", "samp", "small", "span", "strong", "sub",
+    "sup", "time", "u", "var", "wbr",
+];
+/// HTML elements without content and end tag.
+const VOID_ELEMENTS: [&str; 13] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+/// HTML elements whose content is raw text rather than markup.
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+/// HTML elements whose content is never translated.
+const VERBATIM_ELEMENTS: [&str; 8] = [
+    "script", "style", "code", "pre", "kbd", "samp", "var", "textarea",
+];
+/// Attributes holding human-readable text.
+const TRANSLATABLE_ATTRIBUTES: [&str; 2] = ["alt", "title"];
+
+struct Attribute {
+    name: String,
+    value: String,
+    /// Byte range of the value without quotes.
+    range: Range<usize>,
+    /// `None` for unquoted values, which are never translated.
+    quote: Option<char>,
+}
+
+pub(crate) struct Element {
+    pub(crate) name: String,
+    pub(crate) start_tag: Range<usize>,
+    /// `None` for empty and void elements and for end tags HTML allows to omit.
+    pub(crate) end_tag: Option<Range<usize>>,
+    end: usize,
+    attributes: Vec<Attribute>,
+    pub(crate) children: Vec<Node>,
+}
+
+pub(crate) enum Node {
+    Element(Element),
+    Text(Range<usize>),
+    /// Comments, CDATA, declarations and raw text of `script` and `style`.
+    Other(Range<usize>),
+}
+
+impl Element {
+    fn new(text: &str, tag: &BytesStart, start_tag: Range<usize>, dialect: Dialect) -> Self {
+        let mut attributes = match dialect {
+            Dialect::Html => tag.html_attributes(),
+            Dialect::Xml => tag.attributes(),
+        };
+        attributes.with_checks(false);
+        let attributes = attributes
+            .flatten()
+            .filter_map(|attribute| {
+                // Values of a reader over `&str` borrow the input, so their
+                // position in it is known without searching.
+                let Cow::Borrowed(value) = attribute.value else {
+                    return None;
+                };
+                let start = (value.as_ptr() as usize).checked_sub(text.as_ptr() as usize)?;
+                let range = start..start + value.len();
+                if range.end > text.len() {
+                    return None;
+                }
+                let quote = start
+                    .checked_sub(1)
+                    .map(|index| text.as_bytes()[index])
+                    .filter(|byte| matches!(byte, b'"' | b'\''))
+                    .map(char::from);
+                Some(Attribute {
+                    name: tag_name(attribute.key.as_ref(), dialect),
+                    value: String::from_utf8_lossy(value).into_owned(),
+                    range,
+                    quote,
+                })
+            })
+            .collect();
+
+        Self {
+            name: tag_name(tag.name().as_ref(), dialect),
+            end: start_tag.end,
+            start_tag,
+            end_tag: None,
+            attributes,
+            children: Vec::new(),
+        }
+    }
+
+    fn close(&mut self, end_tag: Option<Range<usize>>) {
+        self.end = match &end_tag {
+            Some(end_tag) => end_tag.end,
+            None => self.children.last().map_or(self.start_tag.end, Node::end),
+        };
+        self.end_tag = end_tag;
+    }
+
+    pub(crate) fn range(&self) -> Range<usize> {
+        self.start_tag.start..self.end
+    }
+
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    /// Byte range of a quoted attribute value in the parsed text.
+    pub(crate) fn attribute_range(&self, name: &str) -> Option<Range<usize>> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name && attribute.quote.is_some())
+            .map(|attribute| attribute.range.clone())
+    }
+}
+
+impl Node {
+    fn end(&self) -> usize {
+        match self {
+            Node::Element(element) => element.end,
+            Node::Text(range) | Node::Other(range) => range.end,
+        }
+    }
+}
+
+fn tag_name(name: &[u8], dialect: Dialect) -> String {
+    let name = String::from_utf8_lossy(name);
+    match dialect {
+        Dialect::Html => name.to_ascii_lowercase(),
+        Dialect::Xml => name.into_owned(),
+    }
+}
+
+fn append(root: &mut Vec<Node>, open: &mut [Element], node: Node) {
+    match open.last_mut() {
+        Some(parent) => parent.children.push(node),
+        None => root.push(node),
+    }
+}
+
+fn close_last(root: &mut Vec<Node>, open: &mut Vec<Element>, end_tag: Option<Range<usize>>) {
+    if let Some(mut element) = open.pop() {
+        element.close(end_tag);
+        append(root, open, Node::Element(element));
+    }
+}
+
+fn reader(text: &str, dialect: Dialect) -> Reader<&[u8]> {
+    let html = dialect == Dialect::Html;
+    let mut reader = Reader::from_str(text);
+    let config = reader.config_mut();
+    config.check_end_names = !html;
+    config.allow_unmatched_ends = html;
+    config.allow_dangling_amp = html;
+    reader
+}
+
+/// Builds the element tree with byte ranges of every node. HTML is read
+/// leniently: void elements, omitted end tags and stray end tags are accepted.
+pub(crate) fn parse(text: &str, dialect: Dialect) -> FormatResult<Vec<Node>> {
+    let html = dialect == Dialect::Html;
+    // The reader is restarted after raw text, which it cannot skip by itself.
+    let mut base = 0;
+    let mut reader = reader(text, dialect);
+
+    let mut root = Vec::new();
+    let mut open: Vec<Element> = Vec::new();
+    loop {
+        let start = base + reader.buffer_position() as usize;
+        let event = reader.read_event().map_err(|err| {
+            FormatErrors::Malformed(format!(
+                "{err} at byte {}",
+                base + reader.error_position() as usize
+            ))
+        })?;
+        let end = base + reader.buffer_position() as usize;
+        let node = match event {
+            Event::Start(tag) => {
+                let mut element = Element::new(text, &tag, start..end, dialect);
+                if html && VOID_ELEMENTS.contains(&element.name.as_str()) {
+                    Node::Element(element)
+                } else if html && RAW_TEXT_ELEMENTS.contains(&element.name.as_str()) {
+                    let closing = format!("</{}", element.name);
+                    let content_end = text[end..]
+                        .to_ascii_lowercase()
+                        .find(&closing)
+                        .map_or(text.len(), |offset| end + offset);
+                    let tag_end = text[content_end..]
+                        .find('>')
+                        .map_or(text.len(), |offset| content_end + offset + 1);
+                    element.children.push(Node::Other(end..content_end));
+                    element.close(Some(content_end..tag_end));
+                    base = tag_end;
+                    reader = self::reader(&text[base..], dialect);
+                    Node::Element(element)
+                } else {
+                    open.push(element);
+                    continue;
+                }
+            }
+            Event::Empty(tag) => Node::Element(Element::new(text, &tag, start..end, dialect)),
+            Event::End(tag) => {
+                let name = tag_name(tag.name().as_ref(), dialect);
+                // A stray HTML end tag is skipped; its bytes stay in the source.
+                let Some(position) = open.iter().rposition(|element| element.name == name) else {
+                    continue;
+                };
+                while open.len() > position + 1 {
+                    close_last(&mut root, &mut open, None);
+                }
+                close_last(&mut root, &mut open, Some(start..end));
+                continue;
+            }
+            Event::Text(_) | Event::GeneralRef(_) => Node::Text(start..end),
+            Event::Eof => break,
+            _ => Node::Other(start..end),
+        };
+        append(&mut root, &mut open, node);
+    }
+
+    if let Some(element) = open.last().filter(|_| !html) {
+        return Err(FormatErrors::Malformed(format!(
+            "element <{}> is not closed",
+            element.name
+        )));
+    }
+    while !open.is_empty() {
+        close_last(&mut root, &mut open, None);
+    }
+    Ok(root)
+}
+
+struct Segmenter<'a> {
+    text: &'a str,
+    dialect: Dialect,
+    source_language: &'a str,
+    run: Run,
+    /// Attribute values of inline elements in the current run.
+    attributes: Vec<Segment>,
+    segments: Vec<Segment>,
+}
+
+impl Segmenter<'_> {
+    /// Consecutive text and inline elements form one run; block elements
+    /// end it. In XML an element is inline when its siblings include text.
+    fn walk(&mut self, nodes: &[Node]) {
+        let mixed = nodes.iter().any(|node| match node {
+            Node::Text(range) => !self.text[range.clone()].trim().is_empty(),
+            _ => false,
+        });
+        for node in nodes {
+            match node {
+                Node::Text(range) => self.run.push_text(range.clone()),
+                Node::Other(range) => self.run.extend(range),
+                Node::Element(element) if self.is_inline(element, mixed) => self.inline(element),
+                Node::Element(element) => {
+                    self.flush();
+                    self.block(element);
+                }
+            }
+        }
+        self.flush();
+    }
+
+    fn block(&mut self, element: &Element) {
+        if !self.is_translatable(element) {
+            return;
+        }
+        let attributes = self.attribute_segments(element);
+        self.segments.extend(attributes);
+        self.walk(&element.children);
+    }
+
+    /// Tags of an inline element become placeholders inside the current run;
+    /// an element that must not be translated becomes a single placeholder.
+    /// Attribute values stay in the tags and get segments of their own, which
+    /// follow the run.
+    fn inline(&mut self, element: &Element) {
+        self.run.extend(&element.range());
+        if !self.is_translatable(element) {
+            return;
+        }
+        let attributes = self.attribute_segments(element);
+        self.attributes.extend(attributes);
+        for child in &element.children {
+            match child {
+                Node::Text(range) => self.run.push_text(range.clone()),
+                Node::Other(_) => {}
+                Node::Element(child) => self.inline(child),
+            }
+        }
+    }
+
+    fn attribute_segments(&self, element: &Element) -> Vec<Segment> {
+        translatable_attributes(element)
+            .filter_map(|attribute| {
+                let mut run = Run::default();
+                run.push_text(attribute.range.clone());
+                let quote = attribute.quote.unwrap_or('"');
+                run.finish(self.text, Escape::Attribute(quote))
+            })
+            .collect()
+    }
+
+    fn flush(&mut self) {
+        let run = std::mem::take(&mut self.run);
+        self.segments.extend(run.finish(self.text, Escape::Text));
+        self.segments.append(&mut self.attributes);
+    }
+
+    fn is_inline(&self, element: &Element, mixed: bool) -> bool {
+        match self.dialect {
+            Dialect::Html => INLINE_ELEMENTS.contains(&element.name.as_str()),
+            Dialect::Xml => mixed,
+        }
+    }
+
+    /// `translate="no"`, verbatim HTML elements and content marked with a
+    /// `lang` other than the source language are kept as they are.
+    fn is_translatable(&self, element: &Element) -> bool {
+        if element
+            .attribute("translate")
+            .is_some_and(|value| value.eq_ignore_ascii_case("no"))
+        {
+            return false;
+        }
+        if self.dialect == Dialect::Html && VERBATIM_ELEMENTS.contains(&element.name.as_str()) {
+            return false;
+        }
+        match element
+            .attribute("lang")
+            .or_else(|| element.attribute("xml:lang"))
+            .filter(|lang| !lang.is_empty())
+        {
+            Some(lang) => lang
+                .split(['-', '_'])
+                .next()
+                .is_some_and(|primary| primary.eq_ignore_ascii_case(self.source_language)),
+            None => true,
+        }
+    }
+}
+
+fn translatable_attributes(element: &Element) -> impl Iterator<Item = &Attribute> {
+    element.attributes.iter().filter(|attribute| {
+        TRANSLATABLE_ATTRIBUTES.contains(&attribute.name.as_str())
+            && attribute.quote.is_some()
+            && !attribute.value.trim().is_empty()
+    })
+}
+
+/// Splits an HTML or XML document into text runs and translatable
+/// attribute values.
+pub fn segment(text: &str, dialect: Dialect, source_language: &str) -> FormatResult<SegmentedText> {
+    let nodes = parse(text, dialect)?;
+    let mut segmenter = Segmenter {
+        text,
+        dialect,
+        source_language,
+        run: Run::default(),
+        attributes: Vec::new(),
+        segments: Vec::new(),
+    };
+    segmenter.walk(&nodes);
+    Ok(SegmentedText::new(text, segmenter.segments))
+}
+
+fn collect_tags(nodes: &[Node], tags: &mut Vec<String>) {
+    for node in nodes {
+        if let Node::Element(element) = node {
+            tags.push(format!("<{}>", element.name));
+            collect_tags(&element.children, tags);
+            if element.end_tag.is_some() {
+                tags.push(format!("</{}>", element.name));
+            }
+        }
+    }
+}
+
+/// Fails when the translation has other tags or nests them differently.
+pub fn check_structure(source: &str, translated: &str, dialect: Dialect) -> FormatResult<()> {
+    let mut expected = Vec::new();
+    collect_tags(&parse(source, dialect)?, &mut expected);
+    let nodes = parse(translated, dialect)
+        .map_err(|err| FormatErrors::StructureChanged(err.to_string()))?;
+    let mut found = Vec::new();
+    collect_tags(&nodes, &mut found);
+
+    if let Some((expected, found)) = expected.iter().zip(&found).find(|(a, b)| a != b) {
+        return Err(FormatErrors::StructureChanged(format!(
+            "expected {expected}, found {found}"
+        )));
+    }
+    if expected.len() != found.len() {
+        return Err(FormatErrors::StructureChanged(format!(
+            "expected {} tags, found {}",
+            expected.len(),
+            found.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_markup {
+    use crate::modules::formats::markup::{self, Dialect};
+    use crate::modules::formats::models::Escape;
+
+    #[test]
+    fn test_html_segments() {
+        let html = "<div title=\"Main menu\"><p>Open the <b>valve</b> slowly.<br>\
+                    Check <code>p1</code> &amp; <img src=\"a.png\" alt=\"gauge\"> twice.</p>\
+                    <p translate=\"no\">Keep me</p><p lang=\"fr\">Bonjour</p>\
+                    <script>if (a < b) {}</script><ul><li>First<li>Second</ul></div>";
+        let document = markup::segment(html, Dialect::Html, "en").unwrap();
+        let masked: Vec<&str> = document
+            .segments()
+            .iter()
+            .map(|segment| segment.masked().text().as_str())
+            .collect();
+        assert_eq!(
+            masked,
+            vec![
+                "Main menu",
+                "Open the ⟦1⟧valve⟦2⟧ slowly.⟦3⟧Check ⟦4⟧ &amp; ⟦5⟧ twice.",
+                "gauge",
+                "First",
+                "Second"
+            ]
+        );
+        assert_eq!(document.segments()[0].escape(), Escape::Attribute('"'));
+        assert_eq!(document.segments()[2].escape(), Escape::Attribute('"'));
+
+        let translated = document.render(&[
+            "Главное \"меню\"".to_owned(),
+            "Откройте <b>вентиль</b> медленно.<br>Проверьте <code>p1</code> & <img src=\"a.png\" alt=\"gauge\"> дважды.".to_owned(),
+            "датчик".to_owned(),
+            "Первый".to_owned(),
+            "Второй".to_owned(),
+        ])
+        .unwrap();
+        assert!(markup::check_structure(html, &translated, Dialect::Html).is_ok());
+        assert!(translated.contains("<img src=\"a.png\" alt=\"датчик\"> дважды."));
+        let broken = translated.replacen("</b>", "", 1);
+        assert!(markup::check_structure(html, &broken, Dialect::Html).is_err());
+
+        assert_eq!(
+            Escape::Attribute('"').apply("Say \"go\" & <run> &amp;"),
+            "Say &quot;go&quot; &amp; &lt;run> &amp;"
+        );
+    }
+
+    #[test]
+    fn test_inline_attributes_are_escaped() {
+        let html = "<p>See <a href=\"/a\" title=\"Safety notes\">the notes</a> and \
+                    <a href=\"/b\" title='Safety notes'>the rules</a>.</p>";
+        let document = markup::segment(html, Dialect::Html, "en").unwrap();
+        let masked: Vec<&str> = document
+            .segments()
+            .iter()
+            .map(|segment| segment.masked().text().as_str())
+            .collect();
+        assert_eq!(
+            masked,
+            vec![
+                "See ⟦1⟧the notes⟦2⟧ and ⟦3⟧the rules⟦4⟧.",
+                "Safety notes",
+                "Safety notes"
+            ]
+        );
+
+        // The run keeps its tags as they were, attribute values included.
+        let translations = vec![
+            "Siehe <a href=\"/a\" title=\"Safety notes\">die Hinweise</a> und <a href=\"/b\" title='Safety notes'>die Regeln</a>.".to_owned(),
+            document.segments()[1].escape().apply("\"Sicherheit\" & mehr"),
+            document.segments()[2].escape().apply("Sicherheit's"),
+        ];
+        let translated = document.render(&translations).unwrap();
+        assert_eq!(
+            translated,
+            "<p>Siehe <a href=\"/a\" title=\"&quot;Sicherheit&quot; &amp; mehr\">die Hinweise</a> und \
+             <a href=\"/b\" title='Sicherheit&apos;s'>die Regeln</a>.</p>"
+        );
+        assert!(markup::check_structure(html, &translated, Dialect::Html).is_ok());
+        let placements = document.placements(&translations).unwrap();
+        let offsets: Vec<usize> = placements
+            .iter()
+            .map(|placement| placement.offset())
+            .collect();
+        assert_eq!(offsets, vec![3, 29, 105]);
+        // Text of the run after a replaced value moves with it.
+        let at = |text: &str, part: &str| text[..text.find(part).unwrap()].chars().count();
+        assert_eq!(
+            placements[0].position(at(&translations[0], "die Regeln")),
+            at(&translated, "die Regeln")
+        );
+
+        // An attribute value whose tag the run lost is reported, not dropped.
+        let mut lost = translations.clone();
+        lost[0] = "Siehe die Hinweise und die Regeln.".to_owned();
+        assert!(document.render(&lost).is_err());
+    }
+
+    #[test]
+    fn test_xml_segments() {
+        let xml = "<?xml version=\"1.0\"?><doc><title>Report</title>\
+                   <para>See <ref id=\"r1\">annex</ref> now.</para>\
+                   <para xml:lang=\"de\">Nicht</para><code translate=\"no\">x</code></doc>";
+        let document = markup::segment(xml, Dialect::Xml, "en").unwrap();
+        let masked: Vec<&str> = document
+            .segments()
+            .iter()
+            .map(|segment| segment.masked().text().as_str())
+            .collect();
+        assert_eq!(masked, vec!["Report", "See ⟦1⟧annex⟦2⟧ now."]);
+        assert!(markup::segment("<doc><a></doc>", Dialect::Xml, "en").is_err());
+    }
+}
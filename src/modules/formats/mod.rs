@@ -0,0 +1,35 @@
+pub mod errors;
+pub mod icu;
+pub mod markdown;
+pub mod markup;
+pub mod models;
+
+use crate::modules::formats::errors::FormatResult;
+use crate::modules::formats::markup::Dialect;
+use crate::modules::formats::models::{Segment, SegmentedText, TextFormat};
+
+/// Splits the text into the runs that go to the model.
+pub fn segment(
+    format: TextFormat,
+    text: &str,
+    source_language: &str,
+    target_language: &str,
+) -> FormatResult<SegmentedText> {
+    match format {
+        TextFormat::Plain => Ok(SegmentedText::new(text, vec![Segment::whole(text)])),
+        TextFormat::Markdown => Ok(markdown::segment(text)),
+        TextFormat::Html => markup::segment(text, Dialect::Html, source_language),
+        TextFormat::Xml => markup::segment(text, Dialect::Xml, source_language),
+        TextFormat::Icu => icu::segment(text, target_language),
+    }
+}
+
+/// Checks that the translated document kept the markup of the source.
+pub fn check_structure(format: TextFormat, source: &str, translated: &str) -> FormatResult<()> {
+    match format {
+        TextFormat::Html => markup::check_structure(source, translated, Dialect::Html),
+        TextFormat::Xml => markup::check_structure(source, translated, Dialect::Xml),
+        TextFormat::Icu => icu::check_structure(source, translated),
+        TextFormat::Plain | TextFormat::Markdown => Ok(()),
+    }
+}
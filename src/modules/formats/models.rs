@@ -0,0 +1,319 @@
+use std::ops::Range;
+
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::modules::formats::errors::{FormatErrors, FormatResult};
+use crate::modules::formats::icu;
+use crate::modules::masking::models::MaskedText;
+
+/// Markup of the request text.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TextFormat {
+    /// Text is translated as a whole.
+    #[default]
+    Plain,
+    /// CommonMark with tables, strikethrough, task lists and footnotes.
+    Markdown,
+    /// HTML fragment or page, parsed leniently (void elements, optional end tags).
+    Html,
+    /// Well-formed XML document.
+    Xml,
+    /// ICU MessageFormat message with plural and select arguments.
+    Icu,
+}
+
+/// Escaping applied to the model output before the markup is restored.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Escape {
+    #[default]
+    None,
+    /// Element content: `<` and `&` that does not start an entity.
+    Text,
+    /// Attribute value: as text, plus the quote the value is delimited with.
+    Attribute(char),
+    /// ICU message text: `{`, `}` and apostrophes that would start quoting
+    /// are quoted, `#` too in a plural branch, where the number written for
+    /// `#` is turned back into `#`.
+    Message {
+        plural: bool,
+        count: Option<&'static str>,
+    },
+}
+
+impl Escape {
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            Escape::None => return text.to_owned(),
+            Escape::Message { plural, count } => return icu::escape(text, plural, count),
+            Escape::Text | Escape::Attribute(_) => {}
+        }
+        let mut output = String::with_capacity(text.len());
+        for (index, ch) in text.char_indices() {
+            match ch {
+                '<' => output.push_str("&lt;"),
+                '&' if !starts_with_entity(&text[index..]) => output.push_str("&amp;"),
+                '"' if self == Escape::Attribute('"') => output.push_str("&quot;"),
+                '\'' if self == Escape::Attribute('\'') => output.push_str("&apos;"),
+                _ => output.push(ch),
+            }
+        }
+        output
+    }
+}
+
+/// True for `&name;`, `&#123;` and `&#x1F;`.
+fn starts_with_entity(text: &str) -> bool {
+    let Some(end) = text.find(';') else {
+        return false;
+    };
+    let name = &text[1..end];
+    let name = name
+        .strip_prefix("#x")
+        .or_else(|| name.strip_prefix("#X"))
+        .or_else(|| name.strip_prefix('#'))
+        .unwrap_or(name);
+    !name.is_empty() && name.len() <= 32 && name.chars().all(|ch| ch.is_ascii_alphanumeric())
+}
+
+/// Translatable run of the source with its markup already replaced by placeholders.
+#[derive(Getters, CopyGetters, Clone, Debug)]
+pub struct Segment {
+    /// Byte range of the run in the source.
+    #[getset(get = "pub")]
+    range: Range<usize>,
+    /// Source bytes of the run.
+    #[getset(get = "pub")]
+    source: String,
+    /// Text of the run without markup.
+    #[getset(get = "pub")]
+    plain: String,
+    #[getset(get = "pub")]
+    masked: MaskedText,
+    #[getset(get_copy = "pub")]
+    escape: Escape,
+}
+
+impl Segment {
+    pub fn new(
+        range: Range<usize>,
+        source: String,
+        plain: String,
+        masked: MaskedText,
+        escape: Escape,
+    ) -> Self {
+        Self {
+            range,
+            source,
+            plain,
+            masked,
+            escape,
+        }
+    }
+
+    /// The whole text as a single segment.
+    pub fn whole(text: &str) -> Self {
+        let trimmed = text.trim();
+        Self {
+            range: 0..text.len(),
+            source: text.to_owned(),
+            plain: trimmed.to_owned(),
+            masked: MaskedText::new(trimmed.to_owned(), Vec::new()),
+            escape: Escape::None,
+        }
+    }
+}
+
+/// Inline content collected while walking a document: the byte range it
+/// spans and the ranges of translatable text inside it.
+#[derive(Default)]
+pub struct Run {
+    range: Option<Range<usize>>,
+    texts: Vec<Range<usize>>,
+}
+
+impl Run {
+    pub fn extend(&mut self, range: &Range<usize>) {
+        self.range = Some(match self.range.take() {
+            Some(current) => current.start.min(range.start)..current.end.max(range.end),
+            None => range.clone(),
+        });
+    }
+
+    pub fn push_text(&mut self, range: Range<usize>) {
+        self.extend(&range);
+        self.texts.push(range);
+    }
+
+    /// Turns the run into a segment. Whitespace between text ranges stays
+    /// text, any other gap is markup and goes behind a placeholder. Runs
+    /// without letters or digits give nothing to translate.
+    pub fn finish(self, source: &str, escape: Escape) -> Option<Segment> {
+        let range = self.range?;
+        if !self
+            .texts
+            .iter()
+            .any(|text| !source[text.clone()].trim().is_empty())
+        {
+            return None;
+        }
+
+        let mut masked = MaskedText::default();
+        let mut plain = String::new();
+        let mut cursor = range.start;
+        for text in self.texts {
+            push_gap(&source[cursor..text.start], &mut masked, &mut plain);
+            masked.push_text(&source[text.clone()]);
+            plain.push_str(&source[text.clone()]);
+            cursor = text.end;
+        }
+        push_gap(&source[cursor..range.end], &mut masked, &mut plain);
+
+        Some(Segment {
+            source: source[range.clone()].to_owned(),
+            range,
+            plain,
+            masked,
+            escape,
+        })
+    }
+}
+
+fn push_gap(gap: &str, masked: &mut MaskedText, plain: &mut String) {
+    if gap.is_empty() {
+        return;
+    }
+    if gap.trim().is_empty() {
+        masked.push_text(gap);
+        plain.push_str(gap);
+    } else {
+        masked.push_protected(gap);
+    }
+}
+
+/// Where a translation is in the rendered text.
+#[derive(CopyGetters, Clone, Debug, PartialEq)]
+pub struct Placement {
+    /// Character offset of the translation.
+    #[getset(get_copy = "pub")]
+    offset: usize,
+    /// Attribute values replaced in the tags of the translation: character
+    /// offset in the translation and change in length.
+    replaced: Vec<(usize, isize)>,
+}
+
+impl Placement {
+    fn new(offset: usize) -> Self {
+        Self {
+            offset,
+            replaced: Vec::new(),
+        }
+    }
+
+    /// Character offset in the rendered text of a character offset in the
+    /// translation.
+    pub fn position(&self, index: usize) -> usize {
+        let added: isize = self
+            .replaced
+            .iter()
+            .filter(|(at, _)| *at < index)
+            .map(|(_, added)| added)
+            .sum();
+        (self.offset + index).saturating_add_signed(added)
+    }
+}
+
+/// Source split into segments; everything between them is kept byte for byte.
+#[derive(Getters, Clone, Debug)]
+#[getset(get = "pub")]
+pub struct SegmentedText {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl SegmentedText {
+    pub fn new(source: &str, segments: Vec<Segment>) -> Self {
+        Self {
+            source: source.to_owned(),
+            segments,
+        }
+    }
+
+    /// Puts translations in place of the segments, in segment order. A
+    /// segment inside the previous one is an attribute value of a tag the
+    /// previous translation kept as it was; it is replaced in that tag.
+    pub fn render(&self, translations: &[String]) -> FormatResult<String> {
+        Ok(self.layout(translations)?.0)
+    }
+
+    /// Where every translation is in the rendered text.
+    pub fn placements(&self, translations: &[String]) -> FormatResult<Vec<Placement>> {
+        let (output, mut placements) = self.layout(translations)?;
+        // Offsets are in bytes and only grow, so characters are counted in
+        // one pass.
+        let (mut counted, mut chars) = (0, 0);
+        for placement in &mut placements {
+            chars += output[counted..placement.offset].chars().count();
+            counted = placement.offset;
+            placement.offset = chars;
+        }
+        Ok(placements)
+    }
+
+    /// Rendered text and the placement of every translation in it, offsets
+    /// in bytes. Fails when the tag of an attribute value is missing from the
+    /// translation around it.
+    fn layout(&self, translations: &[String]) -> FormatResult<(String, Vec<Placement>)> {
+        let mut output = String::with_capacity(self.source.len());
+        let mut placements: Vec<Placement> = Vec::with_capacity(self.segments.len());
+        let mut last = 0;
+        // The enclosing translation: its index, where it continues to be
+        // searched and how many bytes its replaced values added.
+        let (mut parent, mut nested_from, mut growth) = (0, 0, 0);
+        for (index, (segment, translation)) in self.segments.iter().zip(translations).enumerate() {
+            if segment.range.start < last {
+                let tag = self.enclosing_tag(&segment.range);
+                let found = output[nested_from..]
+                    .find(&self.source[tag.clone()])
+                    .ok_or_else(|| {
+                        FormatErrors::StructureChanged(format!(
+                            "tag `{}` is missing",
+                            &self.source[tag.clone()]
+                        ))
+                    })?;
+                let start = nested_from + found + (segment.range.start - tag.start);
+                output.replace_range(start..start + segment.range.len(), translation);
+
+                let within = start as isize - placements[parent].offset as isize - growth;
+                let within = translations[parent]
+                    .get(..within as usize)
+                    .map_or(0, |text| text.chars().count());
+                let added = translation.chars().count() as isize
+                    - self.source[segment.range.clone()].chars().count() as isize;
+                placements[parent].replaced.push((within, added));
+                placements.push(Placement::new(start));
+                growth += translation.len() as isize - segment.range.len() as isize;
+                nested_from = start + translation.len();
+                continue;
+            }
+            output.push_str(&self.source[last..segment.range.start]);
+            (parent, nested_from, growth) = (index, output.len(), 0);
+            placements.push(Placement::new(output.len()));
+            output.push_str(translation);
+            last = segment.range.end;
+        }
+        output.push_str(&self.source[last..]);
+        Ok((output, placements))
+    }
+
+    /// Byte range of the tag around an attribute value.
+    fn enclosing_tag(&self, value: &Range<usize>) -> Range<usize> {
+        let start = self.source[..value.start].rfind('<').unwrap_or(value.start);
+        let end = self.source[value.end..]
+            .find('>')
+            .map_or(value.end, |offset| value.end + offset + 1);
+        start..end
+    }
+}
@@ -0,0 +1,16 @@
+use getset::{CopyGetters, Getters};
+use serde::Deserialize;
+
+use crate::modules::glossary::models::GlossaryEnforcement;
+
+#[derive(Clone, Deserialize, CopyGetters, Getters)]
+pub struct GlossaryConfig {
+    #[getset(get = "pub")]
+    storage_path: Option<String>,
+    #[getset(get_copy = "pub")]
+    #[serde(default)]
+    enforcement: GlossaryEnforcement,
+    #[getset(get_copy = "pub")]
+    #[serde(default)]
+    max_retries: u32,
+}
@@ -0,0 +1,50 @@
+use std::io;
+use std::io::Error as IOError;
+use thiserror::Error;
+
+use serde_json::Error as SerdeError;
+
+pub type GlossaryResult<T> = Result<T, GlossaryErrors>;
+
+#[derive(Debug, Error)]
+pub enum GlossaryErrors {
+    #[error("Glossary entry not found: {0}")]
+    NotFound(u64),
+    #[error("Invalid glossary entry: {0}")]
+    InvalidEntry(String),
+    #[error("Import error: {0}")]
+    ImportError(String),
+    #[error("Error reading glossary storage: {0}")]
+    IOError(String),
+    #[error("Another Error: {0}")]
+    AnotherError(String),
+}
+
+impl From<IOError> for GlossaryErrors {
+    fn from(err: IOError) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => {
+                GlossaryErrors::IOError("Glossary storage doesn't exist".to_string())
+            }
+            _ => GlossaryErrors::IOError(err.to_string()),
+        }
+    }
+}
+
+impl From<SerdeError> for GlossaryErrors {
+    fn from(err: SerdeError) -> Self {
+        GlossaryErrors::AnotherError(err.to_string())
+    }
+}
+
+impl From<csv::Error> for GlossaryErrors {
+    fn from(err: csv::Error) -> Self {
+        GlossaryErrors::ImportError(format!("CSV: {}", err))
+    }
+}
+
+impl From<quick_xml::Error> for GlossaryErrors {
+    fn from(err: quick_xml::Error) -> Self {
+        GlossaryErrors::ImportError(format!("TBX: {}", err))
+    }
+}
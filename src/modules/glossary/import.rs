@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use quick_xml::Reader;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::Event;
+
+use crate::modules::glossary::errors::{GlossaryErrors, GlossaryResult};
+
+/// Source term, target term and an optional note.
+pub type ImportedTerm = (String, String, Option<String>);
+
+const CSV_HEADERS: [&str; 4] = ["source", "source_term", "term", "src"];
+
+/// Reads `source,target[,note]` rows. A leading header row is detected and skipped.
+pub fn parse_csv(content: &str) -> GlossaryResult<Vec<ImportedTerm>> {
+    let delimiter = if content
+        .lines()
+        .next()
+        .is_some_and(|line| line.contains('\t'))
+    {
+        b'\t'
+    } else {
+        b','
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(content.as_bytes());
+
+    let mut terms = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record?;
+        let source = record.get(0).unwrap_or_default().trim();
+        if index == 0 && CSV_HEADERS.contains(&source.to_lowercase().as_str()) {
+            continue;
+        }
+        let target = record.get(1).unwrap_or_default().trim();
+        let note = record
+            .get(2)
+            .map(str::trim)
+            .filter(|note| !note.is_empty())
+            .map(str::to_owned);
+        terms.push((source.to_owned(), target.to_owned(), note));
+    }
+    Ok(terms)
+}
+
+/// Reads term entries from TBX (both `termEntry/langSet/tig` of TBX 2 and
+/// `conceptEntry/langSec/termSec` of TBX 3). The first term of each language
+/// in an entry is taken as the preferred one.
+pub fn parse_tbx(
+    content: &str,
+    source_language: &str,
+    target_language: &str,
+) -> GlossaryResult<Vec<ImportedTerm>> {
+    let mut reader = Reader::from_str(content);
+
+    let mut terms = Vec::new();
+    let mut entry_terms: HashMap<String, String> = HashMap::new();
+    let mut entry_note: Option<String> = None;
+    let mut current_language: Option<String> = None;
+    let mut current_text: Option<String> = None;
+    let mut in_note = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"termEntry" | b"conceptEntry" => {
+                    entry_terms.clear();
+                    entry_note = None;
+                }
+                b"langSet" | b"langSec" => {
+                    current_language = element
+                        .try_get_attribute("xml:lang")
+                        .map_err(quick_xml::Error::from)?
+                        .map(|attr| attr.unescape_value().map(|value| primary_subtag(&value)))
+                        .transpose()?;
+                }
+                b"term" => current_text = Some(String::new()),
+                b"descrip" | b"note" => {
+                    in_note = true;
+                    current_text = Some(String::new());
+                }
+                _ => {}
+            },
+            Event::Text(text) => {
+                if let Some(buffer) = current_text.as_mut() {
+                    buffer.push_str(&text.decode().map_err(quick_xml::Error::from)?);
+                }
+            }
+            Event::GeneralRef(reference) => {
+                if let Some(buffer) = current_text.as_mut() {
+                    if let Some(ch) = reference.resolve_char_ref()? {
+                        buffer.push(ch);
+                    } else {
+                        let name = reference.decode().map_err(quick_xml::Error::from)?;
+                        buffer.push_str(resolve_predefined_entity(&name).unwrap_or_default());
+                    }
+                }
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"term" => {
+                    if let (Some(language), Some(text)) = (&current_language, current_text.take()) {
+                        entry_terms
+                            .entry(language.to_owned())
+                            .or_insert(text.trim().to_owned());
+                    }
+                }
+                b"descrip" | b"note" if in_note => {
+                    in_note = false;
+                    if entry_note.is_none() {
+                        entry_note = current_text.take().map(|note| note.trim().to_owned());
+                    }
+                }
+                b"langSet" | b"langSec" => current_language = None,
+                b"termEntry" | b"conceptEntry" => {
+                    if let (Some(source), Some(target)) = (
+                        entry_terms.get(source_language),
+                        entry_terms.get(target_language),
+                    ) {
+                        terms.push((source.to_owned(), target.to_owned(), entry_note.take()));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if terms.is_empty() {
+        return Err(GlossaryErrors::ImportError(format!(
+            "TBX has no entries for {source_language} -> {target_language}"
+        )));
+    }
+    Ok(terms)
+}
+
+fn primary_subtag(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
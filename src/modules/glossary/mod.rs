@@ -0,0 +1,424 @@
+pub mod config;
+pub mod errors;
+pub mod import;
+pub mod models;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::{Regex, RegexBuilder};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+
+use crate::modules::glossary::config::GlossaryConfig;
+use crate::modules::glossary::errors::{GlossaryErrors, GlossaryResult};
+use crate::modules::glossary::models::{
+    GlossaryEntry, GlossaryFilter, GlossaryScope, GlossaryTerm, GlossaryViolation, ImportFormat,
+    ImportReport,
+};
+
+/// Minimal word length for which inflected forms are accepted in the translation.
+const MIN_STEM_WORD_LENGTH: usize = 5;
+/// Number of trailing characters that may differ in an inflected form.
+const INFLECTION_SUFFIX_LENGTH: usize = 2;
+
+pub struct GlossaryStore {
+    config: GlossaryConfig,
+    storage_path: Option<PathBuf>,
+    entries: RwLock<BTreeMap<u64, StoredEntry>>,
+}
+
+/// Entry with its source term compiled once, when it is loaded or changed.
+struct StoredEntry {
+    entry: GlossaryEntry,
+    /// `None` when the term cannot be matched.
+    pattern: Option<Regex>,
+}
+
+impl StoredEntry {
+    fn new(entry: GlossaryEntry) -> Self {
+        let pattern = term_regex(entry.term().source_term(), entry.term().case_sensitive());
+        Self { entry, pattern }
+    }
+}
+
+impl GlossaryStore {
+    pub fn load(config: &GlossaryConfig) -> GlossaryResult<Self> {
+        let storage_path = config.storage_path().as_ref().map(PathBuf::from);
+        let entries = match &storage_path {
+            Some(path) if path.exists() => {
+                let content = fs::read_to_string(path)?;
+                let entries: Vec<GlossaryEntry> = serde_json::from_str(&content)?;
+                entries
+                    .into_iter()
+                    .map(|entry| (entry.id(), StoredEntry::new(entry)))
+                    .collect()
+            }
+            _ => BTreeMap::new(),
+        };
+        tracing::info!(entries = entries.len(), "Loaded glossary");
+        Ok(GlossaryStore {
+            config: config.clone(),
+            storage_path,
+            entries: RwLock::new(entries),
+        })
+    }
+
+    pub fn config(&self) -> &GlossaryConfig {
+        &self.config
+    }
+
+    pub async fn list(&self, filter: &GlossaryFilter) -> Vec<GlossaryEntry> {
+        let entries = self.entries.read().await;
+        entries
+            .values()
+            .map(|stored| &stored.entry)
+            .filter(|entry| {
+                let term = entry.term();
+                filter
+                    .source_language()
+                    .as_ref()
+                    .is_none_or(|lang| lang == term.source_language())
+                    && filter
+                        .target_language()
+                        .as_ref()
+                        .is_none_or(|lang| lang == term.target_language())
+                    && filter
+                        .profile()
+                        .as_ref()
+                        .is_none_or(|profile| term.profile().as_ref() == Some(profile))
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub async fn get(&self, id: u64) -> GlossaryResult<GlossaryEntry> {
+        let entries = self.entries.read().await;
+        entries
+            .get(&id)
+            .map(|stored| stored.entry.clone())
+            .ok_or(GlossaryErrors::NotFound(id))
+    }
+
+    pub async fn create(&self, term: GlossaryTerm) -> GlossaryResult<GlossaryEntry> {
+        validate_term(&term)?;
+        let mut entries = self.entries.write().await;
+        let id = entries.keys().next_back().map_or(1, |id| id + 1);
+        let entry = GlossaryEntry::new(id, term);
+        entries.insert(id, StoredEntry::new(entry.clone()));
+        self.persist(&entries).await?;
+        Ok(entry)
+    }
+
+    pub async fn update(&self, id: u64, term: GlossaryTerm) -> GlossaryResult<GlossaryEntry> {
+        validate_term(&term)?;
+        let mut entries = self.entries.write().await;
+        if !entries.contains_key(&id) {
+            return Err(GlossaryErrors::NotFound(id));
+        }
+        let entry = GlossaryEntry::new(id, term);
+        entries.insert(id, StoredEntry::new(entry.clone()));
+        self.persist(&entries).await?;
+        Ok(entry)
+    }
+
+    pub async fn delete(&self, id: u64) -> GlossaryResult<GlossaryEntry> {
+        let mut entries = self.entries.write().await;
+        let stored = entries.remove(&id).ok_or(GlossaryErrors::NotFound(id))?;
+        self.persist(&entries).await?;
+        Ok(stored.entry)
+    }
+
+    /// Imports a term base into the given scope. Terms already present in the
+    /// scope are replaced with the imported translation.
+    pub async fn import(
+        &self,
+        format: ImportFormat,
+        scope: &GlossaryScope,
+        content: &str,
+    ) -> GlossaryResult<ImportReport> {
+        let imported_terms = match format {
+            ImportFormat::Csv => import::parse_csv(content)?,
+            ImportFormat::Tbx => {
+                import::parse_tbx(content, scope.source_language(), scope.target_language())?
+            }
+        };
+
+        let mut entries = self.entries.write().await;
+        let mut next_id = entries.keys().next_back().map_or(1, |id| id + 1);
+        let (mut imported, mut skipped) = (0, 0);
+        for (source_term, target_term, note) in imported_terms {
+            let term = GlossaryTerm::new(scope, source_term, target_term, note);
+            if validate_term(&term).is_err() {
+                skipped += 1;
+                continue;
+            }
+
+            let existing = entries.values().map(|stored| &stored.entry).find(|entry| {
+                let existing = entry.term();
+                existing.source_language() == term.source_language()
+                    && existing.target_language() == term.target_language()
+                    && existing.profile() == term.profile()
+                    && existing.source_term().to_lowercase() == term.source_term().to_lowercase()
+            });
+            let id = match existing {
+                Some(entry) => entry.id(),
+                None => {
+                    next_id += 1;
+                    next_id - 1
+                }
+            };
+            entries.insert(id, StoredEntry::new(GlossaryEntry::new(id, term)));
+            imported += 1;
+        }
+        self.persist(&entries).await?;
+
+        tracing::info!(imported = imported, skipped = skipped, "Glossary imported");
+        Ok(ImportReport::new(imported, skipped))
+    }
+
+    /// Returns the entries of the language pair and profile whose source term
+    /// occurs in the text.
+    pub async fn matching_entries(
+        &self,
+        source_language: &str,
+        target_language: &str,
+        profile: &str,
+        text: &str,
+    ) -> Vec<GlossaryEntry> {
+        let entries = self.entries.read().await;
+        entries
+            .values()
+            .filter(|stored| {
+                let term = stored.entry.term();
+                term.source_language() == source_language
+                    && term.target_language() == target_language
+                    && term.profile().as_deref().is_none_or(|name| name == profile)
+            })
+            .filter(|stored| stored.pattern.as_ref().is_some_and(|re| re.is_match(text)))
+            .map(|stored| stored.entry.clone())
+            .collect()
+    }
+
+    /// Writes the entries to the storage file. Called with the write lock
+    /// held, so writes never interleave.
+    async fn persist(&self, entries: &BTreeMap<u64, StoredEntry>) -> GlossaryResult<()> {
+        let Some(path) = &self.storage_path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let entries: Vec<&GlossaryEntry> = entries.values().map(|stored| &stored.entry).collect();
+        write_atomically(path, &serde_json::to_string_pretty(&entries)?).await?;
+        Ok(())
+    }
+}
+
+/// Formats matched entries as a term list for the `{{glossary}}` template variable.
+pub fn format_for_prompt(entries: &[GlossaryEntry]) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let term = entry.term();
+            format!("{} -> {}", term.source_term(), term.target_term())
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
+/// Checks that the approved translation of every matched entry is present in
+/// the output. Inflected forms of longer words are accepted, so `винтовку`
+/// satisfies `винтовка`.
+pub fn find_violations(entries: &[GlossaryEntry], translation: &str) -> Vec<GlossaryViolation> {
+    let translation_lower = translation.to_lowercase();
+    let translation_words: Vec<&str> = translation_lower
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    entries
+        .iter()
+        .filter(|entry| {
+            let target = entry.term().target_term();
+            if entry.term().case_sensitive() {
+                return !translation.contains(target.as_str());
+            }
+            let target = target.to_lowercase();
+            if translation_lower.contains(&target) {
+                return false;
+            }
+            !target
+                .split(|ch: char| !ch.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .all(|word| {
+                    translation_words
+                        .iter()
+                        .any(|candidate| same_stem(word, candidate))
+                })
+        })
+        .map(|entry| {
+            GlossaryViolation::new(
+                entry.term().source_term().to_owned(),
+                entry.term().target_term().to_owned(),
+            )
+        })
+        .collect()
+}
+
+fn same_stem(expected: &str, candidate: &str) -> bool {
+    let expected_len = expected.chars().count();
+    if expected_len < MIN_STEM_WORD_LENGTH {
+        return expected == candidate;
+    }
+    let stem: String = expected
+        .chars()
+        .take(expected_len - INFLECTION_SUFFIX_LENGTH)
+        .collect();
+    candidate.starts_with(&stem)
+        && candidate.chars().count() <= expected_len + INFLECTION_SUFFIX_LENGTH
+}
+
+fn term_regex(term: &str, case_sensitive: bool) -> Option<Regex> {
+    let starts_with_word = term.chars().next().is_some_and(char::is_alphanumeric);
+    let ends_with_word = term.chars().last().is_some_and(char::is_alphanumeric);
+    let pattern = format!(
+        "{}{}{}",
+        if starts_with_word { r"\b" } else { "" },
+        regex::escape(term),
+        if ends_with_word { r"\b" } else { "" },
+    );
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .ok()
+}
+
+fn validate_term(term: &GlossaryTerm) -> GlossaryResult<()> {
+    if term.source_term().trim().is_empty() || term.target_term().trim().is_empty() {
+        return Err(GlossaryErrors::InvalidEntry(
+            "source and target terms must not be empty".to_string(),
+        ));
+    }
+    if term.source_language() == term.target_language() {
+        return Err(GlossaryErrors::InvalidEntry(
+            "source and target languages must differ".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Writes a temporary file, syncs it and renames it over `path`, so the
+/// file holds either the old or the new content after a crash.
+async fn write_atomically(path: &Path, content: &str) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let mut file = tokio::fs::File::create(&tmp_path).await?;
+    file.write_all(content.as_bytes()).await?;
+    file.sync_all().await?;
+    drop(file);
+    tokio::fs::rename(tmp_path, path).await
+}
+
+#[cfg(test)]
+mod test_glossary {
+    use crate::modules::glossary::config::GlossaryConfig;
+    use crate::modules::glossary::models::{GlossaryEntry, GlossaryTerm};
+    use crate::modules::glossary::{GlossaryStore, find_violations, import};
+
+    async fn matching(store: &GlossaryStore, text: &str) -> usize {
+        store
+            .matching_entries("en", "ru", "default", text)
+            .await
+            .len()
+    }
+
+    #[tokio::test]
+    async fn test_store_matches_and_persists() -> Result<(), anyhow::Error> {
+        let dir = std::env::temp_dir().join(format!("glossary-{}", std::process::id()));
+        let path = dir.join("glossary.json");
+        let config: GlossaryConfig = serde_json::from_value(serde_json::json!({
+            "storage_path": path.to_str(),
+        }))?;
+        let term = |source_term: &str, case_sensitive: bool| -> GlossaryTerm {
+            serde_json::from_value(serde_json::json!({
+                "source_language": "en",
+                "target_language": "ru",
+                "source_term": source_term,
+                "target_term": "винтовка",
+                "case_sensitive": case_sensitive,
+            }))
+            .unwrap()
+        };
+
+        let store = GlossaryStore::load(&config)?;
+        let entry = store.create(term("rifle", false)).await?;
+        assert_eq!(matching(&store, "Clean the Rifle.").await, 1);
+        assert_eq!(matching(&store, "Clean the rifles.").await, 0);
+
+        store.update(entry.id(), term("Rifle", true)).await?;
+        assert_eq!(matching(&store, "Clean the rifle.").await, 0);
+        assert_eq!(matching(&store, "Clean the Rifle.").await, 1);
+        assert!(!path.with_extension("tmp").exists());
+
+        let reloaded = GlossaryStore::load(&config)?;
+        assert_eq!(matching(&reloaded, "Clean the Rifle.").await, 1);
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_violations() -> Result<(), anyhow::Error> {
+        let terms = import::parse_csv("source,target\nrifle,винтовка\nround,патрон\n")?;
+        let entries: Vec<GlossaryEntry> = terms
+            .into_iter()
+            .enumerate()
+            .map(|(id, (source_term, target_term, note))| {
+                let term: GlossaryTerm = serde_json::from_value(serde_json::json!({
+                    "source_language": "en",
+                    "target_language": "ru",
+                    "source_term": source_term,
+                    "target_term": target_term,
+                    "note": note,
+                }))
+                .unwrap();
+                GlossaryEntry::new(id as u64, term)
+            })
+            .collect();
+
+        let violations = find_violations(&entries, "Я чищу свою винтовку.");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].source_term(), "round");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_tbx() -> Result<(), anyhow::Error> {
+        let tbx = r#"<martif type="TBX"><text><body>
+            <termEntry id="1">
+                <descrip type="definition">Shoulder weapon</descrip>
+                <langSet xml:lang="en-US"><tig><term>rifle</term></tig></langSet>
+                <langSet xml:lang="ru"><tig><term>винтовка</term></tig></langSet>
+            </termEntry>
+            <termEntry id="2">
+                <langSet xml:lang="en"><tig><term> R &amp; D </term></tig></langSet>
+                <langSet xml:lang="de"><tig><term>F&amp;E</term></tig></langSet>
+            </termEntry>
+        </body></text></martif>"#;
+        let terms = import::parse_tbx(tbx, "en", "ru")?;
+        assert_eq!(
+            terms,
+            vec![(
+                "rifle".to_owned(),
+                "винтовка".to_owned(),
+                Some("Shoulder weapon".to_owned())
+            )]
+        );
+        let terms = import::parse_tbx(tbx, "en", "de")?;
+        assert_eq!(terms[0].0, "R & D");
+        Ok(())
+    }
+}
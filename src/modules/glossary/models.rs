@@ -0,0 +1,145 @@
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Clone, Copy, Default, Deserialize, Serialize, PartialEq, Debug, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GlossaryEnforcement {
+    /// Violations are returned with the translation.
+    #[default]
+    Report,
+    /// The translation is repeated with the violated terms emphasized.
+    Retry,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, Debug, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    Tbx,
+}
+
+#[derive(Clone, Deserialize, Serialize, Getters, CopyGetters, PartialEq, Debug, ToSchema)]
+pub struct GlossaryTerm {
+    #[getset(get = "pub")]
+    #[schema(default = "en")]
+    source_language: String,
+    #[getset(get = "pub")]
+    #[schema(default = "ru")]
+    target_language: String,
+    /// Profile the term belongs to. Applies to every profile when omitted.
+    #[getset(get = "pub")]
+    #[serde(default)]
+    profile: Option<String>,
+    #[getset(get = "pub")]
+    #[schema(default = "rifle")]
+    source_term: String,
+    #[getset(get = "pub")]
+    #[schema(default = "винтовка")]
+    target_term: String,
+    #[getset(get_copy = "pub")]
+    #[serde(default)]
+    case_sensitive: bool,
+    #[getset(get = "pub")]
+    #[serde(default)]
+    note: Option<String>,
+}
+
+impl GlossaryTerm {
+    pub fn new(
+        scope: &GlossaryScope,
+        source_term: String,
+        target_term: String,
+        note: Option<String>,
+    ) -> Self {
+        Self {
+            source_language: scope.source_language.to_owned(),
+            target_language: scope.target_language.to_owned(),
+            profile: scope.profile.to_owned(),
+            source_term,
+            target_term,
+            case_sensitive: false,
+            note,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, Getters, CopyGetters, Debug, ToSchema)]
+pub struct GlossaryEntry {
+    #[getset(get_copy = "pub")]
+    id: u64,
+    #[getset(get = "pub")]
+    #[serde(flatten)]
+    term: GlossaryTerm,
+}
+
+impl GlossaryEntry {
+    pub fn new(id: u64, term: GlossaryTerm) -> Self {
+        Self { id, term }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, Getters, Debug)]
+#[getset(get = "pub")]
+pub struct GlossaryScope {
+    source_language: String,
+    target_language: String,
+    profile: Option<String>,
+}
+
+#[derive(Clone, Default, Deserialize, Getters, Debug, IntoParams)]
+#[getset(get = "pub")]
+pub struct GlossaryFilter {
+    source_language: Option<String>,
+    target_language: Option<String>,
+    profile: Option<String>,
+}
+
+#[derive(Clone, Deserialize, CopyGetters, Debug, IntoParams)]
+pub struct GlossaryImportParams {
+    #[getset(get_copy = "pub")]
+    format: ImportFormat,
+    source_language: String,
+    target_language: String,
+    profile: Option<String>,
+}
+
+impl GlossaryImportParams {
+    pub fn scope(&self) -> GlossaryScope {
+        GlossaryScope {
+            source_language: self.source_language.to_owned(),
+            target_language: self.target_language.to_owned(),
+            profile: self.profile.to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, CopyGetters, Debug, ToSchema)]
+#[getset(get_copy = "pub")]
+pub struct ImportReport {
+    imported: usize,
+    skipped: usize,
+}
+
+impl ImportReport {
+    pub fn new(imported: usize, skipped: usize) -> Self {
+        Self { imported, skipped }
+    }
+}
+
+/// Approved term that was expected in the translation but not found there.
+#[derive(Clone, Serialize, Deserialize, Getters, PartialEq, Debug, ToSchema)]
+#[getset(get = "pub")]
+pub struct GlossaryViolation {
+    source_term: String,
+    target_term: String,
+}
+
+impl GlossaryViolation {
+    pub fn new(source_term: String, target_term: String) -> Self {
+        Self {
+            source_term,
+            target_term,
+        }
+    }
+}
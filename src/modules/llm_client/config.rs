@@ -7,4 +7,6 @@ use crate::modules::llm_client::openai::config::OpenAIClientConfig;
 #[getset(get = "pub")]
 pub struct LLMClientConfig {
     openai: OpenAIClientConfig,
+    #[serde(default)]
+    fallback: Option<OpenAIClientConfig>,
 }
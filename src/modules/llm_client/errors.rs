@@ -16,8 +16,13 @@ pub enum TranslatorErrors {
     ModelModerationError(String),
     #[error("User is limited: {0}")]
     RateLimited(String),
+    #[error("Model refused to translate: {0}")]
+    Refusal(String),
     #[error("Invalid Model Response: {0}")]
     InvalidResponse(String),
+    /// The model answered, but its output cannot be used as a translation.
+    #[error("Model output rejected: {0}")]
+    RejectedOutput(String),
     #[error("Request Error: {0}")]
     RequestError(String),
     #[error("IO Error: {0}")]
@@ -10,7 +10,7 @@ use serde::Deserialize;
 use crate::ServiceConnect;
 use crate::modules::llm_client::config::LLMClientConfig;
 use crate::modules::llm_client::errors::TranslatorResult;
-use crate::modules::llm_client::models::TranslateTask;
+use crate::modules::llm_client::models::{Completion, TranslatePrompt};
 use crate::modules::llm_client::openai::OpenAIClient;
 
 #[derive(Clone, Deserialize, Debug)]
@@ -28,14 +28,81 @@ impl WorkingMode {
             WorkingMode::OPENAI => {
                 let config = config.openai();
                 tracing::info!("Running OPENAI mode!");
-                println!("Running OPENAI mode!");
                 Ok(Arc::new(OpenAIClient::connect(config).await?))
             }
         }
     }
+
+    pub async fn create_fallback_client(
+        &self,
+        config: &LLMClientConfig,
+    ) -> TranslatorResult<Option<Arc<dyn LLMClient + Send + Sync>>> {
+        let Some(config) = config.fallback() else {
+            return Ok(None);
+        };
+        match self {
+            WorkingMode::OPENAI => {
+                tracing::info!("Running OPENAI fallback client!");
+                Ok(Some(Arc::new(OpenAIClient::connect(config).await?)))
+            }
+        }
+    }
 }
 
 #[async_trait::async_trait]
 pub trait LLMClient {
-    async fn translate(&self, translate_task: TranslateTask) -> TranslatorResult<String>;
+    async fn translate(&self, prompt: TranslatePrompt) -> TranslatorResult<Completion>;
+}
+
+/// Shortest overlap treated as text repeated by the model at the start of a continuation.
+const MIN_CONTINUATION_OVERLAP: usize = 10;
+
+/// Joins output cut by `max_tokens` with its continuation. Models often
+/// repeat the last words before continuing, so the repeated part is dropped.
+pub fn stitch_continuation(previous: &str, continuation: &str) -> String {
+    let dropped = continuation_overlap(previous, continuation);
+    format!("{previous}{}", &continuation[dropped..])
+}
+
+/// Bytes at the start of a continuation that `stitch_continuation` drops:
+/// text repeated from the output before it, and whitespace after a repeat
+/// or after output that already ends with whitespace.
+pub fn continuation_overlap(previous: &str, continuation: &str) -> usize {
+    let trimmed = continuation.trim_start();
+    let whitespace = continuation.len() - trimmed.len();
+    let overlap = previous
+        .char_indices()
+        .map(|(index, _)| &previous[index..])
+        .filter(|suffix| suffix.trim().chars().count() >= MIN_CONTINUATION_OVERLAP)
+        .find(|suffix| trimmed.starts_with(suffix.trim_start()))
+        .map(|suffix| suffix.trim_start().len());
+
+    match overlap {
+        Some(length) => whitespace + length,
+        None if previous.ends_with(char::is_whitespace) => whitespace,
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod test_llm_client {
+    use crate::modules::llm_client::{continuation_overlap, stitch_continuation};
+
+    #[test]
+    fn test_stitch_continuation() {
+        assert_eq!(
+            stitch_continuation("Моя винтовка — мой лучший ", "друг. Это моя жизнь."),
+            "Моя винтовка — мой лучший друг. Это моя жизнь."
+        );
+        assert_eq!(
+            stitch_continuation(
+                "Моя винтовка — мой лучший",
+                "мой лучший друг. Это моя жизнь."
+            ),
+            "Моя винтовка — мой лучший друг. Это моя жизнь."
+        );
+        assert_eq!(stitch_continuation("", "Это моя жизнь."), "Это моя жизнь.");
+        assert_eq!(continuation_overlap("лучший ", "  друг."), 2);
+        assert_eq!(continuation_overlap("мой лучший", " мой лучший друг."), 20);
+    }
 }
@@ -1,9 +1,16 @@
 use std::fmt;
+use std::ops::AddAssign;
 
-use getset::{Getters, Setters};
+use getset::{CopyGetters, Getters, Setters};
 use serde::{Deserialize, Deserializer, Serialize};
 use utoipa::ToSchema;
 
+use crate::modules::context::models::TranslationContext;
+use crate::modules::formality::models::Formality;
+use crate::modules::formats::models::TextFormat;
+
+const DEFAULT_MAX_TOKENS: u32 = 32_000;
+
 #[derive(Serialize, Deserialize, Getters, Setters, PartialEq, Debug, Clone, ToSchema)]
 #[getset(get = "pub", set = "pub")]
 pub struct TranslateTask {
@@ -13,7 +20,6 @@ pub struct TranslateTask {
     #[serde(deserialize_with = "trim_string")]
     #[schema(default = "ru")]
     target_language: String,
-    #[serde(deserialize_with = "trim_string")]
     #[schema(
         default = r"This is my rifle. There are many like it, but this one is mine.
 My rifle is my best friend. It is my life. I must master it as I must master my life.
@@ -23,6 +29,24 @@ My rifle and I know that what counts in war is not the rounds we fire, the noise
 We know that it is the hits that count. We will hit.."
     )]
     text: String,
+    #[serde(default)]
+    #[schema(default = "military")]
+    profile: Option<String>,
+    #[serde(default)]
+    format: TextFormat,
+    #[serde(default)]
+    formality: Formality,
+    #[serde(default)]
+    context: Option<TranslationContext>,
+    /// Translations of earlier requests with the same ID are added to the context.
+    #[serde(default)]
+    session_id: Option<String>,
+    /// Translate the output back to the source language and score it against the text.
+    #[serde(default)]
+    back_translation: bool,
+    /// Request token log probabilities and return confidence of the translation.
+    #[serde(default)]
+    confidence: bool,
 }
 
 impl Default for TranslateTask {
@@ -36,6 +60,13 @@ My rifle, without me, is useless. Without my rifle, I am useless. I must fire my
 I must shoot straighter than my enemy who is trying to kill me. I must shoot him before he shoots me. I will...
 My rifle and I know that what counts in war is not the rounds we fire, the noise of our burst, nor the smoke we make.
 We know that it is the hits that count. We will hit...".to_owned(),
+        profile: None,
+        format: TextFormat::Plain,
+        formality: Formality::Default,
+        context: None,
+        session_id: None,
+        back_translation: false,
+        confidence: false,
         }
     }
 }
@@ -47,12 +78,152 @@ impl fmt::Display for TranslateTask {
             "TranslateTask:\n
             Source Language: \"{}\"\n
             Target Language: \"{}\"\n
+            Profile: \"{}\"\n
+            Format: \"{:?}\"\n
+            Formality: \"{}\"\n
+            Session: \"{}\"\n
+            Back-translation: \"{}\"\n
+            Confidence: \"{}\"\n
             Text: \"{}\"",
-            self.source_language, self.target_language, self.text
+            self.source_language,
+            self.target_language,
+            self.profile.as_deref().unwrap_or("default"),
+            self.format,
+            self.formality,
+            self.session_id.as_deref().unwrap_or("none"),
+            self.back_translation,
+            self.confidence,
+            self.text
         )
     }
 }
 
+#[derive(Clone, Deserialize, Serialize, CopyGetters, Debug, ToSchema)]
+#[getset(get_copy = "pub")]
+pub struct SamplingParams {
+    temperature: Option<f32>,
+    top_p: Option<f32>,
+    #[serde(default = "default_max_tokens")]
+    max_tokens: u32,
+}
+
+impl Default for SamplingParams {
+    fn default() -> Self {
+        Self {
+            temperature: None,
+            top_p: None,
+            max_tokens: DEFAULT_MAX_TOKENS,
+        }
+    }
+}
+
+/// Fully rendered chat request handed to an [`LLMClient`](crate::modules::llm_client::LLMClient) backend.
+#[derive(Getters, CopyGetters, Clone, Debug)]
+pub struct TranslatePrompt {
+    #[getset(get = "pub")]
+    system_prompt: String,
+    #[getset(get = "pub")]
+    user_prompt: String,
+    #[getset(get = "pub")]
+    sampling: SamplingParams,
+    /// Request log probabilities of the output tokens.
+    #[getset(get_copy = "pub")]
+    logprobs: bool,
+}
+
+impl TranslatePrompt {
+    pub fn new(system_prompt: String, user_prompt: String, sampling: SamplingParams) -> Self {
+        Self {
+            system_prompt,
+            user_prompt,
+            sampling,
+            logprobs: false,
+        }
+    }
+
+    pub fn with_logprobs(mut self, logprobs: bool) -> Self {
+        self.logprobs = logprobs;
+        self
+    }
+}
+
+/// Tokens spent on a translation, summed over continuations and retries.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, CopyGetters, PartialEq, Debug, ToSchema)]
+#[getset(get_copy = "pub")]
+pub struct TokenUsage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    total_tokens: u32,
+}
+
+impl TokenUsage {
+    pub fn new(prompt_tokens: u32, completion_tokens: u32, total_tokens: u32) -> Self {
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens,
+        }
+    }
+}
+
+impl AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+/// Complete model output returned by an [`LLMClient`](crate::modules::llm_client::LLMClient) backend.
+#[derive(Getters, CopyGetters, Clone, Debug)]
+pub struct Completion {
+    #[getset(get = "pub")]
+    text: String,
+    #[getset(get_copy = "pub")]
+    usage: TokenUsage,
+    /// Follow-up calls made because the output was cut by `max_tokens`.
+    #[getset(get_copy = "pub")]
+    continuations: u32,
+    /// Output tokens in order; empty unless requested with the prompt.
+    #[getset(get = "pub")]
+    logprobs: Vec<TokenLogprob>,
+}
+
+impl Completion {
+    pub fn new(text: String, usage: TokenUsage, continuations: u32) -> Self {
+        Self {
+            text,
+            usage,
+            continuations,
+            logprobs: Vec::new(),
+        }
+    }
+
+    pub fn with_logprobs(mut self, logprobs: Vec<TokenLogprob>) -> Self {
+        self.logprobs = logprobs;
+        self
+    }
+}
+
+/// Output token with its natural log probability.
+#[derive(Getters, CopyGetters, Clone, Debug)]
+pub struct TokenLogprob {
+    #[getset(get = "pub")]
+    token: String,
+    #[getset(get_copy = "pub")]
+    logprob: f64,
+}
+
+impl TokenLogprob {
+    pub fn new(token: String, logprob: f64) -> Self {
+        Self { token, logprob }
+    }
+}
+
+fn default_max_tokens() -> u32 {
+    DEFAULT_MAX_TOKENS
+}
+
 fn trim_string<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
     D: Deserializer<'de>,
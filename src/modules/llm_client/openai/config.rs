@@ -6,5 +6,12 @@ use serde::Deserialize;
 pub struct OpenAIClientConfig {
     address: String,
     openai_api_key: String,
-    model_name: String
+    model_name: String,
+    /// Follow-up requests made when the output is cut by `max_tokens`.
+    #[serde(default = "default_max_continuations")]
+    max_continuations: u32,
+}
+
+fn default_max_continuations() -> u32 {
+    3
 }
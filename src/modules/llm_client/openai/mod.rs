@@ -2,7 +2,6 @@ pub mod config;
 pub mod errors;
 pub mod models;
 
-use regex::Regex;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -10,17 +9,19 @@ use async_openai::Client;
 use async_openai::config::OpenAIConfig;
 use async_openai::error::OpenAIError;
 use async_openai::types::{
+    ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
     ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
-    CreateChatCompletionRequestArgs,
+    CreateChatCompletionRequest, CreateChatCompletionRequestArgs, FinishReason,
 };
 use getset::CopyGetters;
-use isolang::Language;
 
 use crate::ServiceConnect;
-use crate::modules::llm_client::LLMClient;
-use crate::modules::llm_client::errors::TranslatorResult;
-use crate::modules::llm_client::models::TranslateTask;
+use crate::modules::llm_client::errors::{TranslatorErrors, TranslatorResult};
+use crate::modules::llm_client::models::{
+    Completion, SamplingParams, TokenLogprob, TokenUsage, TranslatePrompt,
+};
 use crate::modules::llm_client::openai::config::OpenAIClientConfig;
+use crate::modules::llm_client::{LLMClient, continuation_overlap, stitch_continuation};
 
 #[derive(Clone, CopyGetters)]
 pub struct OpenAIClient {
@@ -54,102 +55,271 @@ impl ServiceConnect for OpenAIClient {
     }
 }
 
-#[async_trait::async_trait]
-impl LLMClient for OpenAIClient {
-    async fn translate(&self, translate_task: TranslateTask) -> TranslatorResult<String> {
-        const SYSTEM_PROMPT: &str = "You are a machine translation model specialized in military and legal texts.
-Translate with maximum accuracy, without interpretation or alteration of meaning.
-Preserve terminology, structure, numbering, formatting, and the formal tone of documents.
-Translate military terminology according to established professional usage.
-Do not decipher abbreviations.
-Do not add comments, explanations, or summaries.
-If a term is ambiguous, keep the original or use the most neutral equivalent.
-By default, perform translation only.
-Additionally:
-Do not change labels or formatting elements such as:
-lettered lists (a., b., c.)
-Roman numeral points (I., II., III.)
-numbered lists (1., 1.1., etc.)
-links, references, citations
-code blocks, inline code, technical inserts
-any original notation, markers, or formatting symbols
-All structural and typographical elements must remain exactly as in the source text
-                                 ";
-        const MAX_TOKENS: u32 = 32_000;
-        let model_name = self.options.model_name();
-
-        let source_language: &str = Language::from_639_1(translate_task.source_language())
-            .unwrap()
-            .to_name();
-        let target_language: &str = Language::from_639_1(translate_task.target_language())
-            .unwrap()
-            .to_name();
-
-        let text = translate_task.text().trim();
-        if text.is_empty() {
-            tracing::debug!("Text is empty string. Returning.");
-            return Ok(text.to_string());
+const CONTINUE_PROMPT: &str = "Continue the translation exactly where it stopped. \
+Output only the remaining part, without repeating already translated text.";
+
+impl OpenAIClient {
+    fn build_request(
+        &self,
+        messages: &[ChatCompletionRequestMessage],
+        sampling: &SamplingParams,
+        logprobs: bool,
+    ) -> TranslatorResult<CreateChatCompletionRequest> {
+        let mut request_args = CreateChatCompletionRequestArgs::default();
+        request_args
+            .model(self.options.model_name())
+            .messages(messages.to_vec())
+            .max_tokens(sampling.max_tokens());
+        if let Some(temperature) = sampling.temperature() {
+            request_args.temperature(temperature);
+        }
+        if let Some(top_p) = sampling.top_p() {
+            request_args.top_p(top_p);
         }
+        if logprobs {
+            request_args.logprobs(true);
+        }
+        Ok(request_args.build()?)
+    }
+}
 
-        let patterns = [
-            r"^\s*(\d+)\s*$",                         // Целые числа: "123", "42"
-            r"^\s*([IVXLCDM]+\.?)\s*$",               // Римские цифры: "I", "II", "IV"
-            r"^\s*([a-zA-Z]\.)\s*$",                  // Буквенные пункты: "a.", "b.", "A."
-            r"^\s*(\d+\.)\s*$",                       // Номера с точкой: "1.", "2.", "10."
-            r"^\s*(\d+/\d+)\s*$",                     // Дроби: "1/2", "3/4"
-            r"^\s*\((\d+|[a-zA-Z]|[IVXLCDM]+)\)\s*$", // Номера в скобках: "(1)", "(a)", "(IV)"
+#[async_trait::async_trait]
+impl LLMClient for OpenAIClient {
+    async fn translate(&self, prompt: TranslatePrompt) -> TranslatorResult<Completion> {
+        let mut messages: Vec<ChatCompletionRequestMessage> = vec![
+            ChatCompletionRequestSystemMessageArgs::default()
+                .content(prompt.system_prompt().as_str())
+                .build()?
+                .into(),
+            ChatCompletionRequestUserMessageArgs::default()
+                .content(prompt.user_prompt().as_str())
+                .build()?
+                .into(),
         ];
-        for pattern in &patterns {
-            if let Ok(re) = Regex::new(pattern)
-                && let Some(_caps) = re.captures(text)
+        let max_continuations = *self.options.max_continuations();
+        let mut text = String::new();
+        let mut usage = TokenUsage::default();
+        let mut continuations = 0;
+        let mut logprobs = Vec::new();
+
+        loop {
+            let request = self.build_request(&messages, prompt.sampling(), prompt.logprobs())?;
+            let ctx = self.client.read().await;
+            let response = ctx.chat().create(request).await?;
+            drop(ctx);
+
+            if let Some(response_usage) = &response.usage {
+                usage += TokenUsage::new(
+                    response_usage.prompt_tokens,
+                    response_usage.completion_tokens,
+                    response_usage.total_tokens,
+                );
+            }
+            let choice = response.choices.into_iter().next().ok_or_else(|| {
+                TranslatorErrors::InvalidResponse("completion has no choices".to_string())
+            })?;
+            if let Some(refusal) = choice.message.refusal {
+                return Err(TranslatorErrors::Refusal(refusal));
+            }
+            let part = choice.message.content.unwrap_or_default();
+            if let Some(content) = choice
+                .logprobs
+                .and_then(|choice_logprobs| choice_logprobs.content)
             {
-                tracing::debug!("String is a number. Returning.");
-                return Ok(text.to_string());
+                // Tokens of the text a continuation repeats are not in the output.
+                let dropped = continuation_overlap(&text, &part);
+                let mut end = 0;
+                logprobs.extend(
+                    content
+                        .into_iter()
+                        .filter(|token| {
+                            end += token.token.len();
+                            end > dropped
+                        })
+                        .map(|token| TokenLogprob::new(token.token, f64::from(token.logprob))),
+                );
+            }
+
+            match choice.finish_reason {
+                Some(FinishReason::Length) => {
+                    if continuations >= max_continuations {
+                        return Err(TranslatorErrors::RejectedOutput(format!(
+                            "output is truncated by max_tokens after {continuations} continuation(s)"
+                        )));
+                    }
+                    continuations += 1;
+                    tracing::warn!(
+                        continuation = continuations,
+                        "Output is truncated by max_tokens, requesting continuation"
+                    );
+                    text = stitch_continuation(&text, &part);
+                    messages.push(
+                        ChatCompletionRequestAssistantMessageArgs::default()
+                            .content(part)
+                            .build()?
+                            .into(),
+                    );
+                    messages.push(
+                        ChatCompletionRequestUserMessageArgs::default()
+                            .content(CONTINUE_PROMPT)
+                            .build()?
+                            .into(),
+                    );
+                }
+                Some(FinishReason::ContentFilter) => {
+                    return Err(TranslatorErrors::RejectedOutput(
+                        "completion is stopped by content filter".to_string(),
+                    ));
+                }
+                Some(FinishReason::ToolCalls) | Some(FinishReason::FunctionCall) => {
+                    return Err(TranslatorErrors::RejectedOutput(
+                        "model returned a tool call instead of translation".to_string(),
+                    ));
+                }
+                Some(FinishReason::Stop) | None => {
+                    if text.is_empty() && part.trim().is_empty() {
+                        return Err(TranslatorErrors::RejectedOutput(
+                            "completion has no content".to_string(),
+                        ));
+                    }
+                    text = stitch_continuation(&text, &part);
+                    break;
+                }
             }
         }
-        let user_prompt = format!("Translate the following segment into {target_language}, without additional explanation.
-        The {source_language} segment:
-        ```
-        {text}
-        ```", target_language=target_language, source_language=source_language, text=text);
-        println!("{}", user_prompt);
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(model_name)
-            .messages([
-                ChatCompletionRequestSystemMessageArgs::default()
-                    .content(SYSTEM_PROMPT)
-                    .build()?
-                    .into(),
-                ChatCompletionRequestUserMessageArgs::default()
-                    .content(user_prompt)
-                    .build()?
-                    .into(),
-            ])
-            .max_tokens(MAX_TOKENS)
-            .build()?;
-        let ctx = self.client.read().await;
-        let response = ctx.chat().create(request).await?;
-        let transalted_response = response.choices[0].message.content.as_deref().unwrap();
-        println!("\nResponse:\n");
-        Ok(transalted_response.to_owned())
+
+        tracing::debug!(
+            prompt_tokens = usage.prompt_tokens(),
+            completion_tokens = usage.completion_tokens(),
+            continuations = continuations,
+            "Completion received"
+        );
+        Ok(Completion::new(text, usage, continuations).with_logprobs(logprobs))
     }
 }
 
 #[cfg(test)]
 mod test_open_ai {
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    use axum::Json;
+    use axum::routing::post;
+    use serde_json::{Value, json};
 
+    use crate::ServiceConnect;
     use crate::config::ServiceConfig;
-    use crate::modules::llm_client::TranslateTask;
+    use crate::modules::llm_client::LLMClient;
+    use crate::modules::llm_client::errors::TranslatorErrors;
+    use crate::modules::llm_client::models::{SamplingParams, TranslatePrompt};
+    use crate::modules::llm_client::openai::OpenAIClient;
+    use crate::modules::llm_client::openai::config::OpenAIClientConfig;
+
+    fn response(content: &str, finish_reason: &str) -> Value {
+        json!({
+            "id": "chatcmpl-test",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "test-model",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": content },
+                "finish_reason": finish_reason
+            }],
+            "usage": { "prompt_tokens": 10, "completion_tokens": 5, "total_tokens": 15 }
+        })
+    }
+
+    /// Response with log probabilities of `tokens`, which make up its content.
+    fn response_with_tokens(tokens: &[&str], finish_reason: &str) -> Value {
+        let mut response = response(&tokens.concat(), finish_reason);
+        let content: Vec<Value> = tokens
+            .iter()
+            .map(|token| json!({ "token": token, "logprob": -0.1, "bytes": null, "top_logprobs": [] }))
+            .collect();
+        response["choices"][0]["logprobs"] = json!({ "content": content });
+        response
+    }
+
+    /// Client of a local server answering chat completions with `responses` in order.
+    async fn mock_client(responses: Vec<Value>) -> OpenAIClient {
+        let responses = Arc::new(Mutex::new(VecDeque::from(responses)));
+        let app = axum::Router::new().route(
+            "/chat/completions",
+            post(move |Json(_): Json<Value>| async move {
+                Json(responses.lock().unwrap().pop_front().unwrap())
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let config: OpenAIClientConfig = serde_json::from_value(json!({
+            "address": format!("http://{address}"),
+            "openai_api_key": "test",
+            "model_name": "test-model",
+            "max_continuations": 1
+        }))
+        .unwrap();
+        OpenAIClient::connect(&config).await.unwrap()
+    }
+
+    fn prompt() -> TranslatePrompt {
+        TranslatePrompt::new(
+            "Translate to Russian".to_string(),
+            "My rifle is my best friend.".to_string(),
+            SamplingParams::default(),
+        )
+    }
 
     #[tokio::test]
+    #[ignore = "needs the LLM backend of the service config"]
     async fn test_openai_transalting() -> Result<(), anyhow::Error> {
-        let translate_task = TranslateTask::default();
         let s_config = ServiceConfig::new()?;
         let llm_client_config = s_config.llm_client();
         let mode = s_config.server().llm_mode();
         let client = mode.create_client(llm_client_config).await?;
-        let result = client.translate(translate_task).await?;
-        println!("{}", result);
+        let completion = client.translate(prompt()).await?;
+        assert!(!completion.text().trim().is_empty());
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_openai_stitches_continuations() {
+        let client = mock_client(vec![
+            response_with_tokens(&["Моя", " винтовка", " —", " мой", " лучший"], "length"),
+            response_with_tokens(&[" мой", " лучший", " друг", "."], "stop"),
+        ])
+        .await;
+        let completion = client.translate(prompt()).await.unwrap();
+        assert_eq!(completion.text(), "Моя винтовка — мой лучший друг.");
+        assert_eq!(completion.continuations(), 1);
+        let tokens: String = completion
+            .logprobs()
+            .iter()
+            .map(|token| token.token().as_str())
+            .collect();
+        assert_eq!(&tokens, completion.text());
+        assert_eq!(completion.usage().prompt_tokens(), 20);
+        assert_eq!(completion.usage().total_tokens(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_openai_finish_reasons() {
+        let client = mock_client(vec![
+            response("Моя винтовка", "length"),
+            response("— мой лучший", "length"),
+        ])
+        .await;
+        let result = client.translate(prompt()).await;
+        assert!(matches!(result, Err(TranslatorErrors::RejectedOutput(_))));
+
+        let client = mock_client(vec![response("", "content_filter")]).await;
+        let result = client.translate(prompt()).await;
+        assert!(matches!(result, Err(TranslatorErrors::RejectedOutput(_))));
+
+        let client = mock_client(vec![response("  ", "stop")]).await;
+        let result = client.translate(prompt()).await;
+        assert!(matches!(result, Err(TranslatorErrors::RejectedOutput(_))));
+    }
 }
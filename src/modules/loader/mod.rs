@@ -11,8 +11,12 @@ use serde_json::Value;
 use crate::config::ServiceConfig;
 use crate::modules::loader::models::units::{Language, TargetLanguage};
 use crate::modules::loader::{errors::LoaderResult, models::units::ModelGarden};
+use crate::modules::profiles::ProfileRegistry;
 
-pub async fn model_garden(server_config: &ServiceConfig) -> LoaderResult<ModelGarden> {
+pub async fn model_garden(
+    server_config: &ServiceConfig,
+    profiles: &ProfileRegistry,
+) -> LoaderResult<ModelGarden> {
     const SOURCE_LANGUAGE_IDX: usize = 0;
     const TARGET_LANGUAGE_IDX: usize = 1;
     tracing::info!("Getting languages");
@@ -31,6 +35,7 @@ pub async fn model_garden(server_config: &ServiceConfig) -> LoaderResult<ModelGa
     language_permutations.sort();
     let formalized_string = read_formalized_file(&file_path)?;
     let mut model_garden = ModelGarden::new();
+    model_garden.set_profiles(profiles.list());
 
     let deserialized_data: Value = serde_json::from_str(&formalized_string)?;
 
@@ -116,12 +121,15 @@ fn read_formalized_file(formalized_file_path: &Path) -> LoaderResult<String> {
 
 #[cfg(test)]
 mod test_loader {
+    use crate::modules::profiles::ProfileRegistry;
     use crate::{config::ServiceConfig, modules::loader::model_garden};
 
     #[tokio::test]
     async fn test_model_garden() -> Result<(), anyhow::Error> {
         let service_config = ServiceConfig::new()?;
-        let _test_data = model_garden(&service_config).await?;
+        let profiles = ProfileRegistry::load(service_config.profiles())?;
+        let test_data = model_garden(&service_config, &profiles).await?;
+        assert!(!test_data.profiles().is_empty());
 
         Ok(())
     }
@@ -2,11 +2,17 @@ use getset::{Getters, MutGetters};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::modules::formality;
+use crate::modules::profiles::models::ProfileInfo;
+
 #[derive(Serialize, Deserialize, Getters, ToSchema, Debug)]
 #[getset(get = "pub")]
 pub struct TargetLanguage {
     name: String,
     iso: String,
+    /// The `formality` option of translate requests has an effect for this target.
+    #[serde(default)]
+    supports_formality: bool,
 }
 
 #[derive(Serialize, Deserialize, Getters, ToSchema, Debug, MutGetters)]
@@ -22,6 +28,8 @@ pub struct Language {
 #[getset(get = "pub", get_mut = "pub")]
 pub struct ModelGarden {
     languages: Vec<Language>,
+    #[serde(default)]
+    profiles: Vec<ProfileInfo>,
 }
 
 impl Language {
@@ -40,7 +48,12 @@ impl Language {
 
 impl TargetLanguage {
     pub fn new(name: String, iso: String) -> Self {
-        Self { name, iso }
+        let supports_formality = formality::supported(&iso);
+        Self {
+            name,
+            iso,
+            supports_formality,
+        }
     }
 }
 
@@ -48,12 +61,17 @@ impl ModelGarden {
     pub fn new() -> Self {
         Self {
             languages: Vec::new(),
+            profiles: Vec::new(),
         }
     }
 
     pub fn add_language(&mut self, language: Language) {
         self.languages.push(language);
     }
+
+    pub fn set_profiles(&mut self, profiles: Vec<ProfileInfo>) {
+        self.profiles = profiles;
+    }
 }
 
 impl Default for ModelGarden {
@@ -0,0 +1,11 @@
+use getset::{CopyGetters, Getters};
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, CopyGetters, Getters)]
+pub struct MaskingConfig {
+    #[getset(get_copy = "pub")]
+    enabled: bool,
+    #[getset(get = "pub")]
+    #[serde(default)]
+    custom_patterns: Vec<String>,
+}
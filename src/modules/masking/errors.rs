@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+pub type MaskingResult<T> = Result<T, MaskingErrors>;
+
+#[derive(Debug, Error)]
+pub enum MaskingErrors {
+    #[error("Invalid masking pattern: {0}")]
+    InvalidPattern(String),
+}
+
+impl From<regex::Error> for MaskingErrors {
+    fn from(err: regex::Error) -> Self {
+        MaskingErrors::InvalidPattern(err.to_string())
+    }
+}
@@ -0,0 +1,326 @@
+pub mod config;
+pub mod errors;
+pub mod models;
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use regex::Regex;
+
+use crate::modules::masking::config::MaskingConfig;
+use crate::modules::masking::errors::MaskingResult;
+use crate::modules::masking::models::{
+    MaskedSpan, MaskedText, PlaceholderIssue, PlaceholderIssueKind,
+};
+
+pub const PLACEHOLDER_PATTERN: &str = r"⟦\d+⟧";
+/// Matches placeholders together with the damage small models tend to do to
+/// them: inner spaces and brackets replaced by `[[`/`]]`. As `[[n]]` is also
+/// ordinary wiki text, it is only taken for a placeholder the model lost.
+const DAMAGED_PLACEHOLDER_PATTERN: &str = r"(?:⟦|\[\[)\s*(\d+)\s*(?:⟧|\]\])";
+
+/// Rule that hides numbers, which ICU plural segments need visible.
+const NUMBER_RULE: &str = "number_with_unit";
+
+/// Built-in rules in application order. Code goes first, so markup and links
+/// inside code blocks are protected as part of the block.
+/// Units are abbreviations that are not words: single letters such as `m`,
+/// `l` or `A` and words such as `in` or `mi` also follow plain counts
+/// ("3 in total", "5 l" for a list item) and would hide them from the model.
+const BUILTIN_RULES: [(&str, &str, Option<usize>); 6] = [
+    (
+        "code_fence",
+        r"(?ms)^[ \t]*(?:```|~~~).*?^[ \t]*(?:```|~~~)[ \t]*$",
+        None,
+    ),
+    ("inline_code", r"`[^`\n]+`", None),
+    ("dnt", r"(?s)<dnt>(.*?)</dnt>", Some(1)),
+    (
+        "url",
+        r#"\b(?:https?://|ftp://|www\.)[^\s<>"'`]+[^\s<>"'`.,;:!?)\]]"#,
+        None,
+    ),
+    ("email", r"\b[\w.+-]+@[\w-]+(?:\.[\w-]+)+\b", None),
+    (
+        NUMBER_RULE,
+        r"\b\d+(?:[.,]\d+)?\s?(?:km/h|m/s|mm|cm|km|kg|mg|ml|MHz|GHz|kHz|Hz|kW|MW|ft|lb|oz|nm|kt|mph)\b|\b\d+(?:[.,]\d+)?\s?(?:%|°[CF]?)",
+        None,
+    ),
+];
+
+struct MaskRule {
+    name: String,
+    regex: Regex,
+    /// Capture group restored instead of the whole match (strips `<dnt>` tags).
+    restore_group: Option<usize>,
+}
+
+/// Replaces spans that must reach the output untouched with opaque
+/// placeholders before translation and puts them back afterwards.
+pub struct Masker {
+    enabled: bool,
+    rules: Vec<MaskRule>,
+    placeholder: Regex,
+    damaged_placeholder: Regex,
+}
+
+impl Masker {
+    pub fn new(config: &MaskingConfig) -> MaskingResult<Self> {
+        let mut rules = Vec::new();
+        for (name, pattern, restore_group) in BUILTIN_RULES {
+            rules.push(MaskRule {
+                name: name.to_owned(),
+                regex: Regex::new(pattern)?,
+                restore_group,
+            });
+        }
+        for (index, pattern) in config.custom_patterns().iter().enumerate() {
+            rules.push(MaskRule {
+                name: format!("custom_{}", index + 1),
+                regex: Regex::new(pattern)?,
+                restore_group: None,
+            });
+        }
+
+        Ok(Masker {
+            enabled: config.enabled(),
+            rules,
+            placeholder: Regex::new(PLACEHOLDER_PATTERN)?,
+            damaged_placeholder: Regex::new(DAMAGED_PLACEHOLDER_PATTERN)?,
+        })
+    }
+
+    pub fn mask(&self, text: &str) -> MaskedText {
+        self.mask_segment(MaskedText::new(text.to_owned(), Vec::new()))
+    }
+
+    /// Masks text that already carries placeholders (inline markup of a
+    /// document); numbering continues after the existing ones.
+    pub fn mask_segment(&self, segment: MaskedText) -> MaskedText {
+        self.mask_with(segment, true)
+    }
+
+    /// Masks a segment whose numbers must reach the model, such as the
+    /// number an ICU plural branch writes for `#`.
+    pub fn mask_segment_keeping_numbers(&self, segment: MaskedText) -> MaskedText {
+        self.mask_with(segment, false)
+    }
+
+    fn mask_with(&self, segment: MaskedText, numbers: bool) -> MaskedText {
+        if !self.enabled {
+            return segment;
+        }
+
+        let mut spans: Vec<MaskedSpan> = segment.spans().clone();
+        let mut current = segment.text().clone();
+        for rule in &self.rules {
+            if !numbers && rule.name == NUMBER_RULE {
+                continue;
+            }
+            let protected: Vec<Range<usize>> = self
+                .placeholder
+                .find_iter(&current)
+                .map(|found| found.range())
+                .collect();
+
+            let mut output = String::with_capacity(current.len());
+            let mut last = 0;
+            for caps in rule.regex.captures_iter(&current) {
+                let Some(found) = caps.get(0) else {
+                    continue;
+                };
+                let range = found.range();
+                if range.is_empty()
+                    || protected
+                        .iter()
+                        .any(|p| p.start < range.end && range.start < p.end)
+                {
+                    continue;
+                }
+                let original = match rule.restore_group {
+                    Some(group) => caps.get(group).map_or("", |inner| inner.as_str()),
+                    None => found.as_str(),
+                };
+                let placeholder = format!("⟦{}⟧", spans.len() + 1);
+                tracing::debug!(rule = rule.name, placeholder = placeholder, "Masked span");
+
+                output.push_str(&current[last..range.start]);
+                output.push_str(&placeholder);
+                spans.push(MaskedSpan::new(placeholder, original.to_owned()));
+                last = range.end;
+            }
+            output.push_str(&current[last..]);
+            current = output;
+        }
+
+        MaskedText::new(current, spans)
+    }
+
+    /// Restores masked spans in the translation and reports every placeholder
+    /// that was lost, duplicated or altered by the model.
+    pub fn unmask(
+        &self,
+        masked: &MaskedText,
+        translation: &str,
+    ) -> (String, Vec<PlaceholderIssue>) {
+        if masked.spans().is_empty() {
+            return (translation.to_owned(), Vec::new());
+        }
+
+        let is_wiki_link = |found: &str| found.starts_with("[[") && found.ends_with("]]");
+        let bracketed: Vec<&str> = self
+            .damaged_placeholder
+            .captures_iter(translation)
+            .filter(|caps| !is_wiki_link(&caps[0]))
+            .filter_map(|caps| caps.get(1).map(|id| id.as_str()))
+            .collect();
+
+        let mut occurrences: HashMap<usize, usize> = HashMap::new();
+        let mut altered: Vec<usize> = Vec::new();
+        let restored = self
+            .damaged_placeholder
+            .replace_all(translation, |caps: &regex::Captures| {
+                let found = &caps[0];
+                let span = caps[1]
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|id| Some(id).zip(masked.spans().get(id.checked_sub(1)?)))
+                    .filter(|(id, _)| {
+                        !is_wiki_link(found)
+                            || (!bracketed.contains(&&caps[1]) && !occurrences.contains_key(id))
+                    });
+                match span {
+                    Some((id, span)) => {
+                        *occurrences.entry(id).or_default() += 1;
+                        if found != span.placeholder() && !altered.contains(&id) {
+                            altered.push(id);
+                        }
+                        span.original().to_owned()
+                    }
+                    None => found.to_owned(),
+                }
+            })
+            .into_owned();
+
+        let mut issues = Vec::new();
+        for (index, span) in masked.spans().iter().enumerate() {
+            let id = index + 1;
+            let kind = match occurrences.get(&id).copied().unwrap_or_default() {
+                0 => Some(PlaceholderIssueKind::Lost),
+                1 if altered.contains(&id) => Some(PlaceholderIssueKind::Altered),
+                1 => None,
+                _ => Some(PlaceholderIssueKind::Duplicated),
+            };
+            if let Some(kind) = kind {
+                issues.push(PlaceholderIssue::new(
+                    span.placeholder().to_owned(),
+                    span.original().to_owned(),
+                    kind,
+                ));
+            }
+        }
+
+        if !issues.is_empty() {
+            tracing::warn!(
+                issues = format!("{:?}", issues),
+                "Placeholder issues in translation"
+            );
+        }
+        (restored, issues)
+    }
+}
+
+/// Instruction for the `{{placeholders}}` template variable.
+pub fn placeholder_instruction(masked: &MaskedText) -> Option<String> {
+    if masked.spans().is_empty() {
+        return None;
+    }
+    let placeholders: Vec<&str> = masked
+        .spans()
+        .iter()
+        .map(|span| span.placeholder().as_str())
+        .collect();
+    Some(format!(
+        "The text contains placeholders {}. Copy every placeholder exactly once and unchanged; do not translate, split or remove them.",
+        placeholders.join(" ")
+    ))
+}
+
+#[cfg(test)]
+mod test_masking {
+    use crate::modules::masking::Masker;
+    use crate::modules::masking::config::MaskingConfig;
+    use crate::modules::masking::models::PlaceholderIssueKind;
+
+    fn masker() -> Masker {
+        let config: MaskingConfig = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "custom_patterns": [r"\b\d{2}[A-Z]{3}\d{5}\b"],
+        }))
+        .unwrap();
+        Masker::new(&config).unwrap()
+    }
+
+    #[test]
+    fn test_mask_roundtrip() {
+        let masker = masker();
+        let text = "See https://example.com/a?b=1, write to ops@example.com, run `make`, \
+                    keep <dnt>Operation Anvil</dnt> at 38SMB12345 with 120 mm.";
+        let masked = masker.mask(text);
+        assert_eq!(
+            masked.text(),
+            "See ⟦3⟧, write to ⟦4⟧, run ⟦1⟧, keep ⟦2⟧ at ⟦6⟧ with ⟦5⟧."
+        );
+
+        let (restored, issues) = masker.unmask(&masked, masked.text());
+        assert!(issues.is_empty());
+        assert_eq!(restored, text.replace("<dnt>", "").replace("</dnt>", ""));
+    }
+
+    #[test]
+    fn test_mask_numbers_with_units() {
+        let masker = masker();
+        for text in [
+            "3 in total",
+            "Take 2 a day",
+            "Step 5 l",
+            "Gate 10 A",
+            "Hold for 4 m",
+            "Page 7 mi amigo",
+        ] {
+            assert_eq!(masker.mask(text).text(), text);
+        }
+        assert_eq!(
+            masker.mask("Drive 5 km/h, climb 30% at 12,5 kg").text(),
+            "Drive ⟦1⟧, climb ⟦2⟧ at ⟦3⟧"
+        );
+    }
+
+    #[test]
+    fn test_unmask_issues() {
+        let masker = masker();
+        let masked = masker.mask("Call `a` and `b` and `c`.");
+        let (restored, issues) = masker.unmask(&masked, "Вызовите [[ 1 ]] и ⟦2⟧ ⟦2⟧.");
+        assert_eq!(restored, "Вызовите `a` и `b` `b`.");
+        let kinds: Vec<PlaceholderIssueKind> = issues.iter().map(|issue| issue.kind()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                PlaceholderIssueKind::Altered,
+                PlaceholderIssueKind::Duplicated,
+                PlaceholderIssueKind::Lost
+            ]
+        );
+
+        let masked = masker.mask("See [[2]], then call `a` and `b`.");
+        assert_eq!(masked.text(), "See [[2]], then call ⟦1⟧ and ⟦2⟧.");
+        let (restored, issues) = masker.unmask(&masked, "См. [[2]], затем вызовите ⟦1⟧ и ⟦2⟧.");
+        assert_eq!(restored, "См. [[2]], затем вызовите `a` и `b`.");
+        assert!(issues.is_empty());
+        let (restored, issues) = masker.unmask(&masked, "См. [[2]], затем вызовите [[1]] и ⟦2⟧.");
+        assert_eq!(restored, "См. [[2]], затем вызовите `a` и `b`.");
+        assert_eq!(issues[0].kind(), PlaceholderIssueKind::Altered);
+        let (restored, _) = masker.unmask(&masked, "См. [[1]], затем вызовите [[1]] и ⟦2⟧.");
+        assert_eq!(restored, "См. `a`, затем вызовите [[1]] и `b`.");
+    }
+}
@@ -0,0 +1,85 @@
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Getters, Debug)]
+#[getset(get = "pub")]
+pub struct MaskedSpan {
+    placeholder: String,
+    original: String,
+}
+
+impl MaskedSpan {
+    pub fn new(placeholder: String, original: String) -> Self {
+        Self {
+            placeholder,
+            original,
+        }
+    }
+}
+
+#[derive(Clone, Default, Getters, Debug)]
+#[getset(get = "pub")]
+pub struct MaskedText {
+    text: String,
+    spans: Vec<MaskedSpan>,
+}
+
+impl MaskedText {
+    pub fn new(text: String, spans: Vec<MaskedSpan>) -> Self {
+        Self { text, spans }
+    }
+
+    /// Appends text that is sent to the model as is.
+    pub fn push_text(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    /// Appends a span hidden behind the next placeholder.
+    pub fn push_protected(&mut self, original: &str) {
+        let placeholder = format!("⟦{}⟧", self.spans.len() + 1);
+        self.text.push_str(&placeholder);
+        self.spans
+            .push(MaskedSpan::new(placeholder, original.to_owned()));
+    }
+
+    /// True when nothing but placeholders and whitespace is left to translate.
+    pub fn is_fully_masked(&self) -> bool {
+        let mut rest = self.text.clone();
+        for span in &self.spans {
+            rest = rest.replace(span.placeholder(), "");
+        }
+        rest.trim().is_empty()
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Debug, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaceholderIssueKind {
+    /// Placeholder is missing in the translation.
+    Lost,
+    /// Placeholder occurs in the translation more than once.
+    Duplicated,
+    /// Placeholder was modified by the model and repaired.
+    Altered,
+}
+
+#[derive(Clone, Serialize, Deserialize, Getters, CopyGetters, PartialEq, Debug, ToSchema)]
+pub struct PlaceholderIssue {
+    #[getset(get = "pub")]
+    placeholder: String,
+    #[getset(get = "pub")]
+    original: String,
+    #[getset(get_copy = "pub")]
+    kind: PlaceholderIssueKind,
+}
+
+impl PlaceholderIssue {
+    pub fn new(placeholder: String, original: String, kind: PlaceholderIssueKind) -> Self {
+        Self {
+            placeholder,
+            original,
+            kind,
+        }
+    }
+}
@@ -1,3 +1,19 @@
+pub mod back_translation;
+pub mod confidence;
+pub mod consistency;
+pub mod context;
+pub mod documents;
+pub mod formality;
+pub mod formats;
+pub mod glossary;
 pub mod llm_client;
 pub mod loader;
+pub mod masking;
+pub mod pass_through;
+pub mod pipeline;
+pub mod profiles;
+pub mod sanitizer;
+pub mod tables;
+pub mod templates;
 pub mod tokenizer;
+pub mod validation;
@@ -0,0 +1,26 @@
+use getset::{CopyGetters, Getters};
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, CopyGetters, Getters)]
+pub struct PassThroughConfig {
+    #[getset(get_copy = "pub")]
+    #[serde(default = "default_builtin_rules")]
+    builtin_rules: bool,
+    #[getset(get = "pub")]
+    #[serde(default)]
+    rules: Vec<PassThroughRuleConfig>,
+}
+
+#[derive(Clone, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct PassThroughRuleConfig {
+    name: String,
+    pattern: String,
+    /// Source languages the rule applies to. Applies to all languages when empty.
+    #[serde(default)]
+    languages: Vec<String>,
+}
+
+fn default_builtin_rules() -> bool {
+    true
+}
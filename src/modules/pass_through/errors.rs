@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+pub type PassThroughResult<T> = Result<T, PassThroughErrors>;
+
+#[derive(Debug, Error)]
+pub enum PassThroughErrors {
+    #[error("Invalid pass-through rule: {0}")]
+    InvalidRule(String),
+}
+
+impl From<regex::Error> for PassThroughErrors {
+    fn from(err: regex::Error) -> Self {
+        PassThroughErrors::InvalidRule(err.to_string())
+    }
+}
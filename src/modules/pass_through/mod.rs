@@ -0,0 +1,156 @@
+pub mod config;
+pub mod errors;
+pub mod models;
+
+use crate::modules::pass_through::config::PassThroughConfig;
+use crate::modules::pass_through::errors::PassThroughResult;
+use crate::modules::pass_through::models::PassThroughRule;
+
+const PASS_THROUGH_METRIC: &str = "translator_pass_through_total";
+
+/// Built-in rules in matching order: `(name, pattern, source languages)`.
+/// Every pattern must match the whole segment.
+const BUILTIN_RULES: [(&str, &str, &[&str]); 10] = [
+    ("number", r"^\s*[+-]?\d+(?:[.,]\d+)*\s*$", &[]),
+    ("numbered_item", r"^\s*\d+(?:\.\d+)*\.\s*$", &[]),
+    ("roman_numeral", r"^\s*[IVXLCDM]+\.?\s*$", &[]),
+    ("list_letter", r"^\s*[a-zA-Z][.)]\s*$", &[]),
+    (
+        "list_letter_cyrillic",
+        r"^\s*[а-яА-ЯёЁіІїЇєЄґҐ][.)]\s*$",
+        &["ru", "uk"],
+    ),
+    ("fraction", r"^\s*\d+/\d+\s*$", &[]),
+    (
+        "parenthesized_marker",
+        r"^\s*\((?:\d+|[a-zA-Z]|[IVXLCDM]+)\)\s*$",
+        &[],
+    ),
+    (
+        "nato_stock_number",
+        r"^\s*\d{4}-?\d{2}-?\d{3}-?\d{4}\s*$",
+        &[],
+    ),
+    (
+        "mgrs_grid_reference",
+        r"^\s*\d{1,2}[C-HJ-NP-X]\s?[A-HJ-NP-Z]{2}(?:\s?\d{2,10}|\s?\d{1,5}\s\d{1,5})?\s*$",
+        &[],
+    ),
+    (
+        "part_number",
+        r"^\s*(?:[A-Z]*\d[A-Z0-9]*(?:[-/.][A-Z0-9]+)+|[A-Z]+(?:[-/.][A-Z0-9]+)*[-/.][A-Z]*\d[A-Z0-9]*)\s*$",
+        &[],
+    ),
+];
+
+/// Shared rule set for segments that are returned to the client as is,
+/// without calling the LLM backend.
+pub struct PassThroughEngine {
+    rules: Vec<PassThroughRule>,
+}
+
+impl PassThroughEngine {
+    pub fn new(config: &PassThroughConfig) -> PassThroughResult<Self> {
+        let mut rules = Vec::new();
+        if config.builtin_rules() {
+            for (name, pattern, languages) in BUILTIN_RULES {
+                let languages = languages.iter().map(|lang| lang.to_string()).collect();
+                rules.push(PassThroughRule::new(name.to_owned(), pattern, languages)?);
+            }
+        }
+        for rule in config.rules() {
+            rules.push(PassThroughRule::new(
+                rule.name().to_owned(),
+                rule.pattern(),
+                rule.languages().to_owned(),
+            )?);
+        }
+
+        tracing::info!(
+            rules = format!(
+                "{:?}",
+                rules.iter().map(|rule| rule.name()).collect::<Vec<_>>()
+            ),
+            "Loaded pass-through rules"
+        );
+        Ok(PassThroughEngine { rules })
+    }
+
+    pub fn rules(&self) -> &[PassThroughRule] {
+        &self.rules
+    }
+
+    /// Returns the name of the first rule matching the segment. Profile rules
+    /// are checked before the shared ones. Every hit is counted per rule.
+    pub fn matching_rule<'a>(
+        &'a self,
+        text: &str,
+        source_language: &str,
+        profile_rules: &'a [PassThroughRule],
+    ) -> Option<&'a str> {
+        let rule = profile_rules
+            .iter()
+            .chain(self.rules.iter())
+            .find(|rule| rule.applies_to(source_language) && rule.is_match(text))?;
+
+        metrics::counter!(PASS_THROUGH_METRIC, "rule" => rule.name().to_owned()).increment(1);
+        Some(rule.name())
+    }
+}
+
+#[cfg(test)]
+mod test_pass_through {
+    use crate::config::ServiceConfig;
+    use crate::modules::pass_through::PassThroughEngine;
+    use crate::modules::pass_through::models::PassThroughRule;
+
+    #[test]
+    fn test_builtin_rules() -> Result<(), anyhow::Error> {
+        let service_config = ServiceConfig::new()?;
+        let engine = PassThroughEngine::new(service_config.pass_through())?;
+
+        let cases = [
+            ("42", Some("number")),
+            ("3,5", Some("number")),
+            ("1.2.", Some("numbered_item")),
+            ("IV.", Some("roman_numeral")),
+            ("b)", Some("list_letter")),
+            ("(a)", Some("parenthesized_marker")),
+            ("3/4", Some("fraction")),
+            ("5820-01-123-4567", Some("nato_stock_number")),
+            ("38SMB 4484 0815", Some("mgrs_grid_reference")),
+            ("AN/PRC-117G", Some("part_number")),
+            ("M4A1-100", Some("part_number")),
+            ("This is my rifle.", None),
+            ("UN-US", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(engine.matching_rule(text, "en", &[]), expected, "{text}");
+        }
+
+        assert_eq!(engine.matching_rule("б)", "en", &[]), None);
+        assert_eq!(
+            engine.matching_rule("б)", "ru", &[]),
+            Some("list_letter_cyrillic")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_rules_first() -> Result<(), anyhow::Error> {
+        let service_config = ServiceConfig::new()?;
+        let engine = PassThroughEngine::new(service_config.pass_through())?;
+        let profile_rules = vec![PassThroughRule::new(
+            "dosage".to_owned(),
+            r"^\s*\d+\s*mg\s*$",
+            Vec::new(),
+        )?];
+
+        assert_eq!(engine.matching_rule("5 mg", "en", &[]), None);
+        assert_eq!(
+            engine.matching_rule("5 mg", "en", &profile_rules),
+            Some("dosage")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use getset::Getters;
+use regex::Regex;
+
+/// Precompiled rule for segments that are returned without translation.
+#[derive(Clone, Debug, Getters)]
+pub struct PassThroughRule {
+    #[getset(get = "pub")]
+    name: String,
+    regex: Regex,
+    #[getset(get = "pub")]
+    languages: Vec<String>,
+}
+
+impl PassThroughRule {
+    pub fn new(name: String, pattern: &str, languages: Vec<String>) -> Result<Self, regex::Error> {
+        Ok(PassThroughRule {
+            name,
+            regex: Regex::new(pattern)?,
+            languages,
+        })
+    }
+
+    pub fn applies_to(&self, source_language: &str) -> bool {
+        self.languages.is_empty() || self.languages.iter().any(|lang| lang == source_language)
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
@@ -0,0 +1,56 @@
+use thiserror::Error;
+
+use crate::modules::documents::errors::DocumentErrors;
+use crate::modules::formats::errors::FormatErrors;
+use crate::modules::llm_client::errors::TranslatorErrors;
+use crate::modules::pipeline::models::TranslationBuilderError;
+use crate::modules::profiles::errors::ProfileErrors;
+use crate::modules::tables::errors::TableErrors;
+use crate::modules::templates::errors::TemplateErrors;
+use crate::modules::templates::models::PromptVariablesBuilderError;
+
+pub type PipelineResult<T> = Result<T, PipelineErrors>;
+
+#[derive(Debug, Error)]
+pub enum PipelineErrors {
+    #[error(transparent)]
+    Translator(#[from] TranslatorErrors),
+    #[error(transparent)]
+    Profile(#[from] ProfileErrors),
+    #[error(transparent)]
+    Template(#[from] TemplateErrors),
+    #[error(transparent)]
+    Format(#[from] FormatErrors),
+    #[error(transparent)]
+    Document(#[from] DocumentErrors),
+    #[error(transparent)]
+    Table(#[from] TableErrors),
+    #[error("Pipeline error: {0}")]
+    AnotherError(String),
+}
+
+impl PipelineErrors {
+    /// True when the model answered but its output was refused or rejected,
+    /// so other texts of the same request may still be translated. Errors of
+    /// the provider, even those it reports as invalid responses, are not.
+    pub fn is_rejected_output(&self) -> bool {
+        matches!(
+            self,
+            PipelineErrors::Translator(
+                TranslatorErrors::Refusal(_) | TranslatorErrors::RejectedOutput(_)
+            ) | PipelineErrors::Format(_)
+        )
+    }
+}
+
+impl From<TranslationBuilderError> for PipelineErrors {
+    fn from(err: TranslationBuilderError) -> Self {
+        PipelineErrors::AnotherError(err.to_string())
+    }
+}
+
+impl From<PromptVariablesBuilderError> for PipelineErrors {
+    fn from(err: PromptVariablesBuilderError) -> Self {
+        PipelineErrors::AnotherError(err.to_string())
+    }
+}
@@ -0,0 +1,1060 @@
+pub mod errors;
+pub mod models;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Weak};
+
+use derive_builder::Builder;
+use futures::{StreamExt, TryStreamExt, stream};
+use isolang::Language;
+use tokio::sync::mpsc;
+
+use crate::modules::back_translation::models::{BackTranslation, MonitorSample};
+use crate::modules::back_translation::{self, BACK_TRANSLATION_METRIC, MonitorSampler};
+use crate::modules::confidence::ConfidenceScorer;
+use crate::modules::consistency::models::ConsistencyAction;
+use crate::modules::consistency::{self, ConsistencyChecker};
+use crate::modules::context::SessionStore;
+use crate::modules::context::models::TranslatedSegment;
+use crate::modules::documents::models::{DocumentOptions, TranslatedDocument};
+use crate::modules::documents::{self, DocumentRegistry};
+use crate::modules::formality::{self, FormalityChecker};
+use crate::modules::formats;
+use crate::modules::formats::models::{Escape, Segment};
+use crate::modules::glossary;
+use crate::modules::glossary::GlossaryStore;
+use crate::modules::glossary::models::{GlossaryEnforcement, GlossaryViolation};
+use crate::modules::llm_client::LLMClient;
+use crate::modules::llm_client::errors::TranslatorErrors;
+use crate::modules::llm_client::models::{TokenUsage, TranslatePrompt, TranslateTask};
+use crate::modules::masking::{self, Masker};
+use crate::modules::pass_through::PassThroughEngine;
+use crate::modules::pipeline::errors::PipelineResult;
+use crate::modules::pipeline::models::{Translation, TranslationBuilder};
+use crate::modules::profiles::ProfileRegistry;
+use crate::modules::profiles::models::Profile;
+use crate::modules::sanitizer::OutputSanitizer;
+use crate::modules::tables::config::TablesConfig;
+use crate::modules::tables::models::CellTranslation;
+use crate::modules::tables::{ColumnMapping, TableReader, TableWriter};
+use crate::modules::templates::TemplateStore;
+use crate::modules::templates::models::PromptVariablesBuilder;
+use crate::modules::validation::models::ValidationIssueKind;
+use crate::modules::validation::{self, OutputValidator};
+
+/// Runs a translate task through every stage around the LLM backend:
+/// profile selection, masking, prompt rendering and output checks.
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct TranslationPipeline<R>
+where
+    R: LLMClient + ?Sized + Send + Sync,
+{
+    llm_client: Arc<R>,
+    /// Provider for retries after rejected output. The main one is used when not set.
+    #[builder(default)]
+    fallback_client: Option<Arc<R>>,
+    profiles: Arc<ProfileRegistry>,
+    templates: Arc<TemplateStore>,
+    glossary: Arc<GlossaryStore>,
+    masker: Arc<Masker>,
+    pass_through: Arc<PassThroughEngine>,
+    sanitizer: Arc<OutputSanitizer>,
+    validator: Arc<OutputValidator>,
+    consistency: Arc<ConsistencyChecker>,
+    formality: Arc<FormalityChecker>,
+    sessions: Arc<SessionStore>,
+    confidence: Arc<ConfidenceScorer>,
+    documents: Arc<DocumentRegistry>,
+    /// Samples translations for back-translation in the background when set.
+    #[builder(default)]
+    monitor: Option<Arc<MonitorSampler>>,
+}
+
+impl<R> TranslationPipeline<R>
+where
+    R: LLMClient + ?Sized + Send + Sync,
+{
+    pub fn profiles(&self) -> &ProfileRegistry {
+        &self.profiles
+    }
+
+    pub fn templates(&self) -> &TemplateStore {
+        &self.templates
+    }
+
+    pub fn glossary(&self) -> &GlossaryStore {
+        &self.glossary
+    }
+
+    pub fn documents(&self) -> &DocumentRegistry {
+        &self.documents
+    }
+
+    /// Translates an uploaded file segment by segment. Earlier segments of
+    /// the file and their translations are passed as context, after the
+    /// history of the session if there is one.
+    pub async fn translate_document(
+        &self,
+        translate_task: TranslateTask,
+        file_name: &str,
+        content_type: Option<&str>,
+        data: &[u8],
+        options: &DocumentOptions,
+    ) -> PipelineResult<TranslatedDocument> {
+        let document = self.documents.find(file_name, content_type)?;
+        let segments = document.extract(data, options)?;
+        tracing::info!(
+            file_name = file_name,
+            format = document.name(),
+            segments = segments.len(),
+            "Translating document"
+        );
+
+        // The session's history is read once and goes before the document's
+        // own segments; segments are not recorded in the session.
+        let history = match translate_task.session_id() {
+            Some(session_id) => {
+                self.sessions
+                    .history(
+                        session_id,
+                        translate_task.source_language(),
+                        translate_task.target_language(),
+                    )
+                    .await
+            }
+            None => Vec::new(),
+        };
+        let mut translate_task = translate_task;
+        translate_task.set_session_id(None);
+
+        let max_context = self.sessions.config().max_segments();
+        let mut translations = Vec::with_capacity(segments.len());
+        let mut translated = Vec::new();
+        let mut warnings = Vec::new();
+        for (index, segment) in segments.into_iter().enumerate() {
+            let mut task = translate_task.clone();
+            let mut context = task.context().clone().unwrap_or_default();
+            let recent = translated.len().saturating_sub(max_context);
+            context.prepend_translations(translated[recent..].to_vec());
+            context.prepend_translations(history.clone());
+            task.set_text(segment.text().to_owned());
+            task.set_format(segment.format());
+            task.set_context(Some(context));
+            // A rejected segment keeps its source text; the rest of the
+            // document is still translated.
+            let translation = match self.translate(task).await {
+                Ok(translation) => translation.text().to_owned(),
+                Err(err) if err.is_rejected_output() => {
+                    warnings.push(format!(
+                        "Segment {}: source text kept, translation rejected: {err}",
+                        index + 1
+                    ));
+                    translations.push(segment.text().to_owned());
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            if !segment.text().trim().is_empty() {
+                translated.push(TranslatedSegment::new(
+                    segment.text().trim().to_owned(),
+                    translation.trim().to_owned(),
+                ));
+            }
+            translations.push(translation);
+        }
+
+        warnings.extend(document.warnings(data, options, &translations)?);
+        for warning in &warnings {
+            tracing::warn!(file_name = file_name, "{warning}");
+        }
+        let data = document.rebuild(data, options, &translations)?;
+        Ok(TranslatedDocument::new(
+            file_name.to_owned(),
+            documents::content_type(document, content_type),
+            data,
+        )
+        .with_warnings(warnings))
+    }
+
+    /// Translates the mapped columns of a table batch by batch and sends each
+    /// batch of rows with its translation columns to `output`, the header
+    /// first. Equal cells are translated once, several at a time, and their
+    /// translations are kept for later batches. Cells whose translation is
+    /// rejected keep their source text and are named in the last column.
+    pub async fn translate_table(
+        &self,
+        translate_task: TranslateTask,
+        mut reader: TableReader,
+        mapping: ColumnMapping,
+        delimiter: u8,
+        config: TablesConfig,
+        output: &mpsc::Sender<PipelineResult<Vec<u8>>>,
+    ) -> PipelineResult<()> {
+        let mut writer = TableWriter::new(mapping.header().clone(), delimiter);
+        let mut memo: HashMap<String, CellTranslation> = HashMap::new();
+        let (mut rows, mut requests, mut rejected) = (0, 0, 0);
+        while let Some(batch) = reader.next_batch().await? {
+            let pending: HashSet<String> = batch
+                .iter()
+                .flat_map(|record| mapping.texts(record))
+                .filter(|text| !memo.contains_key(*text))
+                .map(str::to_owned)
+                .collect();
+            requests += pending.len();
+            let translations: HashMap<String, CellTranslation> = stream::iter(pending)
+                .map(|text| {
+                    let mut task = translate_task.clone();
+                    task.set_text(text.clone());
+                    async move {
+                        // A rejected cell keeps its source text and is named in
+                        // the untranslated column; provider errors end the table.
+                        let translation = match self.translate(task).await {
+                            Ok(translation) => {
+                                CellTranslation::Translated(translation.text().to_owned())
+                            }
+                            Err(err) if err.is_rejected_output() => {
+                                tracing::warn!("Table cell keeps its source text: {err}");
+                                CellTranslation::Rejected
+                            }
+                            Err(err) => return Err(err),
+                        };
+                        PipelineResult::Ok((text, translation))
+                    }
+                })
+                .buffer_unordered(config.max_concurrency().max(1))
+                .try_collect()
+                .await?;
+            rejected += translations
+                .values()
+                .filter(|translation| matches!(translation, CellTranslation::Rejected))
+                .count();
+
+            let translated = batch
+                .iter()
+                .map(|record| {
+                    mapping.extend(record, |text| {
+                        translations.get(text).or_else(|| memo.get(text))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            // The memo is only cleared once the batch has used it.
+            if memo.len() + translations.len() > config.cache_size() {
+                memo.clear();
+            }
+            memo.extend(translations);
+            rows += translated.len();
+            if output.send(Ok(writer.chunk(&translated)?)).await.is_err() {
+                tracing::warn!(rows = rows, "Table client went away, stopping translation");
+                return Ok(());
+            }
+        }
+        if writer.is_pending() {
+            let _ = output.send(Ok(writer.chunk(&[])?)).await;
+        }
+        tracing::info!(
+            rows = rows,
+            requests = requests,
+            rejected = rejected,
+            "Translated table"
+        );
+        Ok(())
+    }
+
+    pub async fn translate(&self, translate_task: TranslateTask) -> PipelineResult<Translation> {
+        let profile = self.profiles.get(translate_task.profile().as_deref())?;
+        let format = *translate_task.format();
+        let document = formats::segment(
+            format,
+            translate_task.text(),
+            translate_task.source_language(),
+            translate_task.target_language(),
+        )?;
+
+        let source_language = translate_task.source_language();
+        let target_language = translate_task.target_language();
+        let mut context = translate_task.context().clone().unwrap_or_default();
+        if let Some(session_id) = translate_task.session_id() {
+            let history = self
+                .sessions
+                .history(session_id, source_language, target_language)
+                .await;
+            context.prepend_translations(history);
+        }
+        let context = self.sessions.format_for_prompt(&context);
+
+        let mut parts = Vec::with_capacity(document.segments().len());
+        for segment in document.segments() {
+            parts.push(
+                self.translate_segment(&translate_task, profile, segment, context.as_deref())
+                    .await?,
+            );
+        }
+        let texts: Vec<String> = parts.iter().map(|part| part.text().to_owned()).collect();
+        let rendered = document.render(&texts)?;
+        formats::check_structure(format, translate_task.text(), &rendered)?;
+        let mut confidence = None;
+        if parts.iter().any(|part| part.confidence().is_some()) {
+            let placed = document
+                .placements(&texts)?
+                .into_iter()
+                .zip(&parts)
+                .filter_map(|(placement, part)| {
+                    let place = move |index| placement.position(index);
+                    Some((place, part.confidence().clone()?))
+                })
+                .collect();
+            confidence = self.confidence.combine(placed);
+        }
+
+        if let Some(session_id) = translate_task.session_id()
+            && !rendered.trim().is_empty()
+        {
+            let segment = TranslatedSegment::new(
+                translate_task.text().trim().to_owned(),
+                rendered.trim().to_owned(),
+            );
+            self.sessions
+                .record(session_id, source_language, target_language, segment)
+                .await;
+        }
+
+        let mut translation = Translation::combine(rendered, profile.name(), parts);
+        translation.set_confidence(confidence);
+        if translation.usage().is_none() {
+            return Ok(translation);
+        }
+        if *translate_task.back_translation() {
+            let (back_translation, usage) = self
+                .back_translate(
+                    profile,
+                    source_language,
+                    target_language,
+                    translate_task.text(),
+                    translation.text(),
+                )
+                .await?;
+            translation.set_back_translation(back_translation, usage);
+        } else if let Some(monitor) = &self.monitor {
+            monitor.offer(|| {
+                MonitorSample::new(
+                    profile.name().to_owned(),
+                    source_language.to_owned(),
+                    target_language.to_owned(),
+                    translate_task.text().to_owned(),
+                    translation.text().to_owned(),
+                )
+            });
+        }
+        Ok(translation)
+    }
+
+    /// Back-translates sampled translations until the pipeline, which owns
+    /// the sampler, is dropped, logging the ones that score below the
+    /// configured threshold. The task holds the pipeline only while it
+    /// checks a sample.
+    pub async fn run_back_translation_monitor(
+        pipeline: Weak<Self>,
+        mut receiver: mpsc::Receiver<MonitorSample>,
+    ) {
+        while let Some(sample) = receiver.recv().await {
+            let Some(pipeline) = pipeline.upgrade() else {
+                break;
+            };
+            let Ok(profile) = pipeline.profiles.get(Some(sample.profile().as_str())) else {
+                continue;
+            };
+            let result = pipeline
+                .back_translate(
+                    profile,
+                    sample.source_language(),
+                    sample.target_language(),
+                    sample.source(),
+                    sample.translation(),
+                )
+                .await;
+            let warning_score = pipeline
+                .monitor
+                .as_ref()
+                .map_or(0.0, |monitor| monitor.config().warning_score());
+            match result {
+                Ok((back_translation, _)) if back_translation.score() < warning_score => {
+                    tracing::warn!(
+                        profile = sample.profile(),
+                        source_language = sample.source_language(),
+                        target_language = sample.target_language(),
+                        score = back_translation.score(),
+                        source = sample.source(),
+                        back_translation = back_translation.text(),
+                        "Back-translation differs from the source"
+                    );
+                }
+                Ok(_) => {}
+                Err(err) => tracing::warn!(err = err.to_string(), "Back-translation failed"),
+            }
+        }
+    }
+
+    /// Translates `translation` back into the source language with the
+    /// profile prompt and scores it against `source` with chrF.
+    async fn back_translate(
+        &self,
+        profile: &Profile,
+        source_language: &str,
+        target_language: &str,
+        source: &str,
+        translation: &str,
+    ) -> PipelineResult<(BackTranslation, TokenUsage)> {
+        let masked = self.masker.mask(translation);
+        let placeholders = masking::placeholder_instruction(&masked);
+        let mut variables = PromptVariablesBuilder::default();
+        variables
+            .source_language(language_name(target_language)?)
+            .target_language(language_name(source_language)?)
+            .text(masked.text().as_str());
+        if let Some(placeholders) = &placeholders {
+            variables.placeholders(placeholders.as_str());
+        }
+        let variables = variables.build()?;
+
+        let rendered = self
+            .templates
+            .render(profile.template(), &variables)
+            .await?;
+        let prompt = TranslatePrompt::new(
+            profile.system_prompt().to_owned(),
+            rendered.prompt().to_owned(),
+            profile.sampling().to_owned(),
+        );
+        let completion = self.llm_client.translate(prompt).await?;
+        let (text, _) = self.sanitizer.sanitize(translation, completion.text());
+        let (text, _) = self.masker.unmask(&masked, &text);
+
+        let score = back_translation::chrf(source, &text);
+        metrics::histogram!(
+            BACK_TRANSLATION_METRIC,
+            "pair" => format!("{source_language}-{target_language}")
+        )
+        .record(score);
+        tracing::info!(
+            profile = profile.name(),
+            score = score,
+            prompt_tokens = completion.usage().prompt_tokens(),
+            completion_tokens = completion.usage().completion_tokens(),
+            "Text back-translated"
+        );
+        Ok((BackTranslation::new(text, score), completion.usage()))
+    }
+
+    async fn translate_segment(
+        &self,
+        translate_task: &TranslateTask,
+        profile: &Profile,
+        segment: &Segment,
+        context: Option<&str>,
+    ) -> PipelineResult<Translation> {
+        let profile_name = profile.name().to_owned();
+
+        let source_text = segment.source();
+        let text = source_text.trim();
+        if text.is_empty() {
+            tracing::debug!("Text is empty string. Returning.");
+            return untranslated(source_text, &profile_name);
+        }
+
+        if let Some(rule) = self.pass_through.matching_rule(
+            segment.plain(),
+            translate_task.source_language(),
+            profile.pass_through_rules(),
+        ) {
+            tracing::debug!(
+                profile = profile_name,
+                rule = rule,
+                "String matches pass-through rule. Returning."
+            );
+            return untranslated(source_text, &profile_name);
+        }
+
+        // The number written for `#` of a plural branch is turned back into
+        // `#` after translation, so it must stay visible.
+        let masked = match segment.escape() {
+            Escape::Message { count: Some(_), .. } => self
+                .masker
+                .mask_segment_keeping_numbers(segment.masked().clone()),
+            _ => self.masker.mask_segment(segment.masked().clone()),
+        };
+        if masked.is_fully_masked() {
+            tracing::debug!("Text consists of protected spans only. Returning.");
+            return untranslated(source_text, &profile_name);
+        }
+        let placeholders = masking::placeholder_instruction(&masked);
+
+        let glossary_entries = self
+            .glossary
+            .matching_entries(
+                translate_task.source_language(),
+                translate_task.target_language(),
+                &profile_name,
+                text,
+            )
+            .await;
+        let glossary_config = self.glossary.config();
+        let mut glossary = glossary::format_for_prompt(&glossary_entries);
+        let source_language = translate_task.source_language();
+        let target_language = translate_task.target_language();
+        let mut attempt = 0;
+        let mut validation_attempt = 0;
+        let mut consistency_attempt = 0;
+        let consistency_config = self.consistency.config();
+        let formality = *translate_task.formality();
+        let formality_instruction = formality::instruction(target_language, formality);
+        let mut formality_attempt = 0;
+        let mut strict_instruction: Option<String> = None;
+        let mut validation_warnings = Vec::new();
+        let mut usage = TokenUsage::default();
+
+        loop {
+            let mut variables = PromptVariablesBuilder::default();
+            variables
+                .source_language(language_name(source_language)?)
+                .target_language(language_name(target_language)?)
+                .text(masked.text().as_str());
+            if let Some(glossary) = &glossary {
+                variables.glossary(glossary.as_str());
+            }
+            if let Some(placeholders) = &placeholders {
+                variables.placeholders(placeholders.as_str());
+            }
+            if let Some(context) = context {
+                variables.context(context);
+            }
+            if let Some(instruction) = formality_instruction {
+                variables.formality(instruction);
+            }
+            let variables = variables.build()?;
+
+            let rendered = self
+                .templates
+                .render(profile.template(), &variables)
+                .await?;
+            tracing::debug!(
+                profile = profile_name,
+                template_version = rendered.version_tag(),
+                user_prompt = rendered.prompt(),
+                "Built user prompt"
+            );
+
+            let mut system_prompt = profile.system_prompt().to_owned();
+            if let Some(instruction) = &strict_instruction {
+                system_prompt = format!("{}\n{instruction}", system_prompt.trim_end());
+            }
+            let prompt = TranslatePrompt::new(
+                system_prompt,
+                rendered.prompt().to_owned(),
+                profile.sampling().to_owned(),
+            )
+            .with_logprobs(*translate_task.confidence());
+            let llm_client = match &self.fallback_client {
+                Some(fallback_client) if validation_attempt > 0 => fallback_client,
+                _ => &self.llm_client,
+            };
+            let completion = llm_client.translate(prompt).await?;
+            usage += completion.usage();
+            let (translated_text, sanitizations) =
+                self.sanitizer.sanitize(source_text, completion.text());
+            for fix in &sanitizations {
+                tracing::info!(
+                    profile = profile_name,
+                    template_version = rendered.version_tag(),
+                    step = fix.step().to_string(),
+                    rule = fix.rule(),
+                    removed = fix.removed(),
+                    "Sanitized model output"
+                );
+            }
+
+            let issues = self.validator.validate(
+                source_language,
+                target_language,
+                masked.text(),
+                &translated_text,
+            );
+            let details: Vec<String> = issues.iter().map(ToString::to_string).collect();
+            let exhausted = validation_attempt >= self.validator.config().max_retries();
+            if exhausted
+                && !issues.is_empty()
+                && issues
+                    .iter()
+                    .all(|issue| issue.kind() == ValidationIssueKind::Unchanged)
+            {
+                tracing::warn!(
+                    attempts = validation_attempt + 1,
+                    issues = format!("{:?}", details),
+                    "Output stays close to the source, accepting it"
+                );
+                validation_warnings = details;
+            } else if !issues.is_empty() {
+                if exhausted {
+                    return Err(TranslatorErrors::RejectedOutput(format!(
+                        "output rejected after {} attempt(s): {}",
+                        validation_attempt + 1,
+                        details.join("; ")
+                    ))
+                    .into());
+                }
+                validation_attempt += 1;
+                tracing::warn!(
+                    attempt = validation_attempt,
+                    issues = format!("{:?}", details),
+                    fallback = self.fallback_client.is_some(),
+                    "Model output rejected, retrying translation"
+                );
+                strict_instruction = Some(validation::strict_instruction(
+                    language_name(target_language)?,
+                    &issues,
+                ));
+                continue;
+            } else {
+                validation_warnings.clear();
+            }
+
+            let translated_text = segment.escape().apply(&translated_text);
+            let (translated_text, placeholder_issues) =
+                self.masker.unmask(&masked, &translated_text);
+            let violations = glossary::find_violations(&glossary_entries, &translated_text);
+            let warnings =
+                self.consistency
+                    .check(source_language, target_language, text, &translated_text);
+            if !warnings.is_empty() && consistency_config.action() == ConsistencyAction::Reject {
+                let details: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+                return Err(TranslatorErrors::RejectedOutput(format!(
+                    "values changed in translation: {}",
+                    details.join("; ")
+                ))
+                .into());
+            }
+
+            let glossary_retry = !violations.is_empty()
+                && glossary_config.enforcement() == GlossaryEnforcement::Retry
+                && attempt < glossary_config.max_retries();
+            let consistency_retry = !warnings.is_empty()
+                && consistency_config.action() == ConsistencyAction::Retry
+                && consistency_attempt < consistency_config.max_retries();
+            let formality_issue =
+                self.formality
+                    .check(target_language, formality, &translated_text);
+            let formality_retry = formality_issue.is_some()
+                && formality_attempt < self.formality.config().max_retries();
+            if glossary_retry {
+                attempt += 1;
+                tracing::warn!(
+                    attempt = attempt,
+                    violations = format!("{:?}", violations),
+                    "Glossary terms violated, retrying translation"
+                );
+                glossary = glossary.map(|glossary| emphasize_violations(&glossary, &violations));
+            }
+            if consistency_retry {
+                consistency_attempt += 1;
+                tracing::warn!(
+                    attempt = consistency_attempt,
+                    warnings = format!("{:?}", warnings),
+                    "Values changed in translation, retrying translation"
+                );
+                strict_instruction = Some(consistency::retry_instruction(&warnings));
+            }
+            if formality_retry
+                && let Some(issue) = &formality_issue
+                && let Some(instruction) = formality_instruction
+            {
+                formality_attempt += 1;
+                tracing::warn!(
+                    attempt = formality_attempt,
+                    issue = issue.to_string(),
+                    "Wrong register in translation, retrying translation"
+                );
+                let retry = formality::retry_instruction(issue, instruction);
+                strict_instruction = Some(match strict_instruction.filter(|_| consistency_retry) {
+                    Some(previous) => format!("{previous}\n{retry}"),
+                    None => retry,
+                });
+            }
+            if glossary_retry || consistency_retry || formality_retry {
+                continue;
+            }
+
+            tracing::info!(
+                profile = profile_name,
+                template_version = rendered.version_tag(),
+                glossary_terms = glossary_entries.len(),
+                glossary_violations = violations.len(),
+                placeholder_issues = placeholder_issues.len(),
+                consistency_warnings = warnings.len(),
+                formality_issue = formality_issue.is_some(),
+                prompt_tokens = usage.prompt_tokens(),
+                completion_tokens = usage.completion_tokens(),
+                "Text translated"
+            );
+            let mut confidence = None;
+            if *translate_task.confidence() {
+                confidence = self
+                    .confidence
+                    .score(&translated_text, completion.logprobs());
+                if confidence.is_none() {
+                    tracing::warn!(
+                        profile = profile_name,
+                        "Backend returned no log probabilities for the output"
+                    );
+                }
+            }
+            let translation = TranslationBuilder::default()
+                .text(translated_text)
+                .profile(profile_name)
+                .template_version(Some(rendered.version_tag().to_owned()))
+                .glossary_violations(violations)
+                .placeholder_issues(placeholder_issues)
+                .consistency_warnings(warnings)
+                .formality_issues(formality_issue.into_iter().collect::<Vec<_>>())
+                .validation_warnings(validation_warnings)
+                .confidence(confidence)
+                .usage(Some(usage))
+                .build()?;
+            return Ok(translation);
+        }
+    }
+}
+
+fn untranslated(text: &str, profile_name: &str) -> PipelineResult<Translation> {
+    let translation = TranslationBuilder::default()
+        .text(text)
+        .profile(profile_name)
+        .build()?;
+    Ok(translation)
+}
+
+fn emphasize_violations(glossary: &str, violations: &[GlossaryViolation]) -> String {
+    let missed: Vec<String> = violations
+        .iter()
+        .map(|violation| format!("{} -> {}", violation.source_term(), violation.target_term()))
+        .collect();
+    format!(
+        "{glossary}\nThese terms are mandatory and were missing in the previous attempt: {}",
+        missed.join("; ")
+    )
+}
+
+fn language_name(iso: &str) -> PipelineResult<&'static str> {
+    Language::from_639_1(iso)
+        .map(|language| language.to_name())
+        .ok_or_else(|| TranslatorErrors::BadRequest(format!("Unknown language code: {iso}")).into())
+}
+
+#[cfg(test)]
+mod test_pipeline {
+    use std::sync::Arc;
+
+    use tokio::sync::mpsc;
+
+    use crate::config::ServiceConfig;
+    use crate::modules::back_translation::MonitorSampler;
+    use crate::modules::back_translation::config::BackTranslationConfig;
+    use crate::modules::confidence::ConfidenceScorer;
+    use crate::modules::consistency::ConsistencyChecker;
+    use crate::modules::context::SessionStore;
+    use crate::modules::context::models::TranslatedSegment;
+    use crate::modules::documents::DocumentRegistry;
+    use crate::modules::documents::models::DocumentOptions;
+    use crate::modules::formality::FormalityChecker;
+    use crate::modules::formats::models::TextFormat;
+    use crate::modules::glossary::GlossaryStore;
+    use crate::modules::llm_client::LLMClient;
+    use crate::modules::llm_client::errors::{TranslatorErrors, TranslatorResult};
+    use crate::modules::llm_client::models::{
+        Completion, TokenUsage, TranslatePrompt, TranslateTask,
+    };
+    use crate::modules::masking::Masker;
+    use crate::modules::pass_through::PassThroughEngine;
+    use crate::modules::pipeline::errors::{PipelineErrors, PipelineResult};
+    use crate::modules::pipeline::{TranslationPipeline, TranslationPipelineBuilder};
+    use crate::modules::profiles::ProfileRegistry;
+    use crate::modules::sanitizer::OutputSanitizer;
+    use crate::modules::tables::config::TablesConfig;
+    use crate::modules::tables::models::TableOptions;
+    use crate::modules::tables::{ColumnMapping, TableReader};
+    use crate::modules::templates::TemplateStore;
+    use crate::modules::validation::OutputValidator;
+
+    type Reply = fn(&str) -> TranslatorResult<String>;
+
+    /// Backend answering every prompt with `reply` of the user prompt.
+    struct ScriptedClient(Reply);
+
+    #[async_trait::async_trait]
+    impl LLMClient for ScriptedClient {
+        async fn translate(&self, prompt: TranslatePrompt) -> TranslatorResult<Completion> {
+            let text = (self.0)(prompt.user_prompt())?;
+            Ok(Completion::new(text, TokenUsage::new(1, 1, 2), 0))
+        }
+    }
+
+    fn scripted_pipeline(reply: Reply) -> TranslationPipeline<ScriptedClient> {
+        scripted_builder(reply).build().unwrap()
+    }
+
+    fn scripted_builder(reply: Reply) -> TranslationPipelineBuilder<ScriptedClient> {
+        let config = ServiceConfig::new().unwrap();
+        TranslationPipelineBuilder::default()
+            .llm_client(Arc::new(ScriptedClient(reply)))
+            .profiles(Arc::new(ProfileRegistry::load(config.profiles()).unwrap()))
+            .templates(Arc::new(TemplateStore::load(config.templates()).unwrap()))
+            .glossary(Arc::new(GlossaryStore::load(config.glossary()).unwrap()))
+            .masker(Arc::new(Masker::new(config.masking()).unwrap()))
+            .pass_through(Arc::new(
+                PassThroughEngine::new(config.pass_through()).unwrap(),
+            ))
+            .sanitizer(Arc::new(OutputSanitizer::new(config.sanitizer()).unwrap()))
+            .validator(Arc::new(OutputValidator::new(config.validation()).unwrap()))
+            .consistency(Arc::new(
+                ConsistencyChecker::new(config.consistency()).unwrap(),
+            ))
+            .formality(Arc::new(FormalityChecker::new(config.formality()).unwrap()))
+            .sessions(Arc::new(SessionStore::new(config.context())))
+            .confidence(Arc::new(ConfidenceScorer::new(config.confidence())))
+            .documents(Arc::new(DocumentRegistry::new(config.documents())))
+    }
+
+    fn task(text: &str) -> TranslateTask {
+        let mut task = TranslateTask::default();
+        task.set_source_language("en".to_string());
+        task.set_target_language("ru".to_string());
+        task.set_text(text.to_string());
+        task
+    }
+
+    const NAME: &str = "Kalashnikov Concern Izhmash Limited";
+
+    #[tokio::test]
+    async fn test_monitor_stops_with_pipeline() {
+        let config: BackTranslationConfig = serde_json::from_value(serde_json::json!({
+            "monitor_every": 1,
+            "monitor_queue": 4,
+            "warning_score": 50.0,
+        }))
+        .unwrap();
+        let (sampler, receiver) = MonitorSampler::new(&config).unwrap();
+        let pipeline = Arc::new(
+            scripted_builder(reply)
+                .monitor(Some(Arc::new(sampler)))
+                .build()
+                .unwrap(),
+        );
+        let monitor = tokio::spawn(TranslationPipeline::run_back_translation_monitor(
+            Arc::downgrade(&pipeline),
+            receiver,
+        ));
+        pipeline.translate(task("Hello, world")).await.unwrap();
+
+        drop(pipeline);
+        tokio::time::timeout(std::time::Duration::from_secs(5), monitor)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_output_is_accepted_with_warning() {
+        let pipeline = scripted_pipeline(|_| Ok(NAME.to_string()));
+        let translation = pipeline.translate(task(NAME)).await.unwrap();
+        assert_eq!(translation.text(), NAME);
+        assert_eq!(translation.validation_warnings().len(), 1);
+
+        let pipeline = scripted_pipeline(|_| {
+            Ok("Mon fusil est mon meilleur ami. C'est ma vie, je dois le maîtriser.".to_string())
+        });
+        let result = pipeline
+            .translate(task(
+                "My rifle is my best friend. It is my life, I must master it.",
+            ))
+            .await;
+        assert!(matches!(
+            result,
+            Err(PipelineErrors::Translator(
+                TranslatorErrors::RejectedOutput(_)
+            ))
+        ));
+    }
+
+    /// Refuses "Refuse", fails like the provider for "Outage" and "Upstream"
+    /// and translates the rest.
+    fn reply(prompt: &str) -> TranslatorResult<String> {
+        if prompt.contains("Refuse") {
+            Err(TranslatorErrors::Refusal("I cannot help".to_string()))
+        } else if prompt.contains("Outage") {
+            Err(TranslatorErrors::ServiceUnavailable("down".to_string()))
+        } else if prompt.contains("Upstream") {
+            Err(TranslatorErrors::InvalidResponse(
+                "provider returned 502".to_string(),
+            ))
+        } else {
+            Ok("Привет, мир".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_document_keeps_source_of_rejected_segments() {
+        let pipeline = scripted_pipeline(reply);
+        let data = br#"{"greeting": "Hello, world", "refused": "Refuse this line"}"#;
+        let document = pipeline
+            .translate_document(task(""), "en.json", None, data, &DocumentOptions::default())
+            .await
+            .unwrap();
+        let output = String::from_utf8(document.data().clone()).unwrap();
+        assert!(output.contains("\"Привет, мир\""));
+        assert!(output.contains("\"Refuse this line\""));
+        assert_eq!(document.warnings().len(), 1);
+        assert!(document.warnings()[0].starts_with("Segment 2:"));
+
+        let data = br#"{"greeting": "Hello, world", "failed": "Outage ahead"}"#;
+        let result = pipeline
+            .translate_document(task(""), "en.json", None, data, &DocumentOptions::default())
+            .await;
+        assert!(matches!(
+            result,
+            Err(PipelineErrors::Translator(
+                TranslatorErrors::ServiceUnavailable(_)
+            ))
+        ));
+
+        let data = br#"{"greeting": "Hello, world", "failed": "Upstream error"}"#;
+        let result = pipeline
+            .translate_document(task(""), "en.json", None, data, &DocumentOptions::default())
+            .await;
+        assert!(matches!(
+            result,
+            Err(PipelineErrors::Translator(
+                TranslatorErrors::InvalidResponse(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_plural_number_with_unit_becomes_pound() {
+        let pipeline = scripted_pipeline(|prompt| {
+            let line = prompt
+                .lines()
+                .rev()
+                .find(|line| line.contains("left to go"));
+            Ok(line
+                .unwrap_or_default()
+                .trim()
+                .replace("left to go", "осталось пройти"))
+        });
+        let mut task = task("{count, plural, one {# km left to go} other {# km left to go}}");
+        task.set_format(TextFormat::Icu);
+        let translation = pipeline.translate(task).await.unwrap();
+        assert_eq!(
+            translation.text(),
+            "{count, plural, one {# km осталось пройти} few {# km осталось пройти} \
+             many {# km осталось пройти} other {# km осталось пройти}}"
+        );
+    }
+
+    /// Translates the `text` column of a table in batches of two rows.
+    async fn translate_table(
+        pipeline: &TranslationPipeline<ScriptedClient>,
+        table: &str,
+        config: TablesConfig,
+    ) -> PipelineResult<String> {
+        let (upload, input) = mpsc::channel(1);
+        upload.send(Ok(table.as_bytes().to_vec())).await.unwrap();
+        drop(upload);
+        let reader = TableReader::start(input, b',', 2).await?;
+        let options = TableOptions::new(vec!["text".to_string()], b',');
+        let mapping = ColumnMapping::new(reader.header(), &options, "ru")?;
+
+        let (sender, mut chunks) = mpsc::channel(8);
+        pipeline
+            .translate_table(task(""), reader, mapping, b',', config, &sender)
+            .await?;
+        drop(sender);
+        let mut output = String::new();
+        while let Some(chunk) = chunks.recv().await {
+            output.push_str(&String::from_utf8(chunk?).unwrap());
+        }
+        Ok(output)
+    }
+
+    #[tokio::test]
+    async fn test_table_keeps_source_of_rejected_cells() {
+        let pipeline = scripted_pipeline(reply);
+        let config = ServiceConfig::new().unwrap().tables().clone();
+        let table = "id,text\n1,Hello\n2,Refuse this\n3,Hello again\n";
+        let output = translate_table(&pipeline, table, config.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            output,
+            "id,text,text_ru,untranslated\n\
+             1,Hello,\"Привет, мир\",\n\
+             2,Refuse this,Refuse this,text_ru\n\
+             3,Hello again,\"Привет, мир\",\n"
+        );
+
+        for failing in ["Outage ahead", "Upstream error"] {
+            let table = format!("id,text\n1,Hello\n2,{failing}\n");
+            let result = translate_table(&pipeline, &table, config.clone()).await;
+            assert!(matches!(
+                result,
+                Err(PipelineErrors::Translator(
+                    TranslatorErrors::ServiceUnavailable(_) | TranslatorErrors::InvalidResponse(_)
+                ))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_table_memo_smaller_than_table() {
+        let pipeline = scripted_pipeline(reply);
+        let config: TablesConfig = serde_json::from_value(serde_json::json!({
+            "batch_rows": 2,
+            "max_concurrency": 2,
+            "cache_size": 2,
+        }))
+        .unwrap();
+        let table = "id,text\n1,Hello\n2,Hi there\n3,Hello\n4,Good day\n5,Hi there\n6,Hello\n";
+        let output = translate_table(&pipeline, table, config).await.unwrap();
+        let translated: Vec<&str> = output.lines().skip(1).collect();
+        assert_eq!(translated.len(), 6);
+        assert!(
+            translated
+                .iter()
+                .all(|row| row.ends_with(",\"Привет, мир\","))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_document_reads_session_once() {
+        // Fails the segment when earlier text reaches the prompt twice.
+        let pipeline = scripted_pipeline(|prompt| {
+            if prompt.matches("Hello, world").count() > 1 {
+                Err(TranslatorErrors::RejectedOutput(
+                    "repeated context".to_string(),
+                ))
+            } else {
+                Ok("Привет, мир".to_string())
+            }
+        });
+        let earlier = TranslatedSegment::new("Good morning".to_string(), "Доброе утро".to_string());
+        pipeline.sessions.record("chat", "en", "ru", earlier).await;
+
+        let mut task = task("");
+        task.set_session_id(Some("chat".to_string()));
+        let data = br#"{"a": "Hello, world", "b": "Good day", "c": "See you soon"}"#;
+        let document = pipeline
+            .translate_document(task, "en.json", None, data, &DocumentOptions::default())
+            .await
+            .unwrap();
+        assert!(document.warnings().is_empty());
+        assert_eq!(pipeline.sessions.history("chat", "en", "ru").await.len(), 1);
+    }
+}
@@ -0,0 +1,89 @@
+use derive_builder::Builder;
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::modules::back_translation::models::BackTranslation;
+use crate::modules::confidence::models::Confidence;
+use crate::modules::consistency::models::ConsistencyWarning;
+use crate::modules::formality::models::FormalityIssue;
+use crate::modules::glossary::models::GlossaryViolation;
+use crate::modules::llm_client::models::TokenUsage;
+use crate::modules::masking::models::PlaceholderIssue;
+
+#[derive(Serialize, Deserialize, Getters, Builder, Clone, Debug)]
+#[builder(setter(into))]
+#[getset(get = "pub")]
+pub struct Translation {
+    text: String,
+    profile: String,
+    #[builder(default)]
+    template_version: Option<String>,
+    #[builder(default)]
+    glossary_violations: Vec<GlossaryViolation>,
+    #[builder(default)]
+    placeholder_issues: Vec<PlaceholderIssue>,
+    #[builder(default)]
+    consistency_warnings: Vec<ConsistencyWarning>,
+    #[builder(default)]
+    formality_issues: Vec<FormalityIssue>,
+    /// Reasons output was accepted although validation kept flagging it.
+    #[builder(default)]
+    validation_warnings: Vec<String>,
+    #[builder(default)]
+    back_translation: Option<BackTranslation>,
+    #[builder(default)]
+    confidence: Option<Confidence>,
+    #[builder(default)]
+    usage: Option<TokenUsage>,
+}
+
+impl Translation {
+    /// Joins translated segments of one document; `text` is the rendered document.
+    pub fn combine(text: String, profile: &str, parts: Vec<Translation>) -> Self {
+        let mut translation = Translation {
+            text,
+            profile: profile.to_owned(),
+            template_version: None,
+            glossary_violations: Vec::new(),
+            placeholder_issues: Vec::new(),
+            consistency_warnings: Vec::new(),
+            formality_issues: Vec::new(),
+            validation_warnings: Vec::new(),
+            back_translation: None,
+            confidence: None,
+            usage: None,
+        };
+        for part in parts {
+            if part.template_version.is_some() {
+                translation.template_version = part.template_version;
+            }
+            translation
+                .glossary_violations
+                .extend(part.glossary_violations);
+            translation
+                .placeholder_issues
+                .extend(part.placeholder_issues);
+            translation
+                .consistency_warnings
+                .extend(part.consistency_warnings);
+            translation.formality_issues.extend(part.formality_issues);
+            translation
+                .validation_warnings
+                .extend(part.validation_warnings);
+            if let Some(usage) = part.usage {
+                *translation.usage.get_or_insert_default() += usage;
+            }
+        }
+        translation
+    }
+
+    pub fn set_confidence(&mut self, confidence: Option<Confidence>) {
+        self.confidence = confidence;
+    }
+
+    /// Attaches the back-translation and counts the tokens spent on it.
+    pub fn set_back_translation(&mut self, back_translation: BackTranslation, usage: TokenUsage) {
+        self.back_translation = Some(back_translation);
+        *self.usage.get_or_insert_default() += usage;
+    }
+}
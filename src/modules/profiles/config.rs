@@ -0,0 +1,15 @@
+use std::collections::HashMap;
+
+use getset::Getters;
+use serde::Deserialize;
+
+use crate::modules::profiles::models::Profile;
+
+#[derive(Clone, Deserialize, Getters)]
+#[getset(get = "pub")]
+pub struct ProfilesConfig {
+    default_profile: String,
+    directory: Option<String>,
+    #[serde(default)]
+    items: HashMap<String, Profile>,
+}
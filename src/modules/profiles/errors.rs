@@ -0,0 +1,32 @@
+use std::io;
+use std::io::Error as IOError;
+use thiserror::Error;
+
+pub type ProfileResult<T> = Result<T, ProfileErrors>;
+
+#[derive(Debug, Error)]
+pub enum ProfileErrors {
+    #[error("Profile not found: {0}")]
+    NotFound(String),
+    #[error("Invalid profile: {0}")]
+    InvalidProfile(String),
+    #[error("Error reading profile: {0}")]
+    IOError(String),
+}
+
+impl From<IOError> for ProfileErrors {
+    fn from(err: IOError) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => {
+                ProfileErrors::IOError("Profile directory doesn't exist".to_string())
+            }
+            _ => ProfileErrors::IOError(err.to_string()),
+        }
+    }
+}
+
+impl From<regex::Error> for ProfileErrors {
+    fn from(err: regex::Error) -> Self {
+        ProfileErrors::InvalidProfile(format!("Invalid pass-through rule: {}", err))
+    }
+}
@@ -0,0 +1,117 @@
+pub mod config;
+pub mod errors;
+pub mod models;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use ::config::{Config, File, FileFormat};
+
+use crate::modules::profiles::config::ProfilesConfig;
+use crate::modules::profiles::errors::{ProfileErrors, ProfileResult};
+use crate::modules::profiles::models::{Profile, ProfileInfo};
+
+const PROFILE_FILE_EXTENSION: &str = "toml";
+
+pub struct ProfileRegistry {
+    default_profile: String,
+    profiles: HashMap<String, Profile>,
+}
+
+impl ProfileRegistry {
+    pub fn load(config: &ProfilesConfig) -> ProfileResult<Self> {
+        let mut profiles = HashMap::new();
+
+        if let Some(directory) = config.directory() {
+            for (name, profile) in read_profiles_directory(Path::new(directory))? {
+                profiles.insert(name.clone(), profile.compile(&name)?);
+            }
+        }
+
+        for (name, profile) in config.items() {
+            profiles.insert(name.clone(), profile.clone().compile(name)?);
+        }
+
+        let default_profile = config.default_profile().to_owned();
+        if !profiles.contains_key(&default_profile) {
+            return Err(ProfileErrors::NotFound(format!(
+                "default profile '{default_profile}' is not defined"
+            )));
+        }
+
+        tracing::info!(
+            profiles = format!("{:?}", profiles.keys().collect::<Vec<_>>()),
+            default = default_profile,
+            "Loaded translation profiles"
+        );
+        Ok(ProfileRegistry {
+            default_profile,
+            profiles,
+        })
+    }
+
+    pub fn get(&self, name: Option<&str>) -> ProfileResult<&Profile> {
+        let name = name.unwrap_or(&self.default_profile);
+        self.profiles
+            .get(name)
+            .ok_or_else(|| ProfileErrors::NotFound(name.to_owned()))
+    }
+
+    pub fn list(&self) -> Vec<ProfileInfo> {
+        let mut infos: Vec<ProfileInfo> = self
+            .profiles
+            .values()
+            .map(|profile| {
+                ProfileInfo::new(
+                    profile.name().to_owned(),
+                    profile.description().to_owned(),
+                    profile.name() == &self.default_profile,
+                )
+            })
+            .collect();
+        infos.sort_by(|a, b| a.name().cmp(b.name()));
+        infos
+    }
+}
+
+fn read_profiles_directory(directory: &Path) -> ProfileResult<Vec<(String, Profile)>> {
+    let mut profiles = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(PROFILE_FILE_EXTENSION) {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let profile: Profile = Config::builder()
+            .add_source(File::from(path.as_path()).format(FileFormat::Toml))
+            .build()
+            .and_then(|settings| settings.try_deserialize())
+            .map_err(|err| ProfileErrors::InvalidProfile(format!("{}: {}", path.display(), err)))?;
+        tracing::debug!(profile = name, path = ?path, "Read profile file");
+        profiles.push((name.to_owned(), profile));
+    }
+    Ok(profiles)
+}
+
+#[cfg(test)]
+mod test_profiles {
+    use crate::config::ServiceConfig;
+    use crate::modules::profiles::ProfileRegistry;
+
+    #[test]
+    fn test_profile_pass_through() -> Result<(), anyhow::Error> {
+        let service_config = ServiceConfig::new()?;
+        let registry = ProfileRegistry::load(service_config.profiles())?;
+        let profile = registry.get(Some("medical"))?;
+        let rules = profile.pass_through_rules();
+        assert!(rules.iter().any(|rule| rule.is_match("5 mg")));
+        assert!(!rules.iter().any(|rule| rule.is_match("Take 5 mg daily.")));
+        assert_eq!(rules[0].name(), "medical_1");
+        assert_eq!(registry.get(None)?.name(), "military");
+        assert!(registry.get(Some("unknown")).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::modules::llm_client::models::SamplingParams;
+use crate::modules::pass_through::models::PassThroughRule;
+
+#[derive(Clone, Deserialize, Getters, Debug)]
+#[getset(get = "pub")]
+pub struct Profile {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: String,
+    system_prompt: String,
+    template: String,
+    #[serde(default)]
+    sampling: SamplingParams,
+    #[serde(default)]
+    pass_through: Vec<String>,
+    #[serde(skip)]
+    pass_through_rules: Vec<PassThroughRule>,
+}
+
+#[derive(Serialize, Deserialize, Getters, ToSchema, Debug)]
+#[getset(get = "pub")]
+pub struct ProfileInfo {
+    name: String,
+    description: String,
+    is_default: bool,
+}
+
+impl Profile {
+    pub fn compile(mut self, name: &str) -> Result<Self, regex::Error> {
+        self.name = name.to_owned();
+        self.pass_through_rules = self
+            .pass_through
+            .iter()
+            .enumerate()
+            .map(|(index, pattern)| {
+                PassThroughRule::new(format!("{name}_{}", index + 1), pattern, Vec::new())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self)
+    }
+}
+
+impl ProfileInfo {
+    pub fn new(name: String, description: String, is_default: bool) -> Self {
+        Self {
+            name,
+            description,
+            is_default,
+        }
+    }
+}
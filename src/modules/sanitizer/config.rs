@@ -0,0 +1,16 @@
+use getset::{CopyGetters, Getters};
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, CopyGetters, Getters)]
+pub struct SanitizerConfig {
+    #[getset(get_copy = "pub")]
+    enabled: bool,
+    /// Extra patterns of chatter before the translation, matched at the start of output.
+    #[getset(get = "pub")]
+    #[serde(default)]
+    preambles: Vec<String>,
+    /// Extra patterns of chatter after the translation, matched at the end of output.
+    #[getset(get = "pub")]
+    #[serde(default)]
+    epilogues: Vec<String>,
+}
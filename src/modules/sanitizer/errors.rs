@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+pub type SanitizerResult<T> = Result<T, SanitizerErrors>;
+
+#[derive(Debug, Error)]
+pub enum SanitizerErrors {
+    #[error("Invalid sanitizer pattern: {0}")]
+    InvalidPattern(String),
+}
+
+impl From<regex::Error> for SanitizerErrors {
+    fn from(err: regex::Error) -> Self {
+        SanitizerErrors::InvalidPattern(err.to_string())
+    }
+}
@@ -0,0 +1,373 @@
+pub mod config;
+pub mod errors;
+pub mod models;
+
+use regex::Regex;
+
+use crate::modules::sanitizer::config::SanitizerConfig;
+use crate::modules::sanitizer::errors::SanitizerResult;
+use crate::modules::sanitizer::models::{Sanitization, SanitizeStep};
+
+/// Chatter can be nested ("Sure! Here is the translation:" around a fenced
+/// block), so steps are repeated until the output stops changing.
+const MAX_PASSES: usize = 3;
+
+const BUILTIN_PREAMBLES: [(&str, &str); 3] = [
+    (
+        "here_is",
+        r"(?i)^(?:(?:sure|certainly|of course|okay)[,!.]?\s*)?here(?:'s| is| are)\s+(?:\w+\s+){0,4}?translation[^\n:]*:[ \t]*\n?",
+    ),
+    (
+        "label",
+        r"(?i)^(?:[\w-]+\s+)?translation(?:\s+(?:in|into|to)\s+[\w -]+)?\s*:[ \t]*\n?",
+    ),
+    (
+        "label_ru",
+        r"(?i)^(?:вот\s+)?перевод(?:\s+на\s+[\w -]+)?\s*:[ \t]*\n?",
+    ),
+];
+
+const BUILTIN_EPILOGUES: [(&str, &str); 2] = [
+    // The last paragraph only: a note is never followed by a blank line.
+    (
+        "note",
+        r"(?i)\n[ \t]*\(?(?:note|примечание)\s*:(?:[^\n]|\n[ \t]*\S)*$",
+    ),
+    (
+        "offer",
+        r"(?i)\n[ \t]*(?:i hope this helps|let me know if|feel free to|if you have any)[^\n]*$",
+    ),
+];
+
+/// Paragraph with a note of the model between paragraphs of the translation.
+const NOTE_PATTERN: &str = r"(?i)^[ \t]*\(?(?:note|примечание)\s*:";
+const PARAGRAPH_BREAK_PATTERN: &str = r"\n[ \t]*\n\s*";
+
+/// Label that starts a text, such as `Hinweis:` or `Translation:`.
+const LABEL_PATTERN: &str = r"^\(?\p{L}[\p{L} -]{0,30}:";
+
+const FENCE_PATTERN: &str = r"(?s)^(?:```|~~~)[\w-]*[ \t]*\n(.*?)\n?[ \t]*(?:```|~~~)$";
+const INLINE_CODE_PATTERN: &str = r"^`([^`\n]+)`$";
+const QUOTE_PAIRS: [(char, char); 5] =
+    [('"', '"'), ('“', '”'), ('«', '»'), ('„', '“'), ('\'', '\'')];
+
+struct SanitizeRule {
+    name: String,
+    regex: Regex,
+}
+
+impl SanitizeRule {
+    fn compile(
+        rules: &[(&str, &str)],
+        custom: &[String],
+        prefix: &str,
+    ) -> SanitizerResult<Vec<Self>> {
+        let builtin = rules
+            .iter()
+            .map(|(name, pattern)| (name.to_string(), pattern.to_string()));
+        let custom = custom
+            .iter()
+            .enumerate()
+            .map(|(index, pattern)| (format!("{prefix}_{}", index + 1), pattern.to_owned()));
+
+        let mut compiled = Vec::new();
+        for (name, pattern) in builtin.chain(custom) {
+            compiled.push(SanitizeRule {
+                name,
+                regex: Regex::new(&pattern)?,
+            });
+        }
+        Ok(compiled)
+    }
+}
+
+/// Cleans up what chat models add around the translation: wrapping fences
+/// and quotes, preambles, epilogues, added notes and changed surrounding
+/// whitespace.
+/// Nothing is removed when the source text has the same construct.
+pub struct OutputSanitizer {
+    enabled: bool,
+    preambles: Vec<SanitizeRule>,
+    epilogues: Vec<SanitizeRule>,
+    label: Regex,
+    note: Regex,
+    paragraph_break: Regex,
+    fence: Regex,
+    inline_code: Regex,
+}
+
+impl OutputSanitizer {
+    pub fn new(config: &SanitizerConfig) -> SanitizerResult<Self> {
+        Ok(OutputSanitizer {
+            enabled: config.enabled(),
+            preambles: SanitizeRule::compile(&BUILTIN_PREAMBLES, config.preambles(), "preamble")?,
+            epilogues: SanitizeRule::compile(&BUILTIN_EPILOGUES, config.epilogues(), "epilogue")?,
+            label: Regex::new(LABEL_PATTERN)?,
+            note: Regex::new(NOTE_PATTERN)?,
+            paragraph_break: Regex::new(PARAGRAPH_BREAK_PATTERN)?,
+            fence: Regex::new(FENCE_PATTERN)?,
+            inline_code: Regex::new(INLINE_CODE_PATTERN)?,
+        })
+    }
+
+    /// Returns the cleaned output together with every applied modification.
+    pub fn sanitize(&self, source: &str, output: &str) -> (String, Vec<Sanitization>) {
+        let mut fixes = Vec::new();
+        if !self.enabled {
+            return (output.to_owned(), fixes);
+        }
+
+        let source_core = source.trim();
+        let mut current = output.trim().to_owned();
+        for _ in 0..MAX_PASSES {
+            let before = fixes.len();
+            current = self.strip_preamble(source_core, current, &mut fixes);
+            current = self.strip_epilogue(source_core, current, &mut fixes);
+            current = self.strip_notes(source_core, current, &mut fixes);
+            current = self.strip_fence(source_core, current, &mut fixes);
+            current = strip_quotes(source_core, current, &mut fixes);
+            if fixes.len() == before {
+                break;
+            }
+        }
+
+        let (leading, trailing) = edges(source);
+        let (output_leading, output_trailing) = edges(output);
+        if leading != output_leading || trailing != output_trailing {
+            let removed = format!("{output_leading:?} ... {output_trailing:?}");
+            fixes.push(Sanitization::new(
+                SanitizeStep::Whitespace,
+                "edges",
+                &removed,
+            ));
+        }
+        (format!("{leading}{current}{trailing}"), fixes)
+    }
+
+    fn strip_preamble(&self, source: &str, text: String, fixes: &mut Vec<Sanitization>) -> String {
+        if self
+            .preambles
+            .iter()
+            .any(|rule| rule.regex.is_match(source))
+        {
+            return text;
+        }
+        // A preamble on the line of the text may be a translated label of
+        // the source, such as `Hinweis:`.
+        let labeled = self.label.is_match(source);
+        for rule in &self.preambles {
+            if let Some(found) = rule.regex.find(&text)
+                && found.start() == 0
+                && !text[found.end()..].trim().is_empty()
+                && (found.as_str().contains('\n') || !labeled)
+                && keeps_lines(source, &text[found.end()..])
+            {
+                fixes.push(Sanitization::new(
+                    SanitizeStep::Preamble,
+                    &rule.name,
+                    found.as_str(),
+                ));
+                return text[found.end()..].trim_start().to_owned();
+            }
+        }
+        text
+    }
+
+    fn strip_epilogue(&self, source: &str, text: String, fixes: &mut Vec<Sanitization>) -> String {
+        if self
+            .epilogues
+            .iter()
+            .any(|rule| rule.regex.is_match(source))
+        {
+            return text;
+        }
+        for rule in &self.epilogues {
+            if let Some(found) = rule.regex.find(&text)
+                && found.end() == text.len()
+                && !text[..found.start()].trim().is_empty()
+                && keeps_lines(source, &text[..found.start()])
+            {
+                fixes.push(Sanitization::new(
+                    SanitizeStep::Epilogue,
+                    &rule.name,
+                    found.as_str(),
+                ));
+                return text[..found.start()].trim_end().to_owned();
+            }
+        }
+        text
+    }
+
+    /// Removes note paragraphs inside the text while it has more paragraphs
+    /// than the source, so notes translated from the source are kept.
+    fn strip_notes(&self, source: &str, text: String, fixes: &mut Vec<Sanitization>) -> String {
+        let paragraphs = |text: &str| {
+            self.paragraph_break
+                .split(text)
+                .filter(|paragraph| !paragraph.trim().is_empty())
+                .count()
+        };
+        let mut extra = paragraphs(&text).saturating_sub(paragraphs(source));
+        if extra == 0 {
+            return text;
+        }
+
+        let mut kept = String::new();
+        let mut start = 0;
+        for separator in self
+            .paragraph_break
+            .find_iter(&text)
+            .map(|found| found.range())
+            .chain(std::iter::once(text.len()..text.len()))
+        {
+            let paragraph = &text[start..separator.start];
+            if extra > 0 && start > 0 && self.note.is_match(paragraph) {
+                fixes.push(Sanitization::new(SanitizeStep::Note, "note", paragraph));
+                extra -= 1;
+            } else {
+                kept.push_str(&text[start..separator.end]);
+            }
+            start = separator.end;
+        }
+        kept.trim_end().to_owned()
+    }
+
+    fn strip_fence(&self, source: &str, text: String, fixes: &mut Vec<Sanitization>) -> String {
+        if source.starts_with('`') || source.starts_with("~~~") {
+            return text;
+        }
+        for (name, regex) in [
+            ("code_fence", &self.fence),
+            ("inline_code", &self.inline_code),
+        ] {
+            if let Some(inner) = regex.captures(&text).and_then(|caps| caps.get(1))
+                && !inner.as_str().trim().is_empty()
+            {
+                let inner = inner.as_str().trim().to_owned();
+                let removed = text.replace(&inner, "...");
+                fixes.push(Sanitization::new(SanitizeStep::Fence, name, &removed));
+                return inner;
+            }
+        }
+        text
+    }
+}
+
+fn strip_quotes(source: &str, text: String, fixes: &mut Vec<Sanitization>) -> String {
+    for (open, close) in QUOTE_PAIRS {
+        if source.starts_with(open) {
+            continue;
+        }
+        let Some(inner) = text
+            .strip_prefix(open)
+            .and_then(|rest| rest.strip_suffix(close))
+        else {
+            continue;
+        };
+        if inner.trim().is_empty() || inner.contains(open) || inner.contains(close) {
+            continue;
+        }
+        let removed = format!("{open}...{close}");
+        fixes.push(Sanitization::new(
+            SanitizeStep::Quotes,
+            "wrapping_quotes",
+            &removed,
+        ));
+        return inner.trim().to_owned();
+    }
+    text
+}
+
+/// Whether the text left after stripping has as many lines as the source,
+/// so chatter is only taken from lines the model added.
+fn keeps_lines(source: &str, kept: &str) -> bool {
+    let lines = |text: &str| text.lines().filter(|line| !line.trim().is_empty()).count();
+    lines(kept) >= lines(source)
+}
+
+fn edges(text: &str) -> (&str, &str) {
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len().max(start);
+    (&text[..start], &text[end..])
+}
+
+#[cfg(test)]
+mod test_sanitizer {
+    use crate::config::ServiceConfig;
+    use crate::modules::sanitizer::OutputSanitizer;
+    use crate::modules::sanitizer::models::SanitizeStep;
+
+    fn sanitizer() -> OutputSanitizer {
+        let service_config = ServiceConfig::new().unwrap();
+        OutputSanitizer::new(service_config.sanitizer()).unwrap()
+    }
+
+    #[test]
+    fn test_strip_chatter() {
+        let sanitizer = sanitizer();
+        let output = "Sure! Here is the translation into Russian:\n```\nЭто моя винтовка.\n```\n\nNote: the tone is formal.";
+        let (text, fixes) = sanitizer.sanitize("This is my rifle.", output);
+        assert_eq!(text, "Это моя винтовка.");
+        let steps: Vec<SanitizeStep> = fixes.iter().map(|fix| fix.step()).collect();
+        assert_eq!(
+            steps,
+            vec![
+                SanitizeStep::Preamble,
+                SanitizeStep::Epilogue,
+                SanitizeStep::Fence
+            ]
+        );
+
+        let (text, _) = sanitizer.sanitize("Hello", "«Привет»");
+        assert_eq!(text, "Привет");
+        let (text, _) = sanitizer.sanitize("Перевод: step 1", "Перевод: шаг 1");
+        assert_eq!(text, "Перевод: шаг 1");
+    }
+
+    #[test]
+    fn test_keep_translated_labels() {
+        let sanitizer = sanitizer();
+        let source = "Das Gerät ist bereit.\n\nHinweis: Nicht öffnen.\nGefahr!\n\nWeiter.";
+        let output = "The device is ready.\n\nNote: Do not open.\nDanger!\n\nContinue.";
+        let (text, fixes) = sanitizer.sanitize(source, output);
+        assert_eq!(text, output);
+        assert!(fixes.is_empty());
+
+        let source = "Das Gerät ist bereit.\n\nHinweis: Nicht öffnen.\nGefahr!";
+        let output = "The device is ready.\n\nNote: Do not open.\nDanger!";
+        let (text, fixes) = sanitizer.sanitize(source, output);
+        assert_eq!(text, output);
+        assert!(fixes.is_empty());
+
+        let (text, _) = sanitizer.sanitize("Übersetzung: Anna Weber", "Translation: Anna Weber");
+        assert_eq!(text, "Translation: Anna Weber");
+
+        let (text, _) = sanitizer.sanitize("Bereit.", "Ready.\n\nNote: \"Bereit\" is informal.");
+        assert_eq!(text, "Ready.");
+        let (text, _) = sanitizer.sanitize("Bereit.", "Translation: Ready.");
+        assert_eq!(text, "Ready.");
+        let (text, _) = sanitizer.sanitize(
+            "Eins.\n\nZwei.",
+            "One.\n\nNote: first.\n\nTwo.\n\nNote: second.",
+        );
+        assert_eq!(text, "One.\n\nTwo.");
+
+        let (text, fixes) = sanitizer.sanitize("Eins.\n\nZwei.", "One.\n\nNote: first.\n\nTwo.");
+        assert_eq!(text, "One.\n\nTwo.");
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].step(), SanitizeStep::Note);
+    }
+
+    #[test]
+    fn test_restore_whitespace() {
+        let sanitizer = sanitizer();
+        let (text, fixes) = sanitizer.sanitize("  Hello\n", "Привет \n\n");
+        assert_eq!(text, "  Привет\n");
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].step(), SanitizeStep::Whitespace);
+
+        let (text, fixes) = sanitizer.sanitize("\"Hello\"", "\"Привет\"");
+        assert_eq!(text, "\"Привет\"");
+        assert!(fixes.is_empty());
+    }
+}
@@ -0,0 +1,48 @@
+use std::fmt;
+
+use getset::{CopyGetters, Getters};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SanitizeStep {
+    Preamble,
+    Epilogue,
+    Note,
+    Fence,
+    Quotes,
+    Whitespace,
+}
+
+impl fmt::Display for SanitizeStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SanitizeStep::Preamble => "preamble",
+            SanitizeStep::Epilogue => "epilogue",
+            SanitizeStep::Note => "note",
+            SanitizeStep::Fence => "fence",
+            SanitizeStep::Quotes => "quotes",
+            SanitizeStep::Whitespace => "whitespace",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Single modification of the model output.
+#[derive(Clone, Getters, CopyGetters, Debug)]
+pub struct Sanitization {
+    #[getset(get_copy = "pub")]
+    step: SanitizeStep,
+    #[getset(get = "pub")]
+    rule: String,
+    #[getset(get = "pub")]
+    removed: String,
+}
+
+impl Sanitization {
+    pub fn new(step: SanitizeStep, rule: &str, removed: &str) -> Self {
+        Self {
+            step,
+            rule: rule.to_owned(),
+            removed: removed.to_owned(),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use getset::CopyGetters;
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct TablesConfig {
+    /// Rows read ahead and translated together; equal cells of a batch are translated once.
+    batch_rows: usize,
+    /// Translation requests of a batch running at the same time.
+    max_concurrency: usize,
+    /// Translations remembered across batches; the memo is cleared when full.
+    cache_size: usize,
+}
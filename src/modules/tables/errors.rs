@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+pub type TableResult<T> = Result<T, TableErrors>;
+
+#[derive(Debug, Error)]
+pub enum TableErrors {
+    #[error("Malformed table: {0}")]
+    Malformed(String),
+    #[error("Unknown column: {0}")]
+    UnknownColumn(String),
+    #[error("Invalid table option: {0}")]
+    InvalidOption(String),
+    #[error("Cell has no translation: {0}")]
+    MissingTranslation(String),
+}
+
+impl From<csv::Error> for TableErrors {
+    fn from(err: csv::Error) -> Self {
+        TableErrors::Malformed(err.to_string())
+    }
+}
@@ -0,0 +1,382 @@
+pub mod config;
+pub mod errors;
+pub mod models;
+
+use std::collections::HashSet;
+use std::io::{self, Read};
+
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::modules::tables::errors::{TableErrors, TableResult};
+use crate::modules::tables::models::{CellTranslation, TableOptions, UNTRANSLATED_COLUMN};
+
+/// Batches parsed ahead of the one being translated.
+const READ_AHEAD: usize = 1;
+
+/// Rows of an uploaded table, parsed on a blocking thread while the upload
+/// is still arriving and handed out in batches.
+pub struct TableReader {
+    header: StringRecord,
+    batches: mpsc::Receiver<TableResult<Vec<StringRecord>>>,
+}
+
+impl TableReader {
+    /// Starts parsing the chunks of `input` and waits for the header row.
+    pub async fn start(
+        input: mpsc::Receiver<io::Result<Vec<u8>>>,
+        delimiter: u8,
+        batch_rows: usize,
+    ) -> TableResult<Self> {
+        let (header_sender, header) = oneshot::channel();
+        let (batch_sender, batches) = mpsc::channel(READ_AHEAD);
+        tokio::task::spawn_blocking(move || {
+            read(
+                ChannelReader::new(input),
+                delimiter,
+                batch_rows.max(1),
+                header_sender,
+                batch_sender,
+            )
+        });
+        let header = header
+            .await
+            .map_err(|_| TableErrors::Malformed("table reader stopped".to_string()))??;
+        Ok(Self { header, batches })
+    }
+
+    pub fn header(&self) -> &StringRecord {
+        &self.header
+    }
+
+    /// Next rows of the table, `None` after the last one.
+    pub async fn next_batch(&mut self) -> TableResult<Option<Vec<StringRecord>>> {
+        self.batches.recv().await.transpose()
+    }
+}
+
+fn read(
+    input: ChannelReader,
+    delimiter: u8,
+    batch_rows: usize,
+    header: oneshot::Sender<TableResult<StringRecord>>,
+    batches: mpsc::Sender<TableResult<Vec<StringRecord>>>,
+) {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(input);
+    let result = match reader.headers() {
+        Ok(record) if record.iter().any(|name| !name.trim().is_empty()) => Ok(record.clone()),
+        Ok(_) => Err(TableErrors::Malformed(
+            "table has no header row".to_string(),
+        )),
+        Err(err) => Err(err.into()),
+    };
+    if header.send(result).is_err() {
+        return;
+    }
+
+    let mut batch = Vec::with_capacity(batch_rows);
+    for record in reader.into_records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                let _ = batches.blocking_send(Err(err.into()));
+                return;
+            }
+        };
+        batch.push(record);
+        if batch.len() >= batch_rows
+            && batches
+                .blocking_send(Ok(std::mem::take(&mut batch)))
+                .is_err()
+        {
+            return;
+        }
+    }
+    if !batch.is_empty() {
+        let _ = batches.blocking_send(Ok(batch));
+    }
+}
+
+/// Blocking [`Read`] over chunks arriving on a channel.
+struct ChannelReader {
+    input: mpsc::Receiver<io::Result<Vec<u8>>>,
+    chunk: Vec<u8>,
+    position: usize,
+}
+
+impl ChannelReader {
+    fn new(input: mpsc::Receiver<io::Result<Vec<u8>>>) -> Self {
+        Self {
+            input,
+            chunk: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.chunk.len() {
+            match self.input.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = chunk?;
+                    self.position = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let count = buf.len().min(self.chunk.len() - self.position);
+        buf[..count].copy_from_slice(&self.chunk[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+/// Columns to translate and the header of the output, which is the input
+/// header followed by one translation column per translated column and the
+/// column of untranslated cells.
+pub struct ColumnMapping {
+    header: StringRecord,
+    width: usize,
+    columns: Vec<usize>,
+    names: Vec<String>,
+}
+
+impl ColumnMapping {
+    pub fn new(
+        header: &StringRecord,
+        options: &TableOptions,
+        target_language: &str,
+    ) -> TableResult<Self> {
+        if options.columns().is_empty() {
+            return Err(TableErrors::InvalidOption(
+                "no columns to translate".to_string(),
+            ));
+        }
+        let mut names: HashSet<String> = header.iter().map(str::to_owned).collect();
+        let mut output = header.clone();
+        let mut columns = Vec::with_capacity(options.columns().len());
+        let mut translated = Vec::with_capacity(options.columns().len());
+        for column in options.columns() {
+            let index = header
+                .iter()
+                .position(|name| name.trim() == column.trim())
+                .ok_or_else(|| TableErrors::UnknownColumn(column.clone()))?;
+            if columns.contains(&index) {
+                continue;
+            }
+            let name = column_name(options.column_pattern(), column, target_language);
+            if name.trim().is_empty() || !names.insert(name.clone()) {
+                return Err(TableErrors::InvalidOption(format!(
+                    "translation column `{name}` of `{column}` is empty or already exists"
+                )));
+            }
+            output.push_field(&name);
+            columns.push(index);
+            translated.push(name);
+        }
+        let mut untranslated = UNTRANSLATED_COLUMN.to_string();
+        while names.contains(&untranslated) {
+            untranslated.push('_');
+        }
+        output.push_field(&untranslated);
+        Ok(Self {
+            header: output,
+            width: header.len(),
+            columns,
+            names: translated,
+        })
+    }
+
+    /// Header of the translated table.
+    pub fn header(&self) -> &StringRecord {
+        &self.header
+    }
+
+    /// Non-blank cells of the translated columns of a row.
+    pub fn texts<'a>(&'a self, record: &'a StringRecord) -> impl Iterator<Item = &'a str> {
+        self.columns
+            .iter()
+            .filter_map(|index| record.get(*index))
+            .filter(|cell| !cell.trim().is_empty())
+    }
+
+    /// Row padded to the input header with its translations appended, and
+    /// the names of the translation columns whose cells kept their source text.
+    pub fn extend<'a>(
+        &self,
+        record: &StringRecord,
+        translation: impl Fn(&str) -> Option<&'a CellTranslation>,
+    ) -> TableResult<StringRecord> {
+        if record.len() > self.width {
+            let line = record.position().map_or(0, |position| position.line());
+            return Err(TableErrors::Malformed(format!(
+                "row at line {line} has {} fields, the header has {}",
+                record.len(),
+                self.width
+            )));
+        }
+        let mut row = record.clone();
+        for _ in record.len()..self.width {
+            row.push_field("");
+        }
+        let mut untranslated = Vec::new();
+        for (index, name) in self.columns.iter().zip(&self.names) {
+            let cell = record.get(*index).unwrap_or_default();
+            if cell.trim().is_empty() {
+                row.push_field("");
+                continue;
+            }
+            match translation(cell) {
+                Some(CellTranslation::Translated(text)) => row.push_field(text),
+                Some(CellTranslation::Rejected) => {
+                    row.push_field(cell);
+                    untranslated.push(name.as_str());
+                }
+                None => return Err(TableErrors::MissingTranslation(cell.to_owned())),
+            }
+        }
+        row.push_field(&untranslated.join(","));
+        Ok(row)
+    }
+}
+
+/// Name of the translation column of `column`.
+pub fn column_name(pattern: &str, column: &str, target_language: &str) -> String {
+    pattern
+        .replace("{col}", column)
+        .replace("{target}", target_language)
+}
+
+/// Writes translated rows as chunks of the output table, the header first.
+pub struct TableWriter {
+    delimiter: u8,
+    header: Option<StringRecord>,
+}
+
+impl TableWriter {
+    pub fn new(header: StringRecord, delimiter: u8) -> Self {
+        Self {
+            delimiter,
+            header: Some(header),
+        }
+    }
+
+    /// Rows quoted where needed, after the header in the first chunk.
+    pub fn chunk(&mut self, rows: &[StringRecord]) -> TableResult<Vec<u8>> {
+        let mut writer = WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(Vec::new());
+        if let Some(header) = self.header.take() {
+            writer.write_record(&header)?;
+        }
+        for row in rows {
+            writer.write_record(row)?;
+        }
+        writer
+            .into_inner()
+            .map_err(|err| TableErrors::Malformed(err.to_string()))
+    }
+
+    /// Whether the header is not written yet.
+    pub fn is_pending(&self) -> bool {
+        self.header.is_some()
+    }
+}
+
+#[cfg(test)]
+mod test_tables {
+    use std::collections::HashMap;
+
+    use csv::StringRecord;
+    use tokio::sync::mpsc;
+
+    use crate::modules::tables::errors::TableErrors;
+    use crate::modules::tables::models::{CellTranslation, TableOptions};
+    use crate::modules::tables::{ColumnMapping, TableReader, TableWriter};
+
+    const TABLE: &str = "id,text,source\n1,\"Hello, world\",radio\n2,\"Line one\nline \"\"two\"\"\",radio\n3,,\n4,Hello\n";
+
+    #[tokio::test]
+    async fn test_tables_translate_columns() {
+        let (sender, input) = mpsc::channel(4);
+        let upload = async move {
+            for chunk in TABLE.as_bytes().chunks(7) {
+                sender.send(Ok(chunk.to_vec())).await.unwrap();
+            }
+        };
+        let (_, reader) = tokio::join!(upload, TableReader::start(input, b',', 2));
+        let mut reader = reader.unwrap();
+
+        let options = TableOptions::new(vec!["text".to_string(), "source".to_string()], b',')
+            .with_column_pattern("{col}_{target}".to_string());
+        let mapping = ColumnMapping::new(reader.header(), &options, "ru").unwrap();
+        let translated = |text: &str| CellTranslation::Translated(text.to_string());
+        let translations = HashMap::from([
+            ("Hello, world", translated("Привет, мир")),
+            (
+                "Line one\nline \"two\"",
+                translated("Строка один\nстрока «два»"),
+            ),
+            ("Hello", CellTranslation::Rejected),
+            ("radio", translated("радио")),
+        ]);
+
+        let mut texts = Vec::new();
+        let mut rows = Vec::new();
+        while let Some(batch) = reader.next_batch().await.unwrap() {
+            for record in &batch {
+                texts.extend(mapping.texts(record).map(str::to_owned));
+                rows.push(
+                    mapping
+                        .extend(record, |text| translations.get(text))
+                        .unwrap(),
+                );
+            }
+        }
+        assert_eq!(texts.len(), 5);
+
+        let mut writer = TableWriter::new(mapping.header().clone(), b',');
+        let output = String::from_utf8(writer.chunk(&rows).unwrap()).unwrap();
+        assert_eq!(
+            output,
+            "id,text,source,text_ru,source_ru,untranslated\n\
+             1,\"Hello, world\",radio,\"Привет, мир\",радио,\n\
+             2,\"Line one\nline \"\"two\"\"\",radio,\"Строка один\nстрока «два»\",радио,\n\
+             3,,,,,\n\
+             4,Hello,,Hello,,text_ru\n"
+        );
+
+        let record = StringRecord::from(vec!["5", "Unknown", "radio"]);
+        let result = mapping.extend(&record, |text| translations.get(text));
+        assert!(matches!(result, Err(TableErrors::MissingTranslation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_tables_reject_bad_columns() {
+        let (sender, input) = mpsc::channel(1);
+        sender.send(Ok(b"id\ttext\n".to_vec())).await.unwrap();
+        drop(sender);
+        let reader = TableReader::start(input, b'\t', 10).await.unwrap();
+
+        let options = TableOptions::new(vec!["body".to_string()], b'\t');
+        let result = ColumnMapping::new(reader.header(), &options, "ru");
+        assert!(matches!(result, Err(TableErrors::UnknownColumn(_))));
+
+        let options = TableOptions::new(vec!["id".to_string(), "text".to_string()], b'\t')
+            .with_column_pattern("{target}".to_string());
+        let result = ColumnMapping::new(reader.header(), &options, "ru");
+        assert!(matches!(result, Err(TableErrors::InvalidOption(_))));
+
+        let header = StringRecord::from(vec!["text", "untranslated"]);
+        let options = TableOptions::new(vec!["text".to_string()], b'\t');
+        let mapping = ColumnMapping::new(&header, &options, "ru").unwrap();
+        assert_eq!(
+            mapping.header(),
+            &StringRecord::from(vec!["text", "untranslated", "text_ru", "untranslated_"])
+        );
+    }
+}
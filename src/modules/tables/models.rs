@@ -0,0 +1,45 @@
+use getset::{CopyGetters, Getters};
+
+/// Name pattern of translation columns when none is given.
+pub const DEFAULT_COLUMN_PATTERN: &str = "{col}_{target}";
+/// Last column of the output, naming the translation columns of a row whose
+/// cells kept their source text.
+pub const UNTRANSLATED_COLUMN: &str = "untranslated";
+
+/// Translation of a cell.
+#[derive(Clone, Debug)]
+pub enum CellTranslation {
+    Translated(String),
+    /// The translation was refused or rejected; the cell keeps its source text.
+    Rejected,
+}
+
+/// Upload options of a table: the columns to translate, where their
+/// translations go and how fields are separated.
+#[derive(Getters, CopyGetters, Clone, Debug)]
+pub struct TableOptions {
+    /// Header names of the columns to translate.
+    #[getset(get = "pub")]
+    columns: Vec<String>,
+    /// Name of a translation column, with `{col}` for the source column
+    /// and `{target}` for the target language.
+    #[getset(get = "pub")]
+    column_pattern: String,
+    #[getset(get_copy = "pub")]
+    delimiter: u8,
+}
+
+impl TableOptions {
+    pub fn new(columns: Vec<String>, delimiter: u8) -> Self {
+        Self {
+            columns,
+            column_pattern: DEFAULT_COLUMN_PATTERN.to_string(),
+            delimiter,
+        }
+    }
+
+    pub fn with_column_pattern(mut self, column_pattern: String) -> Self {
+        self.column_pattern = column_pattern;
+        self
+    }
+}
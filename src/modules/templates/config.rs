@@ -0,0 +1,16 @@
+use std::collections::HashMap;
+
+use getset::{CopyGetters, Getters};
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, CopyGetters, Getters)]
+pub struct TemplatesConfig {
+    #[getset(get = "pub")]
+    directory: String,
+    #[getset(get_copy = "pub")]
+    #[serde(default)]
+    reload_interval_secs: u64,
+    #[getset(get = "pub")]
+    #[serde(default)]
+    pinned: HashMap<String, String>,
+}
@@ -0,0 +1,28 @@
+use std::io;
+use std::io::Error as IOError;
+use thiserror::Error;
+
+pub type TemplateResult<T> = Result<T, TemplateErrors>;
+
+#[derive(Debug, Error)]
+pub enum TemplateErrors {
+    #[error("Template not found: {0}")]
+    NotFound(String),
+    #[error("Template syntax error: {0}")]
+    SyntaxError(String),
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
+    #[error("Error reading template: {0}")]
+    IOError(String),
+}
+
+impl From<IOError> for TemplateErrors {
+    fn from(err: IOError) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => {
+                TemplateErrors::IOError("Template directory doesn't exist".to_string())
+            }
+            _ => TemplateErrors::IOError(err.to_string()),
+        }
+    }
+}
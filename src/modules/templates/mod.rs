@@ -0,0 +1,256 @@
+pub mod config;
+pub mod errors;
+pub mod models;
+pub mod parser;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::RwLock;
+
+use crate::modules::templates::config::TemplatesConfig;
+use crate::modules::templates::errors::{TemplateErrors, TemplateResult};
+use crate::modules::templates::models::{
+    PromptTemplate, PromptVariables, RenderedPrompt, TemplateInfo, TemplateVersion,
+};
+
+const TEMPLATE_FILE_EXTENSION: &str = "tmpl";
+
+type TemplateVersions = BTreeMap<TemplateVersion, PromptTemplate>;
+
+pub struct TemplateStore {
+    config: TemplatesConfig,
+    templates: RwLock<HashMap<String, TemplateVersions>>,
+}
+
+impl TemplateStore {
+    pub fn load(config: &TemplatesConfig) -> TemplateResult<Self> {
+        let templates = read_templates_directory(Path::new(config.directory()))?;
+        validate_pinned(config, &templates)?;
+        log_loaded(&templates);
+        Ok(TemplateStore {
+            config: config.clone(),
+            templates: RwLock::new(templates),
+        })
+    }
+
+    /// Re-reads the template directory. The new set replaces the active one only
+    /// when every file in it is valid, so a broken edit never reaches requests.
+    pub async fn reload(&self) -> TemplateResult<Vec<TemplateInfo>> {
+        let templates = read_templates_directory(Path::new(self.config.directory()))?;
+        validate_pinned(&self.config, &templates)?;
+        log_loaded(&templates);
+        *self.templates.write().await = templates;
+        Ok(self.list().await)
+    }
+
+    pub async fn render(
+        &self,
+        name: &str,
+        variables: &PromptVariables,
+    ) -> TemplateResult<RenderedPrompt> {
+        let templates = self.templates.read().await;
+        let template = active_version(&self.config, &templates, name)?;
+        Ok(RenderedPrompt::new(
+            parser::render(template.nodes(), variables),
+            template.version_tag(),
+        ))
+    }
+
+    pub async fn list(&self) -> Vec<TemplateInfo> {
+        let templates = self.templates.read().await;
+        let mut infos: Vec<TemplateInfo> = templates
+            .iter()
+            .filter_map(|(name, versions)| {
+                let active = active_version(&self.config, &templates, name).ok()?;
+                Some(TemplateInfo::new(
+                    name.to_owned(),
+                    active.version().to_string(),
+                    versions.keys().map(|version| version.to_string()).collect(),
+                ))
+            })
+            .collect();
+        infos.sort_by(|a, b| a.name().cmp(b.name()));
+        infos
+    }
+
+    /// Polls the template directory and reloads it whenever a file is added,
+    /// removed or modified. Disabled when `reload_interval_secs` is zero.
+    pub fn watch(self: &Arc<Self>) {
+        let interval_secs = self.config.reload_interval_secs();
+        if interval_secs == 0 {
+            return;
+        }
+
+        let store = self.clone();
+        tokio::spawn(async move {
+            let directory = PathBuf::from(store.config.directory());
+            let mut fingerprint = directory_fingerprint(&directory);
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                let current = directory_fingerprint(&directory);
+                if current == fingerprint {
+                    continue;
+                }
+                fingerprint = current;
+                match store.reload().await {
+                    Ok(_) => tracing::info!("Prompt templates reloaded"),
+                    Err(err) => {
+                        tracing::error!(err=?err, "Failed to reload prompt templates, keeping previous set")
+                    }
+                }
+            }
+        });
+    }
+}
+
+fn active_version<'a>(
+    config: &TemplatesConfig,
+    templates: &'a HashMap<String, TemplateVersions>,
+    name: &str,
+) -> TemplateResult<&'a PromptTemplate> {
+    let versions = templates
+        .get(name)
+        .ok_or_else(|| TemplateErrors::NotFound(name.to_owned()))?;
+
+    match config.pinned().get(name) {
+        Some(pinned) => TemplateVersion::parse(pinned)
+            .and_then(|version| versions.get(&version))
+            .ok_or_else(|| TemplateErrors::NotFound(format!("{name}@{pinned}"))),
+        None => versions
+            .values()
+            .next_back()
+            .ok_or_else(|| TemplateErrors::NotFound(name.to_owned())),
+    }
+}
+
+fn validate_pinned(
+    config: &TemplatesConfig,
+    templates: &HashMap<String, TemplateVersions>,
+) -> TemplateResult<()> {
+    for name in config.pinned().keys() {
+        active_version(config, templates, name)?;
+    }
+    Ok(())
+}
+
+fn log_loaded(templates: &HashMap<String, TemplateVersions>) {
+    for (name, versions) in templates {
+        tracing::info!(
+            template = name,
+            versions = format!(
+                "{:?}",
+                versions.keys().map(|v| v.to_string()).collect::<Vec<_>>()
+            ),
+            "Loaded prompt template"
+        );
+    }
+}
+
+fn read_templates_directory(directory: &Path) -> TemplateResult<HashMap<String, TemplateVersions>> {
+    let mut templates = HashMap::new();
+    for entry in fs::read_dir(directory)? {
+        let template_dir = entry?.path();
+        if !template_dir.is_dir() {
+            continue;
+        }
+        let Some(name) = template_dir.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+
+        let mut versions = TemplateVersions::new();
+        for file in fs::read_dir(&template_dir)? {
+            let path = file?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(TEMPLATE_FILE_EXTENSION) {
+                continue;
+            }
+            let stem = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default();
+            let version = TemplateVersion::parse(stem).ok_or_else(|| {
+                TemplateErrors::InvalidTemplate(format!(
+                    "{}: file name must be a version like v1.{TEMPLATE_FILE_EXTENSION}",
+                    path.display()
+                ))
+            })?;
+
+            let source = fs::read_to_string(&path)?;
+            let nodes = parser::parse(source.trim()).map_err(|err| {
+                TemplateErrors::InvalidTemplate(format!("{}: {}", path.display(), err))
+            })?;
+            versions.insert(
+                version,
+                PromptTemplate::new(name.to_owned(), version, nodes),
+            );
+        }
+
+        if !versions.is_empty() {
+            templates.insert(name.to_owned(), versions);
+        }
+    }
+    Ok(templates)
+}
+
+fn directory_fingerprint(directory: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut fingerprint = Vec::new();
+    let Ok(entries) = fs::read_dir(directory) else {
+        return fingerprint;
+    };
+    for template_dir in entries.flatten() {
+        let Ok(files) = fs::read_dir(template_dir.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let modified = file.metadata().and_then(|meta| meta.modified()).ok();
+            fingerprint.push((file.path(), modified));
+        }
+    }
+    fingerprint.sort();
+    fingerprint
+}
+
+#[cfg(test)]
+mod test_templates {
+    use crate::modules::templates::models::PromptVariablesBuilder;
+    use crate::modules::templates::parser;
+
+    #[test]
+    fn test_render_optional_sections() -> Result<(), anyhow::Error> {
+        let nodes = parser::parse(
+            "Translate into {{target_language}}.\n{{#glossary}}\nTerms:\n{{glossary}}\n{{/glossary}}\n{{text}}",
+        )?;
+
+        let variables = PromptVariablesBuilder::default()
+            .target_language("Russian")
+            .text("Hello")
+            .build()?;
+        assert_eq!(
+            parser::render(&nodes, &variables),
+            "Translate into Russian.\nHello"
+        );
+
+        let variables = PromptVariablesBuilder::default()
+            .target_language("Russian")
+            .text("Hello")
+            .glossary("rifle = винтовка")
+            .build()?;
+        assert_eq!(
+            parser::render(&nodes, &variables),
+            "Translate into Russian.\nTerms:\nrifle = винтовка\nHello"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(parser::parse("no text variable").is_err());
+        assert!(parser::parse("{{unknown}} {{text}}").is_err());
+        assert!(parser::parse("{{#context}} {{text}}").is_err());
+        assert!(parser::parse("{{text").is_err());
+    }
+}
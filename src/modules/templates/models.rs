@@ -0,0 +1,142 @@
+use std::fmt;
+
+use derive_builder::Builder;
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::modules::templates::parser::Node;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Variable {
+    SourceLanguage,
+    TargetLanguage,
+    Text,
+    Glossary,
+    Context,
+    Formality,
+    Placeholders,
+}
+
+impl Variable {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "source_language" => Some(Self::SourceLanguage),
+            "target_language" => Some(Self::TargetLanguage),
+            "text" => Some(Self::Text),
+            "glossary" => Some(Self::Glossary),
+            "context" => Some(Self::Context),
+            "formality" => Some(Self::Formality),
+            "placeholders" => Some(Self::Placeholders),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Builder, Clone, Default, Getters, Debug)]
+#[builder(default, setter(into, strip_option))]
+#[getset(get = "pub")]
+pub struct PromptVariables {
+    source_language: String,
+    target_language: String,
+    text: String,
+    glossary: Option<String>,
+    context: Option<String>,
+    formality: Option<String>,
+    placeholders: Option<String>,
+}
+
+impl PromptVariables {
+    pub fn value(&self, variable: Variable) -> &str {
+        match variable {
+            Variable::SourceLanguage => &self.source_language,
+            Variable::TargetLanguage => &self.target_language,
+            Variable::Text => &self.text,
+            Variable::Glossary => self.glossary.as_deref().unwrap_or_default(),
+            Variable::Context => self.context.as_deref().unwrap_or_default(),
+            Variable::Formality => self.formality.as_deref().unwrap_or_default(),
+            Variable::Placeholders => self.placeholders.as_deref().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, CopyGetters, Debug)]
+#[getset(get_copy = "pub")]
+pub struct TemplateVersion {
+    number: u32,
+}
+
+impl TemplateVersion {
+    pub fn parse(stem: &str) -> Option<Self> {
+        let number = stem.strip_prefix('v')?.parse().ok()?;
+        Some(Self { number })
+    }
+}
+
+impl fmt::Display for TemplateVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.number)
+    }
+}
+
+#[derive(Clone, Getters, CopyGetters, Debug)]
+pub struct PromptTemplate {
+    #[getset(get = "pub")]
+    name: String,
+    #[getset(get_copy = "pub")]
+    version: TemplateVersion,
+    nodes: Vec<Node>,
+}
+
+impl PromptTemplate {
+    pub fn new(name: String, version: TemplateVersion, nodes: Vec<Node>) -> Self {
+        Self {
+            name,
+            version,
+            nodes,
+        }
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Identifier recorded with every translation, e.g. `translate@v2`.
+    pub fn version_tag(&self) -> String {
+        format!("{}@{}", self.name, self.version)
+    }
+}
+
+#[derive(Getters, Debug)]
+#[getset(get = "pub")]
+pub struct RenderedPrompt {
+    prompt: String,
+    version_tag: String,
+}
+
+impl RenderedPrompt {
+    pub fn new(prompt: String, version_tag: String) -> Self {
+        Self {
+            prompt,
+            version_tag,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Getters, ToSchema, Debug)]
+#[getset(get = "pub")]
+pub struct TemplateInfo {
+    name: String,
+    active_version: String,
+    versions: Vec<String>,
+}
+
+impl TemplateInfo {
+    pub fn new(name: String, active_version: String, versions: Vec<String>) -> Self {
+        Self {
+            name,
+            active_version,
+            versions,
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::modules::templates::errors::{TemplateErrors, TemplateResult};
+use crate::modules::templates::models::{PromptVariables, Variable};
+
+const TAG_OPEN: &str = "{{";
+const TAG_CLOSE: &str = "}}";
+
+#[derive(Clone, Debug)]
+pub enum Node {
+    Text(String),
+    Variable(Variable),
+    Section(Variable, Vec<Node>),
+}
+
+enum Token {
+    Text(String),
+    Variable(Variable),
+    SectionOpen(Variable),
+    SectionClose(Variable),
+}
+
+/// Parses template source into a node tree.
+///
+/// `{{name}}` inserts a variable, `{{#name}}...{{/name}}` renders its body only
+/// when the variable is not empty. A section tag standing alone on its line
+/// consumes the whole line, so optional blocks leave no blank lines behind.
+pub fn parse(source: &str) -> TemplateResult<Vec<Node>> {
+    let mut tokens = Vec::new();
+    for (line_idx, line) in source.split_inclusive('\n').enumerate() {
+        let line_number = line_idx + 1;
+        match standalone_section_tag(line, line_number)? {
+            Some(token) => tokens.push(token),
+            None => tokenize_line(line, line_number, &mut tokens)?,
+        }
+    }
+
+    let mut tokens = tokens.into_iter();
+    let nodes = build_tree(&mut tokens, None)?;
+    if !contains_variable(&nodes, Variable::Text) {
+        return Err(TemplateErrors::InvalidTemplate(
+            "template must contain the {{text}} variable".to_string(),
+        ));
+    }
+    Ok(nodes)
+}
+
+pub fn render(nodes: &[Node], variables: &PromptVariables) -> String {
+    let mut output = String::new();
+    render_into(nodes, variables, &mut output);
+    output
+}
+
+fn render_into(nodes: &[Node], variables: &PromptVariables, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Variable(variable) => output.push_str(variables.value(*variable)),
+            Node::Section(variable, children) => {
+                if !variables.value(*variable).trim().is_empty() {
+                    render_into(children, variables, output);
+                }
+            }
+        }
+    }
+}
+
+fn standalone_section_tag(line: &str, line_number: usize) -> TemplateResult<Option<Token>> {
+    let trimmed = line.trim();
+    if !(trimmed.starts_with(TAG_OPEN) && trimmed.ends_with(TAG_CLOSE)) {
+        return Ok(None);
+    }
+    let inner = &trimmed[TAG_OPEN.len()..trimmed.len() - TAG_CLOSE.len()];
+    if inner.contains(TAG_OPEN) || inner.contains(TAG_CLOSE) {
+        return Ok(None);
+    }
+    match parse_tag(inner, line_number)? {
+        token @ (Token::SectionOpen(_) | Token::SectionClose(_)) => Ok(Some(token)),
+        _ => Ok(None),
+    }
+}
+
+fn tokenize_line(line: &str, line_number: usize, tokens: &mut Vec<Token>) -> TemplateResult<()> {
+    let mut rest = line;
+    while let Some(start) = rest.find(TAG_OPEN) {
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_owned()));
+        }
+        let after_open = &rest[start + TAG_OPEN.len()..];
+        let Some(end) = after_open.find(TAG_CLOSE) else {
+            return Err(TemplateErrors::SyntaxError(format!(
+                "line {line_number}: unclosed tag"
+            )));
+        };
+        tokens.push(parse_tag(&after_open[..end], line_number)?);
+        rest = &after_open[end + TAG_CLOSE.len()..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_owned()));
+    }
+    Ok(())
+}
+
+fn parse_tag(inner: &str, line_number: usize) -> TemplateResult<Token> {
+    let inner = inner.trim();
+    let (kind, name) = match inner.chars().next() {
+        Some('#') => ('#', inner[1..].trim()),
+        Some('/') => ('/', inner[1..].trim()),
+        _ => (' ', inner),
+    };
+    let variable = Variable::from_name(name).ok_or_else(|| {
+        TemplateErrors::SyntaxError(format!("line {line_number}: unknown variable '{name}'"))
+    })?;
+    Ok(match kind {
+        '#' => Token::SectionOpen(variable),
+        '/' => Token::SectionClose(variable),
+        _ => Token::Variable(variable),
+    })
+}
+
+fn build_tree(
+    tokens: &mut impl Iterator<Item = Token>,
+    section: Option<Variable>,
+) -> TemplateResult<Vec<Node>> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(text) => nodes.push(Node::Text(text)),
+            Token::Variable(variable) => nodes.push(Node::Variable(variable)),
+            Token::SectionOpen(variable) => {
+                let children = build_tree(tokens, Some(variable))?;
+                nodes.push(Node::Section(variable, children));
+            }
+            Token::SectionClose(variable) if section == Some(variable) => return Ok(nodes),
+            Token::SectionClose(variable) => {
+                return Err(TemplateErrors::SyntaxError(format!(
+                    "unexpected closing tag for {variable:?}"
+                )));
+            }
+        }
+    }
+    match section {
+        Some(variable) => Err(TemplateErrors::SyntaxError(format!(
+            "section {variable:?} is not closed"
+        ))),
+        None => Ok(nodes),
+    }
+}
+
+fn contains_variable(nodes: &[Node], target: Variable) -> bool {
+    nodes.iter().any(|node| match node {
+        Node::Variable(variable) => *variable == target,
+        Node::Section(_, children) => contains_variable(children, target),
+        Node::Text(_) => false,
+    })
+}
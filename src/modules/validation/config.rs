@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+use getset::{CopyGetters, Getters};
+use serde::Deserialize;
+
+/// Allowed `translation / source` length ratio in characters.
+pub type LengthRatio = (f64, f64);
+
+#[derive(Clone, Deserialize, CopyGetters, Getters)]
+pub struct ValidationConfig {
+    #[getset(get_copy = "pub")]
+    enabled: bool,
+    #[getset(get_copy = "pub")]
+    max_retries: u32,
+    /// Texts with fewer letters are too short for language detection and ratio checks.
+    #[getset(get_copy = "pub")]
+    min_text_length: usize,
+    #[getset(get_copy = "pub")]
+    min_confidence: f64,
+    #[getset(get_copy = "pub")]
+    max_source_similarity: f64,
+    #[getset(get_copy = "pub")]
+    default_length_ratio: LengthRatio,
+    /// Ratios per language pair, keyed as `source-target`.
+    #[getset(get = "pub")]
+    #[serde(default)]
+    length_ratios: HashMap<String, LengthRatio>,
+}
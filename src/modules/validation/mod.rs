@@ -0,0 +1,172 @@
+pub mod config;
+pub mod models;
+
+use isolang::Language;
+use regex::Regex;
+
+use crate::modules::masking::PLACEHOLDER_PATTERN;
+use crate::modules::validation::config::{LengthRatio, ValidationConfig};
+use crate::modules::validation::models::{ValidationIssue, ValidationIssueKind};
+
+/// Checks that the model actually translated the text: output is in the
+/// target language, is not an echo of the source and has a plausible length.
+pub struct OutputValidator {
+    config: ValidationConfig,
+    placeholder: Regex,
+}
+
+impl OutputValidator {
+    pub fn new(config: &ValidationConfig) -> Result<Self, regex::Error> {
+        Ok(OutputValidator {
+            config: config.to_owned(),
+            placeholder: Regex::new(PLACEHOLDER_PATTERN)?,
+        })
+    }
+
+    pub fn config(&self) -> &ValidationConfig {
+        &self.config
+    }
+
+    /// Validates the masked source against the translation before
+    /// placeholders are restored, so protected spans don't skew the checks.
+    pub fn validate(
+        &self,
+        source_language: &str,
+        target_language: &str,
+        source: &str,
+        translation: &str,
+    ) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        if !self.config.enabled() {
+            return issues;
+        }
+
+        let source = self.placeholder.replace_all(source, " ");
+        let translation = self.placeholder.replace_all(translation, " ");
+        if translation.trim().is_empty() {
+            issues.push(ValidationIssue::new(
+                ValidationIssueKind::Untranslated,
+                "output is empty".to_owned(),
+            ));
+            return issues;
+        }
+        if letters_count(&source) < self.config.min_text_length() {
+            return issues;
+        }
+
+        if letters_count(&translation) >= self.config.min_text_length()
+            && let Some(info) = whatlang::detect(&translation)
+            && info.confidence() >= self.config.min_confidence()
+            && let Some(detected) = iso_639_1(info.lang())
+            && detected != target_language
+        {
+            issues.push(ValidationIssue::new(
+                ValidationIssueKind::WrongLanguage,
+                format!(
+                    "output language is '{detected}' (confidence {:.2}), expected '{target_language}'",
+                    info.confidence()
+                ),
+            ));
+        }
+
+        if source_language != target_language {
+            let similarity = strsim::sorensen_dice(&source, &translation);
+            if similarity >= self.config.max_source_similarity() {
+                issues.push(ValidationIssue::new(
+                    ValidationIssueKind::Unchanged,
+                    format!("output is {:.0}% similar to the source", similarity * 100.0),
+                ));
+            }
+        }
+
+        let ratio =
+            translation.trim().chars().count() as f64 / source.trim().chars().count() as f64;
+        let (min, max) = self.length_ratio(source_language, target_language);
+        if ratio < min || ratio > max {
+            issues.push(ValidationIssue::new(
+                ValidationIssueKind::LengthRatio,
+                format!(
+                    "length ratio {ratio:.2} is outside [{min}, {max}] for {source_language}-{target_language}"
+                ),
+            ));
+        }
+        issues
+    }
+
+    fn length_ratio(&self, source_language: &str, target_language: &str) -> LengthRatio {
+        self.config
+            .length_ratios()
+            .get(&format!("{source_language}-{target_language}"))
+            .copied()
+            .unwrap_or(self.config.default_length_ratio())
+    }
+}
+
+/// Extra system prompt instruction for a retry after rejected output.
+pub fn strict_instruction(target_language: &str, issues: &[ValidationIssue]) -> String {
+    let reasons: Vec<String> = issues.iter().map(ToString::to_string).collect();
+    format!(
+        "Your previous answer was rejected: {}.\nRespond only with the complete translation into {target_language}. \
+Never repeat the source text unchanged and never answer in any other language.",
+        reasons.join("; ")
+    )
+}
+
+fn letters_count(text: &str) -> usize {
+    text.chars().filter(|ch| ch.is_alphabetic()).count()
+}
+
+fn iso_639_1(lang: whatlang::Lang) -> Option<&'static str> {
+    match lang {
+        whatlang::Lang::Cmn => Some("zh"),
+        lang => Language::from_639_3(lang.code()).and_then(|language| language.to_639_1()),
+    }
+}
+
+#[cfg(test)]
+mod test_validation {
+    use crate::config::ServiceConfig;
+    use crate::modules::validation::OutputValidator;
+    use crate::modules::validation::models::ValidationIssueKind;
+
+    const SOURCE: &str =
+        "My rifle is my best friend. It is my life. I must master it as I must master my life.";
+
+    fn kinds(validator: &OutputValidator, translation: &str) -> Vec<ValidationIssueKind> {
+        validator
+            .validate("en", "ru", SOURCE, translation)
+            .iter()
+            .map(|issue| issue.kind())
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_output() -> Result<(), anyhow::Error> {
+        let service_config = ServiceConfig::new()?;
+        let validator = OutputValidator::new(service_config.validation())?;
+
+        let translation = "Моя винтовка — мой лучший друг. Это моя жизнь. Я должен овладеть ею, как я должен овладеть своей жизнью.";
+        assert!(kinds(&validator, translation).is_empty());
+
+        assert_eq!(
+            kinds(&validator, SOURCE),
+            vec![ValidationIssueKind::Unchanged]
+        );
+        assert_eq!(
+            kinds(&validator, " "),
+            vec![ValidationIssueKind::Untranslated]
+        );
+
+        let french = "Mon fusil est mon meilleur ami. C'est ma vie. Je dois le maîtriser comme je dois maîtriser ma vie.";
+        assert_eq!(
+            kinds(&validator, french),
+            vec![ValidationIssueKind::WrongLanguage]
+        );
+
+        assert_eq!(
+            kinds(&validator, "Моя винтовка."),
+            vec![ValidationIssueKind::LengthRatio]
+        );
+        Ok(())
+    }
+}
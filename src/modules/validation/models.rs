@@ -0,0 +1,34 @@
+use std::fmt;
+
+use getset::{CopyGetters, Getters};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValidationIssueKind {
+    WrongLanguage,
+    /// Output is empty.
+    Untranslated,
+    /// Output is nearly the source. Names, codes and cognates stay the same,
+    /// so it is accepted with a warning when retries don't change it.
+    Unchanged,
+    LengthRatio,
+}
+
+#[derive(Clone, Getters, CopyGetters, Debug)]
+pub struct ValidationIssue {
+    #[getset(get_copy = "pub")]
+    kind: ValidationIssueKind,
+    #[getset(get = "pub")]
+    detail: String,
+}
+
+impl ValidationIssue {
+    pub fn new(kind: ValidationIssueKind, detail: String) -> Self {
+        Self { kind, detail }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.detail)
+    }
+}
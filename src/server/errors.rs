@@ -5,8 +5,15 @@ use serde::Serialize;
 use thiserror::Error;
 use utoipa::ToSchema;
 
+use crate::modules::documents::errors::DocumentErrors;
+use crate::modules::formats::errors::FormatErrors;
+use crate::modules::glossary::errors::GlossaryErrors;
 use crate::modules::llm_client::errors::TranslatorErrors;
 use crate::modules::loader::errors::LoaderErrors;
+use crate::modules::pipeline::errors::PipelineErrors;
+use crate::modules::profiles::errors::ProfileErrors;
+use crate::modules::tables::errors::TableErrors;
+use crate::modules::templates::errors::TemplateErrors;
 use crate::server::swagger::SwaggerExample;
 
 pub type ServerResult<T> = Result<T, ServerError>;
@@ -27,6 +34,8 @@ pub enum ServerError {
     ModelModerationError(String),
     #[error("User is limited: {0}")]
     RateLimited(String),
+    #[error("Model refused to translate: {0}")]
+    Refused(String),
     #[error("Invalid Model Response: {0}")]
     InvalidResponse(String),
     #[error("IO Error")]
@@ -43,6 +52,10 @@ pub enum ServerError {
     RequestError(String),
     #[error("Unsupptored Language: {0}")]
     UnsupportedLanguage(String),
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
 }
 
 impl ServerError {
@@ -53,7 +66,8 @@ impl ServerError {
             ServerError::DeserializeError(msg) => (msg.to_owned(), StatusCode::BAD_GATEWAY),
             ServerError::IOError(msg) => (msg.to_owned(), StatusCode::NO_CONTENT),
             ServerError::RequestError(msg) => (msg.to_owned(), StatusCode::BAD_GATEWAY),
-            ServerError::InvalidResponse(msg) => (msg.to_owned(), StatusCode::NO_CONTENT),
+            ServerError::Refused(msg) => (msg.to_owned(), StatusCode::UNPROCESSABLE_ENTITY),
+            ServerError::InvalidResponse(msg) => (msg.to_owned(), StatusCode::BAD_GATEWAY),
             ServerError::NoCredits(msg) => (msg.to_owned(), StatusCode::PAYMENT_REQUIRED),
             ServerError::RateLimited(msg) => (msg.to_owned(), StatusCode::TOO_MANY_REQUESTS),
             ServerError::Timeout(msg) => (msg.to_owned(), StatusCode::REQUEST_TIMEOUT),
@@ -67,6 +81,10 @@ impl ServerError {
             ServerError::UnsupportedLanguage(msg) => {
                 (msg.to_owned(), StatusCode::UNPROCESSABLE_ENTITY)
             }
+            ServerError::PayloadTooLarge(msg) => (msg.to_owned(), StatusCode::PAYLOAD_TOO_LARGE),
+            ServerError::UnsupportedMediaType(msg) => {
+                (msg.to_owned(), StatusCode::UNSUPPORTED_MEDIA_TYPE)
+            }
         }
     }
 }
@@ -85,9 +103,15 @@ impl From<TranslatorErrors> for ServerError {
             TranslatorErrors::RequestError(_err) => {
                 ServerError::RequestError("Request Error".to_string())
             }
-            TranslatorErrors::InvalidResponse(_err) => ServerError::InvalidResponse(
-                "Invalid Response from generate API. See the logs".to_string(),
-            ),
+            TranslatorErrors::Refusal(err) => {
+                ServerError::Refused(format!("Model refused to translate: {err}"))
+            }
+            TranslatorErrors::InvalidResponse(err) => {
+                ServerError::InvalidResponse(format!("Invalid response from model: {err}"))
+            }
+            TranslatorErrors::RejectedOutput(err) => {
+                ServerError::InvalidResponse(format!("Model output rejected: {err}"))
+            }
             TranslatorErrors::ModelModerationError(_err) => ServerError::ModelModerationError(
                 "Model API is on moderation.Try another model".to_string(),
             ),
@@ -127,6 +151,131 @@ impl From<LoaderErrors> for ServerError {
     }
 }
 
+impl From<ProfileErrors> for ServerError {
+    fn from(err: ProfileErrors) -> Self {
+        tracing::error!("Error: {err}", err = err.to_string());
+        match err {
+            ProfileErrors::NotFound(err) => {
+                ServerError::NotFound(format!("Profile not found: {err}"))
+            }
+            ProfileErrors::InvalidProfile(_err) | ProfileErrors::IOError(_err) => {
+                ServerError::InternalError("Profile configuration error".to_string())
+            }
+        }
+    }
+}
+
+impl From<FormatErrors> for ServerError {
+    fn from(err: FormatErrors) -> Self {
+        tracing::error!("Error: {err}", err = err.to_string());
+        match err {
+            FormatErrors::Malformed(err) => {
+                ServerError::BadRequest(format!("Malformed document: {err}"))
+            }
+            FormatErrors::StructureChanged(err) => {
+                ServerError::InvalidResponse(format!("markup structure changed: {err}"))
+            }
+        }
+    }
+}
+
+impl From<DocumentErrors> for ServerError {
+    fn from(err: DocumentErrors) -> Self {
+        tracing::error!("Error: {err}", err = err.to_string());
+        match err {
+            DocumentErrors::Unsupported(err) => {
+                ServerError::UnsupportedMediaType(format!("Unsupported document type: {err}"))
+            }
+            DocumentErrors::TooLarge(size) => {
+                ServerError::PayloadTooLarge(format!("Document is larger than {size} bytes"))
+            }
+            DocumentErrors::Malformed(err) => {
+                ServerError::BadRequest(format!("Malformed document: {err}"))
+            }
+            DocumentErrors::InvalidOption(err) => {
+                ServerError::BadRequest(format!("Invalid document option: {err}"))
+            }
+            DocumentErrors::SpecifiersChanged(err) => {
+                ServerError::InvalidResponse(format!("format specifiers changed: {err}"))
+            }
+        }
+    }
+}
+
+impl From<TableErrors> for ServerError {
+    fn from(err: TableErrors) -> Self {
+        tracing::error!("Error: {err}", err = err.to_string());
+        match err {
+            TableErrors::Malformed(err) => {
+                ServerError::BadRequest(format!("Malformed table: {err}"))
+            }
+            TableErrors::UnknownColumn(err) => {
+                ServerError::BadRequest(format!("Unknown column: {err}"))
+            }
+            TableErrors::InvalidOption(err) => {
+                ServerError::BadRequest(format!("Invalid table option: {err}"))
+            }
+            TableErrors::MissingTranslation(_err) => {
+                ServerError::InternalError("Table cell was not translated".to_string())
+            }
+        }
+    }
+}
+
+impl From<TemplateErrors> for ServerError {
+    fn from(err: TemplateErrors) -> Self {
+        tracing::error!("Error: {err}", err = err.to_string());
+        match err {
+            TemplateErrors::NotFound(err) => {
+                ServerError::InternalError(format!("Prompt template not found: {err}"))
+            }
+            TemplateErrors::SyntaxError(err) | TemplateErrors::InvalidTemplate(err) => {
+                ServerError::BadRequest(format!("Invalid prompt template: {err}"))
+            }
+            TemplateErrors::IOError(_err) => {
+                ServerError::InternalError("Error reading prompt templates".to_string())
+            }
+        }
+    }
+}
+
+impl From<GlossaryErrors> for ServerError {
+    fn from(err: GlossaryErrors) -> Self {
+        tracing::error!("Error: {err}", err = err.to_string());
+        match err {
+            GlossaryErrors::NotFound(id) => {
+                ServerError::NotFound(format!("Glossary entry {id} not found"))
+            }
+            GlossaryErrors::InvalidEntry(err) | GlossaryErrors::ImportError(err) => {
+                ServerError::BadRequest(err)
+            }
+            GlossaryErrors::IOError(_err) => {
+                ServerError::IOError("Glossary storage error".to_string())
+            }
+            GlossaryErrors::AnotherError(_err) => {
+                ServerError::InternalError("Internal Server Error".to_string())
+            }
+        }
+    }
+}
+
+impl From<PipelineErrors> for ServerError {
+    fn from(err: PipelineErrors) -> Self {
+        match err {
+            PipelineErrors::Translator(err) => err.into(),
+            PipelineErrors::Profile(err) => err.into(),
+            PipelineErrors::Template(err) => err.into(),
+            PipelineErrors::Format(err) => err.into(),
+            PipelineErrors::Document(err) => err.into(),
+            PipelineErrors::Table(err) => err.into(),
+            PipelineErrors::AnotherError(err) => {
+                tracing::error!("Error: {err}");
+                ServerError::InternalError("Internal server error".to_string())
+            }
+        }
+    }
+}
+
 impl IntoResponse for ServerError {
     fn into_response(self) -> Response {
         #[derive(Serialize)]
@@ -6,6 +6,7 @@ pub mod swagger;
 use std::sync::Arc;
 
 use axum::Router;
+use axum::extract::DefaultBodyLimit;
 use axum::response::Html;
 use axum::routing::{get, post};
 use axum_prometheus::PrometheusMetricLayer;
@@ -15,12 +16,13 @@ use utoipa_swagger_ui::SwaggerUi;
 
 use crate::config::ServiceConfig;
 use crate::modules::llm_client::LLMClient;
+use crate::modules::pipeline::TranslationPipeline;
 
 pub struct AppState<R>
 where
     R: LLMClient + ?Sized + Send + Sync,
 {
-    llm_client: Arc<R>,
+    pipeline: Arc<TranslationPipeline<R>>,
     config: Arc<ServiceConfig>,
 }
 
@@ -28,8 +30,8 @@ impl<R> AppState<R>
 where
     R: LLMClient + ?Sized + Send + Sync,
 {
-    pub fn new(llm_client: Arc<R>, config: Arc<ServiceConfig>) -> Self {
-        AppState { llm_client, config }
+    pub fn new(pipeline: Arc<TranslationPipeline<R>>, config: Arc<ServiceConfig>) -> Self {
+        AppState { pipeline, config }
     }
 }
 
@@ -47,10 +49,39 @@ where
             "/api/v1/translate/text",
             post(router::llm_client::translate_text),
         )
+        .route(
+            "/api/v1/translate/document",
+            // Uploads are limited while streaming, by the configured document size.
+            post(router::documents::translate_document).layer(DefaultBodyLimit::disable()),
+        )
+        .route(
+            "/api/v1/translate/table",
+            // Tables are streamed, so their size is not limited.
+            post(router::tables::translate_table).layer(DefaultBodyLimit::disable()),
+        )
         .route(
             "/api/v1/loader/model-garden",
             get(router::loader::get_available_languages),
         )
+        .route(
+            "/api/v1/glossary",
+            get(router::glossary::list_glossary).post(router::glossary::create_glossary_entry),
+        )
+        .route(
+            "/api/v1/glossary/import",
+            post(router::glossary::import_glossary),
+        )
+        .route(
+            "/api/v1/glossary/{id}",
+            get(router::glossary::get_glossary_entry)
+                .put(router::glossary::update_glossary_entry)
+                .delete(router::glossary::delete_glossary_entry),
+        )
+        .route("/api/v1/templates", get(router::templates::get_templates))
+        .route(
+            "/api/v1/templates/reload",
+            post(router::templates::reload_templates),
+        )
         .route("/metrics", get(|| async move { metric_handle.render() }))
         .layer(prometheus_layer)
         .with_state(app_arc)
@@ -0,0 +1,315 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::extract::multipart::{Field, Multipart, MultipartError};
+use axum::http::{HeaderMap, HeaderValue, header};
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use serde::de::IntoDeserializer;
+
+use crate::errors::ErrorResponse;
+use crate::modules::documents::models::{DocumentOptions, SpreadsheetOutput};
+use crate::modules::documents::spreadsheet::CellRange;
+use crate::modules::formality::models::Formality;
+use crate::modules::llm_client::LLMClient;
+use crate::modules::llm_client::models::TranslateTask;
+use crate::server::AppState;
+use crate::server::errors::{ServerError, ServerResult};
+use crate::server::router::llm_client::check_translate_is_available;
+use crate::server::router::models::DocumentUpload;
+
+/// Largest accepted value of a text field of the form.
+const MAX_FIELD_SIZE: usize = 4096;
+
+/// Response header repeated for every warning about the translated document.
+const DOCUMENT_WARNING_HEADER: &str = "x-document-warning";
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/translate/document",
+    request_body(content = DocumentUpload, content_type = "multipart/form-data"),
+    tags = ["Translator"],
+    description = r#"
+## Translate document
+
+Translate an uploaded file and return it with the original name and content type.
+
+### Form fields
+- `file` (file): Document to translate
+- `source_language` (string, ISO-639): Source language of the document
+- `target_language` (string, ISO-639): Target language of the document
+- `profile` (string, optional): Domain profile
+- `formality` (string, optional): `default`, `formal` or `informal` form of address
+- `session_id` (string, optional): Session the document continues: its latest translations
+  are passed as context. Segments of the document are not added to the session
+- `sheets` (string, optional): Comma-separated names of the spreadsheet sheets to translate
+- `ranges` (string, optional): Comma-separated spreadsheet cells to translate in A1 notation:
+  columns (`B`, `B:D`), rows (`2:40`) or cells (`A2:C40`), optionally with a sheet
+  (`Terms!B:B`, `'Q1 report'!A:A`). Without a sheet list, ranges with a sheet also pick the sheet
+- `output` (string, optional): `overwrite` (default) replaces the text of spreadsheet cells,
+  `new_column` writes translations to a new column right of every translated column
+- `existing_target` (file, optional): Earlier translation of a JSON or YAML resource; only keys
+  missing from it are translated and the translations it has are kept
+- `max_line_chars` (integer, optional): Longest subtitle line; translated cues are wrapped to it
+- `max_chars_per_second` (number, optional): Highest subtitle reading speed
+
+The format is chosen by the content type of the file part, or by the file name extension when
+the content type is missing or `application/octet-stream`:
+
+- `text/plain` (`.txt`), `text/markdown` (`.md`), `text/html` (`.html`, `.htm`) and
+  `application/xml` (`.xml`): UTF-8 text translated as the `plain`, `markdown`, `html` and `xml`
+  text formats
+- `.docx`: paragraphs of the body, tables, headers, footers, footnotes, endnotes and comments.
+  Adjacent runs with the same formatting are merged; formatting changes, hyperlinks, bookmarks
+  and fields inside a paragraph are passed to the model as tags and put back around the
+  translated text. Paragraph and run properties, numbering and tables are kept
+- `.pptx`: text frames, tables, chart titles and speaker notes of every slide, in slide order,
+  with runs merged by formatting as in `.docx`. Layouts and masters are kept as they are. Text
+  boxes whose translation is longer than the source by more than the configured ratio are
+  reported as likely to overflow, unless they resize to fit their text
+- `.xlsx` and `.ods`: text cells of the selected sheets and ranges, all of them by default.
+  Formulas, numbers, dates and booleans are not translated. Rich text runs of `.xlsx` cells are
+  merged by formatting as in `.docx`; `.ods` cells are translated as XML. With `new_column`,
+  a translation goes to the first column right of its source column that holds no values,
+  keeping the style of the source cell
+- `application/x-subrip` (`.srt`) and `text/vtt` (`.vtt`): subtitle cues. Cue numbers,
+  timestamps, cue settings, notes and styling tags wrapping a cue (`<i>`, `{\an8}`, `<v Name>`)
+  are kept. Cues that split a sentence are translated together and the translation is cut
+  back across them at word boundaries in proportion to their duration. Cues that read faster
+  than `max_chars_per_second` are lengthened into the pause before the next cue
+- `text/x-gettext-translation` (`.po`, `.pot`): entries with an empty `msgstr` or the `fuzzy`
+  flag. Plural entries get the translated singular in `msgstr[0]` and the translated plural in
+  the other forms, as many as `nplurals` of the header. Filled entries lose the `fuzzy` flag and
+  their `#|` previous strings; comments, references, contexts and other flags are kept
+- `application/xliff+xml` (`.xlf`, `.xliff`), versions 1.2 and 2.0: units without a target,
+  with an empty one or in a state asking for translation (`new`, `needs-*` in 1.2, `initial`
+  in 2.0). Inline tags such as `<g>`, `<x/>`, `<pc>` and `<ph/>` are kept; filled targets
+  (1.2) or segments (2.0) are marked `translated`, units with `translate="no"` are skipped
+- `application/json` (`.json`) and `application/yaml` (`.yaml`, `.yml`): nested i18n resources
+  (i18next, vue-i18n, Rails). String leaves are translated; keys, ordering, comments, numbers,
+  booleans and plural sub-keys (`one`/`other`, `_one`/`_other`) are kept. Interpolation such as
+  `{{name}}`, `%{count}`, `{0}`, `$t(key)`, `@:key` and `%s` is passed to the model as tags and
+  put back; ICU messages with plural or select arguments are translated as the `icu` text
+  format. A single top-level key naming the source language (Rails) is renamed to the
+  target language. With `existing_target`, the result follows the source file with the
+  translations of the existing file kept; keys only found in the existing file are dropped
+- `text/x-fluent` (`.ftl`): values and attributes of every message and term, with placeables
+  kept and every variant of a select expression translated on its own
+- Android `strings.xml` (also `arrays.xml`, `plurals.xml`, by file name): `<string>`, the items
+  of `<string-array>` and every quantity of `<plurals>`. Resources with `translatable="false"`
+  are skipped; `<xliff:g>` spans, `\n` escapes and format specifiers such as `%1$s` are kept
+- Apple `.strings` (UTF-8 or UTF-16) and `.xcstrings` string catalogs: every value, or every
+  source string unit of a catalog including plural and device variations, is translated; the
+  catalog gets `translated` units of the target language where it has none
+
+Format specifiers of Android and Apple strings (`%1$s`, `%d`, `%@`, `%lld`) must come back with
+the same types in the same order, numbered ones by their number; otherwise the document is
+rejected with `502`.
+
+Warnings about the translated document, such as slide text likely to overflow its box, are
+returned in `X-Document-Warning` response headers, one header per warning. Subtitle cues that
+still read too fast or have lines longer than `max_line_chars` are reported the same way.
+A segment whose translation is refused or rejected by validation keeps its source text and is
+reported as a warning too; the rest of the document is still translated.
+
+Segments of a document are translated in order; earlier segments and their translations are
+passed to the model as context. Files larger than the configured limit are rejected with `413`
+while uploading; unknown types are rejected with `415`.
+
+"#,
+    responses(
+        (status = 200, description="### Translated document", content_type = "application/octet-stream", body = Vec<u8>),
+        (status = 400, description="### Bad request or malformed document", body = ErrorResponse),
+        (status = 404, description="### Requested profile is not defined", body = ErrorResponse),
+        (status = 413, description="### Document is too large", body = ErrorResponse),
+        (status = 415, description="### Unsupported document type", body = ErrorResponse),
+        (status = 422, description="### Unsupported language or model refused to translate", body = ErrorResponse),
+        (status = 500, description="### Internal Server error", body = ErrorResponse),
+        (status = 502, description="### Model output rejected by validation", body = ErrorResponse),
+    )
+)]
+pub async fn translate_document<R>(
+    State(state): State<Arc<AppState<R>>>,
+    mut multipart: Multipart,
+) -> ServerResult<impl IntoResponse>
+where
+    R: LLMClient + Send + Sync + ?Sized,
+{
+    let max_file_size = state.pipeline.documents().config().max_file_size();
+    let mut task = TranslateTask::default();
+    let mut file = None;
+    let mut source_language = None;
+    let mut target_language = None;
+    let mut sheets = Vec::new();
+    let mut ranges = Vec::new();
+    let mut output = SpreadsheetOutput::default();
+    let mut max_line_chars = None;
+    let mut max_chars_per_second = None;
+    let mut existing_target = None;
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        let name = field.name().unwrap_or_default().to_owned();
+        match name.as_str() {
+            "file" => {
+                let file_name = field.file_name().unwrap_or("document").to_owned();
+                let content_type = field.content_type().map(str::to_owned);
+                let data = read_field(field, max_file_size).await?;
+                file = Some((file_name, content_type, data));
+            }
+            "existing_target" => existing_target = Some(read_field(field, max_file_size).await?),
+            "source_language" => source_language = Some(read_text(field).await?),
+            "target_language" => target_language = Some(read_text(field).await?),
+            "profile" => {
+                task.set_profile(Some(read_text(field).await?));
+            }
+            "session_id" => {
+                task.set_session_id(Some(read_text(field).await?));
+            }
+            "formality" => {
+                task.set_formality(formality(&read_text(field).await?)?);
+            }
+            "sheets" => sheets.extend(list(&read_text(field).await?)),
+            "ranges" => {
+                for range in list(&read_text(field).await?) {
+                    ranges.push(range.parse::<CellRange>()?);
+                }
+            }
+            "output" => {
+                let value = read_text(field).await?;
+                output = SpreadsheetOutput::deserialize(value.as_str().into_deserializer())
+                    .map_err(|err: serde::de::value::Error| {
+                        ServerError::BadRequest(format!("Invalid output: {err}"))
+                    })?;
+            }
+            "max_line_chars" => {
+                max_line_chars = Some(number(&name, &read_text(field).await?)?);
+            }
+            "max_chars_per_second" => {
+                max_chars_per_second = Some(number(&name, &read_text(field).await?)?);
+            }
+            _ => tracing::debug!(field = name, "Skipping unknown form field"),
+        }
+    }
+    let (Some((file_name, content_type, data)), Some(source_language), Some(target_language)) =
+        (file, source_language, target_language)
+    else {
+        return Err(ServerError::BadRequest(
+            "Form requires `file`, `source_language` and `target_language` fields".to_string(),
+        ));
+    };
+    let mut options = DocumentOptions::new(sheets, ranges, output)
+        .with_subtitle_limits(max_line_chars, max_chars_per_second)
+        .with_languages(source_language.clone(), target_language.clone());
+    if let Some(existing_target) = existing_target {
+        options = options.with_existing_target(existing_target);
+    }
+    task.set_source_language(source_language);
+    task.set_target_language(target_language);
+
+    let available_languages = state.config.server().allowed_languages().to_owned();
+    if !check_translate_is_available(&task, available_languages) {
+        return Err(ServerError::UnsupportedLanguage(
+            "Указанный язык не поддерживается".to_string(),
+        ));
+    }
+
+    let document = state
+        .pipeline
+        .translate_document(task, &file_name, content_type.as_deref(), &data, &options)
+        .await?;
+    let headers = [
+        (header::CONTENT_TYPE, document.content_type().to_owned()),
+        (
+            header::CONTENT_DISPOSITION,
+            content_disposition(document.file_name()),
+        ),
+    ];
+    let mut warnings = HeaderMap::new();
+    for warning in document.warnings() {
+        let warning: String = warning
+            .chars()
+            .map(|ch| if ch.is_control() { ' ' } else { ch })
+            .collect();
+        if let Ok(value) = HeaderValue::from_bytes(warning.as_bytes()) {
+            warnings.append(DOCUMENT_WARNING_HEADER, value);
+        }
+    }
+    Ok((headers, warnings, document.data().to_owned()))
+}
+
+/// Reads a form field chunk by chunk and stops as soon as it exceeds `limit` bytes.
+async fn read_field(mut field: Field<'_>, limit: usize) -> ServerResult<Vec<u8>> {
+    let mut data = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+        if data.len() + chunk.len() > limit {
+            return Err(ServerError::PayloadTooLarge(format!(
+                "Document is larger than {limit} bytes"
+            )));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
+pub(super) async fn read_text(field: Field<'_>) -> ServerResult<String> {
+    let data = read_field(field, MAX_FIELD_SIZE).await?;
+    String::from_utf8(data)
+        .map(|value| value.trim().to_owned())
+        .map_err(|_| ServerError::BadRequest("Form field is not UTF-8".to_string()))
+}
+
+pub(super) fn formality(value: &str) -> ServerResult<Formality> {
+    Formality::deserialize(value.into_deserializer()).map_err(|err: serde::de::value::Error| {
+        ServerError::BadRequest(format!("Invalid formality: {err}"))
+    })
+}
+
+/// Items of a comma-separated field value.
+pub(super) fn list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Positive number of a form field.
+fn number<T>(name: &str, value: &str) -> ServerResult<T>
+where
+    T: FromStr + PartialOrd + Default,
+{
+    value
+        .trim()
+        .parse::<T>()
+        .ok()
+        .filter(|number| *number > T::default())
+        .ok_or_else(|| {
+            ServerError::BadRequest(format!("Invalid {name}: expected a positive number"))
+        })
+}
+
+pub(super) fn multipart_error(err: MultipartError) -> ServerError {
+    ServerError::BadRequest(format!("Invalid multipart form: {}", err.body_text()))
+}
+
+/// `attachment` with an ASCII fallback name and the exact name per RFC 5987.
+pub(super) fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|ch| match ch {
+            ' '..='~' if ch != '"' && ch != '\\' => ch,
+            _ => '_',
+        })
+        .collect();
+    let encoded: String = file_name
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect();
+    format!("attachment; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
+}
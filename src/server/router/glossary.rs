@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use axum::extract::{Json, Path, Query, State};
+use axum::response::IntoResponse;
+
+use crate::errors::ErrorResponse;
+use crate::modules::glossary::models::{
+    GlossaryEntry, GlossaryFilter, GlossaryImportParams, GlossaryTerm, ImportReport,
+};
+use crate::modules::llm_client::LLMClient;
+use crate::server::AppState;
+use crate::server::errors::ServerResult;
+use crate::server::router::models::GlossaryResponse;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/glossary",
+    tags = ["Glossary"],
+    params(GlossaryFilter),
+    description = r#"
+## Getting glossary entries
+
+Get approved terms, optionally filtered by language pair and profile
+
+"#,
+    responses(
+        (status = 200, description="### Glossary entries", body = GlossaryResponse),
+        (status = 500, description="### Internal Server error", body = ErrorResponse)
+    )
+)]
+pub async fn list_glossary<R>(
+    State(state): State<Arc<AppState<R>>>,
+    Query(filter): Query<GlossaryFilter>,
+) -> ServerResult<impl IntoResponse>
+where
+    R: LLMClient + Send + Sync + ?Sized,
+{
+    let entries = state.pipeline.glossary().list(&filter).await;
+    Ok(Json(GlossaryResponse::new(entries)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/glossary/{id}",
+    tags = ["Glossary"],
+    params(("id" = u64, Path, description = "Glossary entry id")),
+    description = r#"
+## Getting glossary entry
+
+"#,
+    responses(
+        (status = 200, description="### Glossary entry", body = GlossaryEntry),
+        (status = 404, description="### Entry not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_glossary_entry<R>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(id): Path<u64>,
+) -> ServerResult<impl IntoResponse>
+where
+    R: LLMClient + Send + Sync + ?Sized,
+{
+    let entry = state.pipeline.glossary().get(id).await?;
+    Ok(Json(entry))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/glossary",
+    tags = ["Glossary"],
+    request_body = GlossaryTerm,
+    description = r#"
+## Creating glossary entry
+
+### Arguments
+- `source_language` (string, ISO-639): Source language of term
+- `target_language` (string, ISO-639): Target language of term
+- `profile` (string, optional): Profile the term is scoped to. Applies to all profiles when omitted
+- `source_term` (string): Term in source language
+- `target_term` (string): Approved translation of the term
+- `case_sensitive` (bool, optional): Match the source term case-sensitively
+- `note` (string, optional): Comment for terminologists
+
+"#,
+    responses(
+        (status = 200, description="### Created glossary entry", body = GlossaryEntry),
+        (status = 400, description="### Invalid glossary entry", body = ErrorResponse),
+        (status = 500, description="### Internal Server error", body = ErrorResponse)
+    )
+)]
+pub async fn create_glossary_entry<R>(
+    State(state): State<Arc<AppState<R>>>,
+    Json(term): Json<GlossaryTerm>,
+) -> ServerResult<impl IntoResponse>
+where
+    R: LLMClient + Send + Sync + ?Sized,
+{
+    let entry = state.pipeline.glossary().create(term).await?;
+    Ok(Json(entry))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/glossary/{id}",
+    tags = ["Glossary"],
+    params(("id" = u64, Path, description = "Glossary entry id")),
+    request_body = GlossaryTerm,
+    description = r#"
+## Updating glossary entry
+
+"#,
+    responses(
+        (status = 200, description="### Updated glossary entry", body = GlossaryEntry),
+        (status = 400, description="### Invalid glossary entry", body = ErrorResponse),
+        (status = 404, description="### Entry not found", body = ErrorResponse),
+    )
+)]
+pub async fn update_glossary_entry<R>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(id): Path<u64>,
+    Json(term): Json<GlossaryTerm>,
+) -> ServerResult<impl IntoResponse>
+where
+    R: LLMClient + Send + Sync + ?Sized,
+{
+    let entry = state.pipeline.glossary().update(id, term).await?;
+    Ok(Json(entry))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/glossary/{id}",
+    tags = ["Glossary"],
+    params(("id" = u64, Path, description = "Glossary entry id")),
+    description = r#"
+## Deleting glossary entry
+
+"#,
+    responses(
+        (status = 200, description="### Deleted glossary entry", body = GlossaryEntry),
+        (status = 404, description="### Entry not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_glossary_entry<R>(
+    State(state): State<Arc<AppState<R>>>,
+    Path(id): Path<u64>,
+) -> ServerResult<impl IntoResponse>
+where
+    R: LLMClient + Send + Sync + ?Sized,
+{
+    let entry = state.pipeline.glossary().delete(id).await?;
+    Ok(Json(entry))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/glossary/import",
+    tags = ["Glossary"],
+    params(GlossaryImportParams),
+    request_body(content = String, content_type = "text/plain"),
+    description = r#"
+## Importing term base
+
+Import terms into the language pair and profile given in query parameters.
+
+- `csv`: rows of `source_term,target_term[,note]`, comma or tab separated, optional header
+- `tbx`: TBX term base. Terms of `source_language` and `target_language` are taken from every entry
+
+Existing terms of the same scope are replaced.
+
+"#,
+    responses(
+        (status = 200, description="### Import report", body = ImportReport),
+        (status = 400, description="### Malformed term base", body = ErrorResponse),
+        (status = 500, description="### Internal Server error", body = ErrorResponse)
+    )
+)]
+pub async fn import_glossary<R>(
+    State(state): State<Arc<AppState<R>>>,
+    Query(params): Query<GlossaryImportParams>,
+    body: String,
+) -> ServerResult<impl IntoResponse>
+where
+    R: LLMClient + Send + Sync + ?Sized,
+{
+    let report = state
+        .pipeline
+        .glossary()
+        .import(params.format(), &params.scope(), &body)
+        .await?;
+    Ok(Json(report))
+}
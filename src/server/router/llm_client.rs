@@ -10,7 +10,7 @@ use crate::server::AppState;
 use crate::server::errors::{ServerError, ServerResult};
 use crate::server::router::models::{TextTransaltorRequest, TextTransaltorResponse};
 
-fn check_translate_is_available(
+pub(super) fn check_translate_is_available(
     transalte_task: &TranslateTask,
     available_languages: Vec<String>,
 ) -> bool {
@@ -39,6 +39,69 @@ Translate text to following language using LLM
 - `source_language` (string, ISO-639): Source language of text
 - `target_language` (string, ISO-639): Target language of text.
 - `text` (string): Text to translate
+- `profile` (string, optional): Domain profile (system prompt, sampling, pass-through rules). Default profile is used when omitted. Available profiles are listed in the model garden
+- `format` (string, optional): `plain` (default), `markdown`, `html`, `xml` or `icu`
+- `formality` (string, optional): `default`, `formal` or `informal` form of address. Applies to
+  targets with `supports_formality` in the model garden, ignored for the others
+- `context` (object, optional): `preceding` and `following` source segments and earlier
+  `translations` (`source`, `translation`), passed to the model as read-only context
+- `session_id` (string, optional): latest translations of earlier requests with the same ID and
+  language pair are added to the context, and this translation is remembered for the next ones
+- `back_translation` (bool, optional): translate the output back to the source language
+- `confidence` (bool, optional): request token log probabilities and return model confidence
+
+In `markdown` format only the text of paragraphs, headings, list items and table cells is
+translated, block by block. Emphasis, link syntax with URLs, inline code and inline HTML are
+passed to the model as placeholders. Code blocks, HTML blocks, front matter, list numbering and
+table syntax are not sent at all, and everything outside the translated text is returned byte for byte.
+
+In `html` and `xml` formats text runs and `alt`/`title` attributes are translated. Inline tags
+(`<b>`, `<a>`, `<br>` in HTML; any element inside mixed content in XML) are passed as placeholders.
+Elements with `translate="no"`, a `lang`/`xml:lang` other than the source language, and HTML
+`script`, `style`, `code` and `pre` are kept as they are. Malformed XML is rejected with `400`;
+a translation whose tag structure differs from the source is returned as `502`.
+
+In `icu` format the text is an ICU MessageFormat message. Only literal text is translated:
+arguments inside it are placeholders, and every branch of `plural`, `selectordinal` and
+`select` arguments is translated on its own. Plural arguments get the categories the target
+language needs (`one`, `few`, `many`, `other` for Russian) from the `other` branch, and `#`
+is shown to the model as a number of the category. The result is quoted where needed; a
+malformed message is rejected with `400`, a result that lost or renamed an argument is returned as `502`.
+
+Glossary terms of the language pair and profile found in the text are passed to the model.
+Approved terms missing in the translation are returned in `glossary_violations`.
+
+URLs, e-mails, code, numbers with units and spans marked as `<dnt>...</dnt>` are replaced
+with placeholders before translation and restored afterwards. Placeholders the model lost,
+duplicated or altered are returned in `placeholder_issues`.
+
+Numbers, dates, coordinates, unit values, acronyms and designations (`T-72`) are compared between
+the source and the translation after normalization (`1,200.5` and `1 200,5` are equal). Values
+occurring a different number of times are returned in `consistency_warnings`. Depending on the
+configuration such translation is also retried or rejected.
+
+With `formality` set, the translation is checked for pronouns and verb forms of the other
+register (`ты` in a formal Russian text, `Sie` in an informal German one). Such translation is
+retried with a stricter instruction; forms remaining are returned in `formality_issues`.
+
+With `back_translation` the translation is translated back with the same profile and compared
+with the source by chrF (character n-gram F-score, 0 to 100). The back-translated text and the
+score are returned in `back_translation`; its tokens are included in `usage`.
+
+With `confidence` the model is asked for log probabilities of the output tokens. `confidence`
+holds the geometric mean token probability of the whole translation and of each translated
+segment, every token with its probability, and `low_confidence_spans`: adjacent tokens below the
+configured probability, merged. Offsets are in characters of the translated `text`, end exclusive.
+Tokens of placeholders and of output removed by sanitizing are not scored. Backends without
+log probabilities return no `confidence`.
+
+Output cut by the `max_tokens` limit is continued in follow-up requests and joined. Tokens spent
+on all requests of the translation are returned in `usage`.
+
+Output in a wrong language, an echo of the source or output of implausible length is retried
+with a stricter prompt (on the fallback provider when configured). Output rejected on every
+attempt is returned as `502` with the reasons. Output that stays nearly equal to the source,
+as names, codes and cognates do, is returned with the reason in `validation_warnings` instead.
 
 "#,
     responses(
@@ -48,10 +111,12 @@ Translate text to following language using LLM
         (status = 401, description="### Unauth user on target API", body = ErrorResponse),
         (status = 402, description="### No credits on target API", body = ErrorResponse),
         (status = 403, description="### Model in target API is on moderation",body = ErrorResponse),
+        (status = 404, description="### Requested profile is not defined", body = ErrorResponse),
         (status = 408, description="### Timeout on target API", body = ErrorResponse),
+        (status = 422, description="### Model refused to translate the text", body = ErrorResponse),
         (status = 429, description="### Too many requests", body = ErrorResponse),
         (status = 500, description="### Internal Server error", body = ErrorResponse),
-        (status = 502, description="### Deserialization Error. Response of model has no `image` field", body = ErrorResponse),
+        (status = 502, description="### Deserialization Error or model output rejected by validation", body = ErrorResponse),
         (status = 503, description="### Provider of target API is not available", body = ErrorResponse),
     )
 )]
@@ -69,7 +134,7 @@ where
             "Указанный язык не поддерживается".to_string(),
         ));
     }
-    let translated_text = state.llm_client.translate(task).await?;
-    let translated_response = TextTransaltorResponse::new(translated_text);
+    let translation = state.pipeline.translate(task).await?;
+    let translated_response = TextTransaltorResponse::from(translation);
     Ok(Json(translated_response))
 }
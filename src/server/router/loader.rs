@@ -17,7 +17,8 @@ use crate::server::errors::ServerResult;
     description = r#"
 ## Getting allowed languages
  
-Get all the combinations of allowed languages
+Get all the combinations of allowed languages and the available translation profiles.
+Targets marked with `supports_formality` accept the `formality` option of translate requests.
 
 Allowed languages: `["ru", "en", "fr", "uk", "ar", "de", "es", "it", "zh", "pl", "he", "ja", "tr", "pt", "ko", "cs"]`
 
@@ -34,6 +35,6 @@ where
     R: LLMClient + Send + Sync + ?Sized,
 {
     let config = state.config.clone();
-    let allowed_combinations = model_garden(&config).await?;
+    let allowed_combinations = model_garden(&config, state.pipeline.profiles()).await?;
     Ok(Json(allowed_combinations))
 }
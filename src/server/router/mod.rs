@@ -1,3 +1,7 @@
+pub mod documents;
+pub mod glossary;
 pub mod llm_client;
 pub mod loader;
 pub mod models;
+pub mod tables;
+pub mod templates;
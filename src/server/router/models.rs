@@ -2,6 +2,16 @@ use getset::Getters;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::modules::back_translation::models::BackTranslation;
+use crate::modules::confidence::models::Confidence;
+use crate::modules::consistency::models::ConsistencyWarning;
+use crate::modules::documents::models::SpreadsheetOutput;
+use crate::modules::formality::models::{Formality, FormalityIssue};
+use crate::modules::glossary::models::{GlossaryEntry, GlossaryViolation};
+use crate::modules::llm_client::models::TokenUsage;
+use crate::modules::masking::models::PlaceholderIssue;
+use crate::modules::pipeline::models::Translation;
+use crate::modules::templates::models::TemplateInfo;
 use crate::modules::{llm_client::models::TranslateTask, loader::models::units::ModelGarden};
 
 #[derive(Serialize, Deserialize, Getters, ToSchema)]
@@ -15,17 +25,122 @@ pub struct TextTransaltorRequest {
 #[getset(get = "pub")]
 pub struct TextTransaltorResponse {
     text: String,
+    profile: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template_version: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    glossary_violations: Vec<GlossaryViolation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    placeholder_issues: Vec<PlaceholderIssue>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    consistency_warnings: Vec<ConsistencyWarning>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    formality_issues: Vec<FormalityIssue>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    validation_warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    back_translation: Option<BackTranslation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confidence: Option<Confidence>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<TokenUsage>,
 }
 
-impl TextTransaltorResponse {
-    pub fn new(text: String) -> Self {
-        Self { text }
+impl From<Translation> for TextTransaltorResponse {
+    fn from(translation: Translation) -> Self {
+        Self {
+            text: translation.text().to_owned(),
+            profile: translation.profile().to_owned(),
+            template_version: translation.template_version().to_owned(),
+            glossary_violations: translation.glossary_violations().to_owned(),
+            placeholder_issues: translation.placeholder_issues().to_owned(),
+            consistency_warnings: translation.consistency_warnings().to_owned(),
+            formality_issues: translation.formality_issues().to_owned(),
+            validation_warnings: translation.validation_warnings().to_owned(),
+            back_translation: translation.back_translation().to_owned(),
+            confidence: translation.confidence().to_owned(),
+            usage: translation.usage().to_owned(),
+        }
     }
 }
 
+/// Form of the document translation request.
+#[derive(Serialize, Deserialize, Getters, ToSchema)]
+#[getset(get = "pub")]
+pub struct DocumentUpload {
+    #[schema(value_type = String, format = Binary)]
+    file: Vec<u8>,
+    #[schema(default = "en")]
+    source_language: String,
+    #[schema(default = "ru")]
+    target_language: String,
+    profile: Option<String>,
+    formality: Option<Formality>,
+    session_id: Option<String>,
+    /// Comma-separated sheet names
+    sheets: Option<String>,
+    /// Comma-separated cell ranges such as `B:B`, `A2:C40` or `Terms!B:B`
+    ranges: Option<String>,
+    output: Option<SpreadsheetOutput>,
+    /// Earlier translation of a JSON or YAML resource whose keys are kept
+    #[schema(value_type = Option<String>, format = Binary)]
+    existing_target: Option<Vec<u8>>,
+    /// Longest subtitle line in characters
+    max_line_chars: Option<usize>,
+    /// Highest subtitle reading speed in characters per second
+    max_chars_per_second: Option<f64>,
+}
+
+/// Form of the table translation request.
+#[derive(Serialize, Deserialize, Getters, ToSchema)]
+#[getset(get = "pub")]
+pub struct TableUpload {
+    #[schema(default = "en")]
+    source_language: String,
+    #[schema(default = "ru")]
+    target_language: String,
+    /// Comma-separated header names of the columns to translate
+    #[schema(default = "text")]
+    columns: String,
+    /// Name of a translation column with `{col}` and `{target}`
+    #[schema(default = "{col}_{target}")]
+    column_pattern: Option<String>,
+    /// `,`, `;`, `|` or `tab`
+    delimiter: Option<String>,
+    profile: Option<String>,
+    formality: Option<Formality>,
+    /// Table to translate; sent after the other fields
+    #[schema(value_type = String, format = Binary)]
+    file: Vec<u8>,
+}
+
 #[derive(Serialize, Deserialize, Getters, ToSchema)]
 #[getset(get = "pub")]
 pub struct ModelGardenResponse {
     #[serde(flatten)]
     model_garden: ModelGarden,
 }
+
+#[derive(Serialize, Deserialize, Getters, ToSchema)]
+#[getset(get = "pub")]
+pub struct TemplatesResponse {
+    templates: Vec<TemplateInfo>,
+}
+
+impl TemplatesResponse {
+    pub fn new(templates: Vec<TemplateInfo>) -> Self {
+        Self { templates }
+    }
+}
+
+#[derive(Serialize, Deserialize, Getters, ToSchema)]
+#[getset(get = "pub")]
+pub struct GlossaryResponse {
+    entries: Vec<GlossaryEntry>,
+}
+
+impl GlossaryResponse {
+    pub fn new(entries: Vec<GlossaryEntry>) -> Self {
+        Self { entries }
+    }
+}
@@ -0,0 +1,261 @@
+use std::io;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::State;
+use axum::extract::multipart::{Field, Multipart};
+use axum::http::header;
+use axum::response::IntoResponse;
+use futures::{StreamExt, stream};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::errors::ErrorResponse;
+use crate::modules::llm_client::LLMClient;
+use crate::modules::llm_client::models::TranslateTask;
+use crate::modules::pipeline::errors::PipelineResult;
+use crate::modules::tables::models::TableOptions;
+use crate::modules::tables::{ColumnMapping, TableReader};
+use crate::server::AppState;
+use crate::server::errors::{ServerError, ServerResult};
+use crate::server::router::documents::{
+    content_disposition, formality, list, multipart_error, read_text,
+};
+use crate::server::router::llm_client::check_translate_is_available;
+use crate::server::router::models::TableUpload;
+
+/// Upload chunks waiting for the table reader.
+const UPLOAD_CHUNKS: usize = 16;
+
+/// Translated table as it is being written.
+struct TableStream {
+    file_name: String,
+    content_type: &'static str,
+    chunks: mpsc::Receiver<PipelineResult<Vec<u8>>>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/translate/table",
+    request_body(content = TableUpload, content_type = "multipart/form-data"),
+    tags = ["Translator"],
+    description = r#"
+## Translate table
+
+Translate columns of an uploaded CSV or TSV table. The table is read and written row by row
+while it is uploaded, so files of any size are accepted.
+
+### Form fields
+- `source_language` (string, ISO-639): Source language of the table
+- `target_language` (string, ISO-639): Target language of the table
+- `columns` (string): Comma-separated header names of the columns to translate
+- `column_pattern` (string, optional): Name of the translation column of every translated
+  column, with `{col}` for its name and `{target}` for the target language;
+  `{col}_{target}` by default
+- `delimiter` (string, optional): `,`, `;`, `|` or `tab`. Tab for `.tsv` files and
+  `text/tab-separated-values`, comma otherwise
+- `profile` (string, optional): Domain profile
+- `formality` (string, optional): `default`, `formal` or `informal` form of address
+- `file` (file): Table to translate, with a header row. It must be the last field
+
+The first row names the columns. Every row is returned with its fields as they were, padded
+to the header, and the translations of its cells appended as new columns in the order of
+`columns`. Quoted fields, delimiters and line breaks inside them are kept; fields are quoted
+in the output where needed. Empty cells stay empty.
+
+The last column, `untranslated` (with `_` appended while the header already has that name),
+lists the comma-separated translation columns of the row whose cells kept their source text.
+
+Rows are translated in batches. Equal cells of a batch are translated once, several at a
+time, and translations are reused for equal cells of later batches.
+
+A cell whose translation is refused or rejected by validation keeps its source text in the
+translation column and is named in `untranslated`. Errors in the form, the header or the first batch are returned with their
+status. Later errors, such as a malformed row or an unavailable model, end the response early.
+"#,
+    responses(
+        (status = 200, description="### Translated table", content_type = "text/csv", body = String),
+        (status = 400, description="### Bad request, malformed table or unknown column", body = ErrorResponse),
+        (status = 404, description="### Requested profile is not defined", body = ErrorResponse),
+        (status = 422, description="### Unsupported language", body = ErrorResponse),
+        (status = 500, description="### Internal Server error", body = ErrorResponse),
+    )
+)]
+pub async fn translate_table<R>(
+    State(state): State<Arc<AppState<R>>>,
+    multipart: Multipart,
+) -> ServerResult<impl IntoResponse>
+where
+    R: LLMClient + Send + Sync + ?Sized + 'static,
+{
+    // The form is read by its own task, which keeps reading the file while
+    // the translated rows are sent back.
+    let (ready, started) = oneshot::channel();
+    tokio::spawn(async move {
+        let mut ready = Some(ready);
+        if let Err(err) = receive_table(&state, multipart, &mut ready).await
+            && let Some(ready) = ready.take()
+        {
+            let _ = ready.send(Err(err));
+        }
+    });
+    let TableStream {
+        file_name,
+        content_type,
+        mut chunks,
+    } = started
+        .await
+        .map_err(|_| ServerError::InternalError("Table upload stopped".to_string()))??;
+
+    // The first chunk holds the header and the first batch; errors up to it
+    // still get their status.
+    let first = chunks
+        .recv()
+        .await
+        .ok_or_else(|| ServerError::InternalError("Table translation stopped".to_string()))??;
+    let rest = stream::unfold(chunks, |mut chunks| async move {
+        let chunk = chunks.recv().await?.map_err(|err| {
+            tracing::error!("Table translation aborted: {err}");
+            io::Error::other(err.to_string())
+        });
+        Some((chunk, chunks))
+    });
+    let body = stream::once(async { Ok(first) }).chain(rest);
+    let headers = [
+        (
+            header::CONTENT_TYPE,
+            format!("{content_type}; charset=utf-8"),
+        ),
+        (header::CONTENT_DISPOSITION, content_disposition(&file_name)),
+    ];
+    Ok((headers, Body::from_stream(body)))
+}
+
+/// Reads the form up to the file, then starts the translation and sends its
+/// stream to `ready` while passing the rest of the file to the reader.
+async fn receive_table<R>(
+    state: &AppState<R>,
+    mut multipart: Multipart,
+    ready: &mut Option<oneshot::Sender<ServerResult<TableStream>>>,
+) -> ServerResult<()>
+where
+    R: LLMClient + Send + Sync + ?Sized + 'static,
+{
+    let mut task = TranslateTask::default();
+    let mut source_language = None;
+    let mut target_language = None;
+    let mut columns = Vec::new();
+    let mut column_pattern = None;
+    let mut delimiter = None;
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        let name = field.name().unwrap_or_default().to_owned();
+        match name.as_str() {
+            "file" => {
+                let (Some(source_language), Some(target_language)) =
+                    (source_language.take(), target_language.take())
+                else {
+                    return Err(ServerError::BadRequest(
+                        "`source_language` and `target_language` must come before `file`"
+                            .to_string(),
+                    ));
+                };
+                task.set_source_language(source_language);
+                task.set_target_language(target_language);
+                let available_languages = state.config.server().allowed_languages().to_owned();
+                if !check_translate_is_available(&task, available_languages) {
+                    return Err(ServerError::UnsupportedLanguage(
+                        "Указанный язык не поддерживается".to_string(),
+                    ));
+                }
+                state.pipeline.profiles().get(task.profile().as_deref())?;
+
+                let file_name = field.file_name().unwrap_or("table.csv").to_owned();
+                let tab_separated = file_name.to_lowercase().ends_with(".tsv")
+                    || field.content_type() == Some("text/tab-separated-values");
+                let delimiter = delimiter.unwrap_or(if tab_separated { b'\t' } else { b',' });
+                let content_type = match delimiter {
+                    b'\t' => "text/tab-separated-values",
+                    _ => "text/csv",
+                };
+                let mut options = TableOptions::new(std::mem::take(&mut columns), delimiter);
+                if let Some(column_pattern) = column_pattern.take() {
+                    options = options.with_column_pattern(column_pattern);
+                }
+
+                let config = state.config.tables().clone();
+                let pipeline = state.pipeline.clone();
+                let (upload, input) = mpsc::channel(UPLOAD_CHUNKS);
+                let start = async move {
+                    let reader = TableReader::start(input, delimiter, config.batch_rows()).await?;
+                    let mapping =
+                        ColumnMapping::new(reader.header(), &options, task.target_language())?;
+                    let (sender, chunks) = mpsc::channel(1);
+                    tokio::spawn(async move {
+                        if let Err(err) = pipeline
+                            .translate_table(task, reader, mapping, delimiter, config, &sender)
+                            .await
+                        {
+                            let _ = sender.send(Err(err)).await;
+                        }
+                    });
+                    Ok(TableStream {
+                        file_name,
+                        content_type,
+                        chunks,
+                    })
+                };
+                let started = async {
+                    let started = start.await;
+                    if let Some(ready) = ready.take() {
+                        let _ = ready.send(started);
+                    }
+                };
+                tokio::join!(pass_upload(field, upload), started);
+                return Ok(());
+            }
+            "source_language" => source_language = Some(read_text(field).await?),
+            "target_language" => target_language = Some(read_text(field).await?),
+            "columns" => columns.extend(list(&read_text(field).await?)),
+            "column_pattern" => column_pattern = Some(read_text(field).await?),
+            "delimiter" => delimiter = Some(parse_delimiter(&read_text(field).await?)?),
+            "profile" => {
+                task.set_profile(Some(read_text(field).await?));
+            }
+            "formality" => {
+                task.set_formality(formality(&read_text(field).await?)?);
+            }
+            _ => tracing::debug!(field = name, "Skipping unknown form field"),
+        }
+    }
+    Err(ServerError::BadRequest(
+        "Form requires `file`, `source_language`, `target_language` and `columns` fields"
+            .to_string(),
+    ))
+}
+
+/// Passes the chunks of the file to the table reader until the upload ends,
+/// fails or the reader stops.
+async fn pass_upload(mut field: Field<'_>, upload: mpsc::Sender<io::Result<Vec<u8>>>) {
+    loop {
+        let chunk = match field.chunk().await {
+            Ok(Some(chunk)) => Ok(chunk.to_vec()),
+            Ok(None) => return,
+            Err(err) => Err(io::Error::other(err.body_text())),
+        };
+        let failed = chunk.is_err();
+        if upload.send(chunk).await.is_err() || failed {
+            return;
+        }
+    }
+}
+
+fn parse_delimiter(value: &str) -> ServerResult<u8> {
+    match value {
+        "," => Ok(b','),
+        ";" => Ok(b';'),
+        "|" => Ok(b'|'),
+        "tab" | "\\t" => Ok(b'\t'),
+        _ => Err(ServerError::BadRequest(
+            "Invalid delimiter: expected `,`, `;`, `|` or `tab`".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+
+use crate::errors::ErrorResponse;
+use crate::modules::llm_client::LLMClient;
+use crate::server::AppState;
+use crate::server::errors::ServerResult;
+use crate::server::router::models::TemplatesResponse;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/templates",
+    tags = ["Templates"],
+    description = r#"
+## Getting prompt templates
+
+Get all loaded prompt templates with their versions on disk and the version used for translation
+
+"#,
+    responses(
+        (status = 200, description="### Loaded prompt templates", body = TemplatesResponse),
+        (status = 500, description="### Internal Server error", body = ErrorResponse)
+    )
+)]
+pub async fn get_templates<R>(
+    State(state): State<Arc<AppState<R>>>,
+) -> ServerResult<impl IntoResponse>
+where
+    R: LLMClient + Send + Sync + ?Sized,
+{
+    let templates = state.pipeline.templates().list().await;
+    Ok(Json(TemplatesResponse::new(templates)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/templates/reload",
+    tags = ["Templates"],
+    description = r#"
+## Reloading prompt templates
+
+Re-read prompt templates from disk without restarting the service.
+The new set is applied only if every template is valid, otherwise the previous set stays active.
+
+"#,
+    responses(
+        (status = 200, description="### Reloaded prompt templates", body = TemplatesResponse),
+        (status = 400, description="### Invalid template on disk", body = ErrorResponse),
+        (status = 500, description="### Internal Server error", body = ErrorResponse)
+    )
+)]
+pub async fn reload_templates<R>(
+    State(state): State<Arc<AppState<R>>>,
+) -> ServerResult<impl IntoResponse>
+where
+    R: LLMClient + Send + Sync + ?Sized,
+{
+    let templates = state.pipeline.templates().reload().await?;
+    Ok(Json(TemplatesResponse::new(templates)))
+}
@@ -1,9 +1,26 @@
 use crate::errors::*;
+use crate::modules::back_translation::models::BackTranslation;
+use crate::modules::confidence::models::{Confidence, ConfidenceSpan, SegmentConfidence};
+use crate::modules::consistency::models::{ConsistencyKind, ConsistencyWarning};
+use crate::modules::context::models::{TranslatedSegment, TranslationContext};
+use crate::modules::documents::models::SpreadsheetOutput;
+use crate::modules::formality::models::{Formality, FormalityIssue};
+use crate::modules::formats::models::TextFormat;
+use crate::modules::glossary::models::{
+    GlossaryEntry, GlossaryTerm, GlossaryViolation, ImportReport,
+};
+use crate::modules::llm_client::models::TokenUsage;
+use crate::modules::masking::models::{PlaceholderIssue, PlaceholderIssueKind};
+use crate::server::router::documents::*;
+use crate::server::router::glossary::*;
 use crate::server::router::llm_client::*;
 use crate::server::router::loader::*;
 use crate::server::router::models::{
-    ModelGardenResponse, TextTransaltorRequest, TextTransaltorResponse,
+    DocumentUpload, GlossaryResponse, ModelGardenResponse, TableUpload, TemplatesResponse,
+    TextTransaltorRequest, TextTransaltorResponse,
 };
+use crate::server::router::tables::*;
+use crate::server::router::templates::*;
 use utoipa::OpenApi;
 
 #[derive(OpenApi)]
@@ -22,13 +39,44 @@ use utoipa::OpenApi;
             name = "Loader",
             description = "### Get formalized available language translation combinations",
         ),
+        (
+            name = "Glossary",
+            description = "### Approved terminology injected into prompts and checked in translations",
+        ),
+        (
+            name = "Templates",
+            description = "### Versioned prompt templates",
+        ),
     ),
 
     components(
         schemas(
             TextTransaltorRequest,
             TextTransaltorResponse,
+            DocumentUpload,
+            TableUpload,
+            SpreadsheetOutput,
+            TextFormat,
+            Formality,
+            FormalityIssue,
+            BackTranslation,
+            Confidence,
+            SegmentConfidence,
+            ConfidenceSpan,
+            TranslationContext,
+            TranslatedSegment,
             ModelGardenResponse,
+            TemplatesResponse,
+            GlossaryResponse,
+            GlossaryEntry,
+            GlossaryTerm,
+            GlossaryViolation,
+            ImportReport,
+            PlaceholderIssue,
+            PlaceholderIssueKind,
+            ConsistencyWarning,
+            ConsistencyKind,
+            TokenUsage,
             Successful,
             ErrorResponse,
         ),
@@ -36,6 +84,16 @@ use utoipa::OpenApi;
     paths(
     get_available_languages,
     translate_text,
+    translate_document,
+    translate_table,
+    list_glossary,
+    get_glossary_entry,
+    create_glossary_entry,
+    update_glossary_entry,
+    delete_glossary_entry,
+    import_glossary,
+    get_templates,
+    reload_templates,
     )
 )]
 pub(super) struct ApiDoc;